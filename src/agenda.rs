@@ -0,0 +1,90 @@
+use std::fmt::Write;
+
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+
+use crate::config::Entry;
+use crate::project::Project;
+
+pub const DEFAULT_AGENDA_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AgendaKind {
+    Due,
+    Resurfaces,
+}
+
+impl AgendaKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Due => "due",
+            Self::Resurfaces => "resurfaces",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AgendaItem<'a> {
+    /// the local day the item is listed under
+    pub day: NaiveDate,
+    pub entry: &'a Entry,
+    pub kind: AgendaKind,
+    pub at: DateTime<Utc>,
+}
+
+/// Due dates and snooze ends of the coming `days` days, in order. Overdue entries are listed under today.
+pub fn items(project: &Project, now: DateTime<Utc>, days: i64) -> Vec<AgendaItem<'_>> {
+    let today = now.with_timezone(&Local).date_naive();
+    let last_day = today + Duration::days(days - 1);
+    let due = project
+        .entries
+        .iter()
+        .chain(project.snoozed.iter())
+        .filter_map(|entry| Some((entry, AgendaKind::Due, entry.due?)));
+    let resurfacing = project
+        .snoozed
+        .iter()
+        .filter_map(|entry| Some((entry, AgendaKind::Resurfaces, entry.snoozed_until?)));
+    let mut ret = due
+        .chain(resurfacing)
+        .filter_map(|(entry, kind, at)| {
+            let day = at.with_timezone(&Local).date_naive().max(today);
+            (day <= last_day).then_some(AgendaItem {
+                day,
+                entry,
+                kind,
+                at,
+            })
+        })
+        .collect::<Vec<_>>();
+    ret.sort_by_key(|item| (item.day, item.at));
+    ret
+}
+
+/// A plaintext listing of the agenda, grouped by day.
+pub fn render(project: &Project, now: DateTime<Utc>, days: i64) -> String {
+    let items = items(project, now, days);
+    let mut ret = String::new();
+    if items.is_empty() {
+        writeln!(ret, "Nothing due or resurfacing in the next {days} days").unwrap();
+    }
+    let mut current_day = None;
+    for item in items {
+        if current_day != Some(item.day) {
+            if current_day.is_some() {
+                ret.push('\n');
+            }
+            writeln!(ret, "{}:", item.day.format("%A %Y-%m-%d")).unwrap();
+            current_day = Some(item.day);
+        }
+        writeln!(
+            ret,
+            "  - {} [{} {}] {}",
+            item.entry.name,
+            item.kind.label(),
+            item.at.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+            item.entry.link.as_str()
+        )
+        .unwrap();
+    }
+    ret
+}
@@ -1,17 +1,40 @@
 use chrono::Utc;
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
 use std::fs;
 use std::fs::create_dir_all;
-use std::io::{BufWriter, Write};
-use std::net::{IpAddr, SocketAddr, TcpStream};
-use std::path::PathBuf;
-use std::str::FromStr;
-use std::time::Duration;
-
-use crate::config::{self, FlistConfig, Lock, LockedWithoutListener};
-use crate::errors::LockedProject;
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use crate::agenda;
+use crate::bookmarks::FolderMapping;
+use crate::bundle;
+use crate::cd::{self, Shell};
+use crate::config::{self, FlistConfig, InsertSource, Lock, LockedWithoutListener};
+use crate::diff;
+use crate::digest;
+use crate::errors::{FlistError, LockedProject};
+use crate::export;
+use crate::hooks;
+use crate::journal;
+use crate::link::Link;
+use crate::list::{self, ListFormat};
+use crate::net::Net;
 use crate::project::Project;
-use crate::requests::InsertRequest;
+use crate::query::{self, Query, CONFIRM_OPEN_ABOVE};
+use crate::relink;
+use crate::report::{self, ReportFormat, ReportGroup};
+use crate::requests::{
+    self, ArchiveRequest, EditLinkRequest, InsertRequest, RemoveRequest, RenameRequest,
+};
+use crate::secrets;
+use crate::shell;
+use crate::tls::Peer;
+use crate::trace::{self, Tracer};
+use crate::webhooks;
 
 const SECS_OF_GRACE_FOR_NONLISTENING_LOCK: u64 = 60;
 const LOCK_CONNECTION_TIMEOUT_MS: u64 = 250;
@@ -32,32 +55,72 @@ pub struct MainArgs {
     /// exit after completing the command
     #[arg(short, long)]
     pub exit: bool,
+    /// use a linear, border-free layout that announces state changes, for use with screen readers
+    #[arg(long)]
+    pub accessible: bool,
+    /// other projects to view alongside this one, switched between with Tab
+    #[arg(long = "also", value_name = "DIR")]
+    pub also: Vec<PathBuf>,
+    /// print what the command would change instead of writing it
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+    /// log every remote request, received or sent, to flist.log in the project, for debugging integrations
+    #[arg(long, global = true)]
+    pub trace_protocol: bool,
 }
 
 impl MainArgs {
-    pub fn on_locked(self, stream: TcpStream) {
-        self.command.unwrap_or_default().on_locked(stream)
+    pub fn on_locked(self, peer: Peer) -> Result<(), FlistError> {
+        let tracer = Tracer::new(&self.project_root, self.trace_protocol);
+        // the running flist holds the lock, reading the config doesn't need it
+        let net = read_config(&self.project_root, true)
+            .map(|config| config.net())
+            .unwrap_or_default();
+        self.command
+            .unwrap_or_default()
+            .on_locked(peer, &tracer, &net)
+    }
+
+    /// read-only commands neither respect nor take the project's lock, neither do dry runs as they write nothing
+    pub fn is_read_only(&self) -> bool {
+        self.dry_run || self.command.as_ref().is_some_and(Command::is_read_only)
     }
 
-    pub fn get_config(&self) -> Result<FlistConfig, LockedProject> {
+    pub fn is_fsck(&self) -> bool {
+        matches!(self.command, Some(Command::Fsck))
+    }
+
+    pub fn get_config(&self) -> Result<FlistConfig, FlistError> {
         match self.command.as_ref() {
             Some(Command::New(new_args)) => {
                 let config_path = self.project_root.join("flist.toml");
                 let files_to_delete = if !self.project_root.exists() {
-                    create_dir_all(&self.project_root).expect("Failed to create project directory");
+                    if self.dry_run {
+                        println!("Would create {}", self.project_root.display());
+                    } else {
+                        create_dir_all(&self.project_root)
+                            .map_err(FlistError::io("create", &self.project_root))?;
+                    }
                     vec![]
                 } else if !self.project_root.is_dir() {
-                    panic!("Project root is not a directory");
+                    return Err(FlistError::Command(format!(
+                        "{} is not a directory",
+                        self.project_root.display()
+                    )));
                 } else {
                     if !new_args.force {
                         // dir already existed and we can't overwrite an existing toml, we need to check if the plint project exists
                         if config_path.exists() {
-                            panic!("Project already exists, to overwrite use --force");
+                            return Err(FlistError::Command(
+                                "Project already exists, to overwrite use --force".to_string(),
+                            ));
                         }
                     }
 
                     let mut files_to_delete = vec![];
-                    for delete_candidate in ["flist.lock", "entries.json", "archive.json"] {
+                    for delete_candidate in
+                        ["flist.lock", "entries.json", "archive.json", "snoozed.json"]
+                    {
                         let delete_candidate = self.project_root.join(delete_candidate);
                         if delete_candidate.exists() {
                             files_to_delete.push(delete_candidate);
@@ -77,69 +140,71 @@ impl MainArgs {
                     new_args.max_archive.unwrap_or(config::DEFAULT_MAX_ARCHIVE),
                     quick_launch,
                 );
+                let config_text = toml::to_string(&config).expect("Failed to serialize config");
+                if self.dry_run {
+                    println!("Would write {}:\n{config_text}", config_path.display());
+                    if new_args.clear {
+                        for file in files_to_delete {
+                            println!("Would delete {}", file.display());
+                        }
+                    }
+                    return Ok(config);
+                }
 
-                fs::write(
-                    config_path,
-                    toml::to_string(&config).expect("Failed to serialize config"),
-                )
-                .expect("failed to write config file");
+                fs::write(&config_path, config_text)
+                    .map_err(FlistError::io("write", &config_path))?;
 
                 if new_args.clear {
                     for file in files_to_delete {
-                        fs::remove_file(file).expect("Failed to delete file");
+                        fs::remove_file(&file).map_err(FlistError::io("delete", &file))?;
                     }
                 }
                 Ok(config)
             }
-            _ => {
-                let lock_path = self.project_root.join("flist.lock");
-                if lock_path.exists() {
-                    // file is locked, we need to read the lock file, and attempt to establish a connection.
-                    let lock: Lock = serde_json::from_str(
-                        &fs::read_to_string(&lock_path).expect("Failed to read lock file"),
-                    )
-                    .expect("failed to read lock file");
-                    match lock {
-                        Lock::WithListener(listener) => {
-                            let hostname = IpAddr::from_str(&listener.hostname)
-                                .expect("Failed to parse hostname");
-                            let stream = TcpStream::connect_timeout(
-                                &SocketAddr::from((hostname, listener.listener_port)),
-                                Duration::from_millis(LOCK_CONNECTION_TIMEOUT_MS),
-                            );
-                            if let Ok(stream) = stream {
-                                return Err(LockedProject::WithListener(stream));
-                            }
-                            // if the connection failed, the lock can be deleted
-                        }
-                        Lock::WithoutListener(LockedWithoutListener { time_locked }) => {
-                            let diff: u64 = (time_locked - Utc::now())
-                                .num_seconds()
-                                .try_into()
-                                .unwrap_or_default();
-                            if diff < SECS_OF_GRACE_FOR_NONLISTENING_LOCK {
-                                // if the lock was created less than a minute ago, we can't delete it
-                                return Err(LockedProject::WithoutListener(time_locked));
-                            }
-                        }
+            Some(Command::Unbundle(unbundle_args)) => {
+                if !unbundle_args.force && self.project_root.join("flist.toml").exists() {
+                    return Err(FlistError::Command(
+                        "Project already exists, to overwrite use --force".to_string(),
+                    ));
+                }
+                // a running flist would overwrite the unbundled files when it saves, so its lock is checked first
+                if !self.dry_run {
+                    if let Err(err @ FlistError::Locked(..)) =
+                        read_config(&self.project_root, false)
+                    {
+                        return Err(err);
                     }
-                    // if we made it this far, we can delete the lock
-                    fs::remove_file(lock_path).expect("Failed to delete lock file");
                 }
-                let config_path = self.project_root.join("flist.toml");
-                if !config_path.exists() {
-                    panic!("No flist.toml found in project directory");
+                let data = fs::read(&unbundle_args.bundle)
+                    .map_err(FlistError::io("read", &unbundle_args.bundle))?;
+                let files = bundle::unpack(&data, || bundle::read_passphrase("Passphrase"))
+                    .map_err(FlistError::Command)?;
+                if self.dry_run {
+                    for name in files.keys() {
+                        println!("Would write {}", self.project_root.join(name).display());
+                    }
+                    return toml::from_str(&files["flist.toml"]).map_err(|err| {
+                        FlistError::malformed(&unbundle_args.bundle.join("flist.toml"), err)
+                    });
                 }
-                let config = fs::read_to_string(config_path).expect("Failed to read config file");
-                Ok(toml::from_str(&config).expect("Failed to parse config file"))
+                let count = files.len();
+                bundle::write(files, &self.project_root)
+                    .map_err(FlistError::io("unbundle into", &self.project_root))?;
+                println!(
+                    "Unpacked {count} files into {}",
+                    self.project_root.display()
+                );
+                read_config(&self.project_root, self.is_read_only())
             }
+            _ => read_config(&self.project_root, self.is_read_only()),
         }
     }
 
-    pub fn apply(self, project: &mut Project) -> ArgsApplyResult {
+    pub fn apply(self, project: &mut Project) -> Result<ArgsApplyResult, FlistError> {
         let should_exit = self.exit;
-        self.command.unwrap_or_default().apply(project);
-        ArgsApplyResult { should_exit }
+        project.dry_run = self.dry_run;
+        self.command.unwrap_or_default().apply(project)?;
+        Ok(ArgsApplyResult { should_exit })
     }
 }
 
@@ -152,34 +217,631 @@ pub enum Command {
     View,
     /// adds a new entry to the project
     Add(AddArgs),
+    /// print a summary of recently added, due, and stale entries
+    Digest(DigestArgs),
+    /// print the due and snoozed entries of the coming days
+    Agenda(AgendaArgs),
+    /// export the project's entries to another format
+    Export(ExportArgs),
+    /// adds a GitHub or GitLab issue or pull request, named after its title and tagged with its repo and state
+    AddIssue(AddIssueArgs),
+    /// find moved files of broken file and directory entries, and update the entries to point to them
+    Relink,
+    /// print the path of a directory entry, for use by the function from shell-init
+    Cd(CdArgs),
+    /// print a shell function, `fcd`, that changes to a directory entry of the project
+    ShellInit(ShellInitArgs),
+    /// open the entries that match a query or an alias from the config
+    Open(OpenArgs),
+    /// archive an entry, in the running flist if the project is open
+    Archive(EntryArgs),
+    /// delete an entry without archiving it, in the running flist if the project is open
+    Remove(EntryArgs),
+    /// rename an entry, in the running flist if the project is open
+    Rename(RenameArgs),
+    /// point an entry at another link, in the running flist if the project is open
+    EditLink(EditLinkArgs),
+    /// print the entries, without opening the view, for piping into other tools
+    List(ListArgs),
+    /// print a readable report of the entries, grouped by section or tag, for status updates or printing
+    Report(ReportArgs),
+    /// print the journal of changes to the entries, latest first
+    Log(LogArgs),
+    /// undo a single change from the journal, keeping the changes made after it
+    Revert(RevertArgs),
+    /// print the entries that were added, removed, renamed, moved, or changed since another state of the project
+    Diff(DiffArgs),
+    /// check the saved entries for broken or inconsistent data, and offer to repair it
+    Fsck,
+    /// trim the archive to `max_archive` and drop old changes from the journal
+    Compact(CompactArgs),
+    /// print the end of the log written with --trace-protocol
+    TailLog(TailLogArgs),
+    /// add an entry for each line of a file or stdin, either a link or a name and a link separated by a tab, or for each
+    /// bookmark of a browser's export
+    Import(ImportArgs),
+    /// write a static html page of the entries, grouped by section or tag, to a directory
+    Publish(PublishArgs),
+    /// pack the project into a single file, optionally encrypted, to move it or send it elsewhere
+    Bundle(BundleArgs),
+    /// unpack a file made with `bundle` into the project directory, and open it
+    Unbundle(UnbundleArgs),
+    /// store or delete a secret in the system's keyring, for the values of flist.toml written as `{ secret = "<name>" }`
+    Secret(SecretArgs),
 }
 
 impl Command {
-    fn on_locked(self, stream: TcpStream) {
+    fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            Self::Digest(..)
+                | Self::Agenda(..)
+                | Self::Export(..)
+                | Self::Cd(..)
+                | Self::ShellInit(..)
+                | Self::Open(..)
+                | Self::List(..)
+                | Self::Report(..)
+                | Self::Log(..)
+                | Self::Diff(..)
+                | Self::TailLog(..)
+                | Self::Publish(..)
+                | Self::Bundle(..)
+                | Self::Secret(..)
+        )
+    }
+
+    fn on_locked(self, peer: Peer, tracer: &Tracer, net: &Net) -> Result<(), FlistError> {
+        let refuse = |message: &str| -> Result<(), FlistError> {
+            Err(FlistError::Command(message.to_string()))
+        };
         match self {
-            Self::New(..) => unreachable!(),
-            Self::View => {}
-            Self::Add(args) => {
-                let request = InsertRequest::from(args);
-                let mut stream = BufWriter::new(stream);
-                serde_json::to_writer(&mut stream, &request).expect("Failed to serialize request");
-                stream.flush().expect("Failed to send request");
+            Self::New(..)
+            | Self::Digest(..)
+            | Self::Agenda(..)
+            | Self::Export(..)
+            | Self::Cd(..)
+            | Self::ShellInit(..)
+            | Self::Open(..)
+            | Self::List(..)
+            | Self::Report(..)
+            | Self::Log(..)
+            | Self::Diff(..)
+            | Self::TailLog(..)
+            | Self::Publish(..)
+            | Self::Bundle(..)
+            | Self::Secret(..) => {
+                unreachable!()
+            }
+            Self::Unbundle(..) => refuse("Project is open, close it before unbundling into it"),
+            Self::View => Ok(()),
+            Self::Relink => refuse("Project is open, close it before relinking"),
+            Self::Fsck => refuse("Project is open, close it before checking it"),
+            Self::Compact(..) => refuse("Project is open, close it before compacting it"),
+            Self::Revert(..) => refuse("Project is open, revert from its history (H) instead"),
+            Self::Add(args) => send_insert(peer, InsertRequest::try_from(args)?, tracer),
+            Self::AddIssue(args) => send_insert(peer, InsertRequest::issue(args, net)?, tracer),
+            Self::Import(args) => {
+                let requests = Vec::<InsertRequest>::try_from(args)?;
+                let count = requests.len();
+                send_request(peer, "insert-many", 0, &requests, tracer)?;
+                println!("Sent {count} entries to the running flist");
+                Ok(())
+            }
+            Self::Archive(args) => {
+                let request = ArchiveRequest {
+                    archive: args.entry,
+                };
+                send_request(peer, "archive", 0, &request, tracer)?;
+                println!(
+                    "Sent {} to be archived by the running flist",
+                    request.archive
+                );
+                Ok(())
+            }
+            Self::Remove(args) => {
+                let request = RemoveRequest { remove: args.entry };
+                send_request(peer, "remove", 0, &request, tracer)?;
+                println!("Sent {} to be removed by the running flist", request.remove);
+                Ok(())
+            }
+            Self::Rename(args) => {
+                let request = RenameRequest::from(args);
+                send_request(peer, "rename", request.protocol(), &request, tracer)?;
+                println!("Sent {} to be renamed by the running flist", request.rename);
+                Ok(())
+            }
+            Self::EditLink(args) => {
+                let request = EditLinkRequest::from(args);
+                send_request(peer, "edit-link", request.protocol(), &request, tracer)?;
+                println!(
+                    "Sent {} to be relinked by the running flist",
+                    request.edit_link
+                );
+                Ok(())
             }
         }
     }
 
-    fn apply(self, project: &mut Project) {
+    fn apply(self, project: &mut Project) -> Result<(), FlistError> {
         match self {
-            Self::New(..) | Self::View => {}
-            Self::Add(args) => {
-                let request = InsertRequest::from(args).into();
-                project.insert_entry(request);
-                project.save();
+            Self::New(..) | Self::Unbundle(..) | Self::View => {}
+            Self::Fsck => unreachable!("fsck runs before the project is loaded"),
+            Self::Add(args) => insert(project, InsertRequest::try_from(args)?)?,
+            Self::AddIssue(args) => {
+                let net = project.config.net();
+                insert(project, InsertRequest::issue(args, &net)?)?
+            }
+            Self::Import(args) => {
+                let requests = Vec::<InsertRequest>::try_from(args)?;
+                let count = requests.len();
+                insert_all(project, requests)?;
+                println!("Imported {count} entries");
+            }
+            Self::Relink => {
+                let dry_run = project.dry_run;
+                print!("{}", relink::relink_all(project, dry_run));
+                project.save()?;
+            }
+            Self::Cd(args) => match cd::pick(project, args.query.as_deref()) {
+                Some(path) => println!("{path}"),
+                None => return Err(FlistError::Command("No directory entry chosen".to_string())),
+            },
+            Self::ShellInit(args) => print!("{}", cd::init_script(args.shell, &project.root)),
+            Self::Open(args) => {
+                let text = args.query.join(" ");
+                let query = Query::parse(query::expand(&project.config.aliases, &text));
+                let matching = query::matching(project, &query, args.all);
+                if matching.is_empty() {
+                    return Err(FlistError::Command(format!("No entries match {text}")));
+                }
+                if matching.len() > CONFIRM_OPEN_ABOVE && !args.yes {
+                    print!("Open {} entries? [y/N] ", matching.len());
+                    let mut answer = String::new();
+                    io::stdout()
+                        .flush()
+                        .and_then(|_| io::stdin().read_line(&mut answer))
+                        .map_err(|err| {
+                            FlistError::Command(format!("Failed to read answer: {err}"))
+                        })?;
+                    if !answer.trim().eq_ignore_ascii_case("y") {
+                        return Ok(());
+                    }
+                }
+                let ids = matching
+                    .into_iter()
+                    .map(|entry| {
+                        println!("Opening {}", entry.name);
+                        if let Err(err) = entry.link.explore() {
+                            println!("{err}, its link is {}", entry.link.as_str());
+                        }
+                        entry.id
+                    })
+                    .collect::<Vec<_>>();
+                for id in ids {
+                    project.opened(id);
+                }
+                deliver_events(project);
+            }
+            Self::Archive(args) => {
+                let id = project
+                    .find(&args.entry, false)
+                    .map_err(FlistError::Command)?;
+                let name = project
+                    .get(id)
+                    .map(|entry| entry.name.clone())
+                    .ok_or_else(|| FlistError::Command(format!("No entry {}", args.entry)))?;
+                project.complete(id);
+                project.save()?;
+                if project.dry_run {
+                    return Ok(());
+                }
+                println!("Archived {name}");
+                deliver_events(project);
+            }
+            Self::Remove(args) => {
+                let id = project
+                    .find(&args.entry, true)
+                    .map_err(FlistError::Command)?;
+                let entry = project
+                    .remove(id)
+                    .ok_or_else(|| FlistError::Command(format!("No entry {}", args.entry)))?;
+                project.save()?;
+                if !project.dry_run {
+                    println!("Removed {}", entry.name);
+                }
+            }
+            Self::Rename(args) => {
+                let request = RenameRequest::from(args);
+                let id = project
+                    .find(&request.rename, true)
+                    .map_err(FlistError::Command)?;
+                project
+                    .rename(
+                        id,
+                        request.name.clone(),
+                        request.expected.as_deref(),
+                        request.revision,
+                    )
+                    .map_err(FlistError::Command)?;
+                project.save()?;
+                if !project.dry_run {
+                    println!("Renamed {} to {}", request.rename, request.name);
+                }
+            }
+            Self::EditLink(args) => {
+                let request = EditLinkRequest::from(args);
+                let id = project
+                    .find(&request.edit_link, true)
+                    .map_err(FlistError::Command)?;
+                project
+                    .change_link(
+                        id,
+                        request.link,
+                        request.expected.as_ref(),
+                        request.revision,
+                    )
+                    .map_err(FlistError::Command)?;
+                project.save()?;
+                if !project.dry_run {
+                    println!("Changed the link of {}", request.edit_link);
+                }
+            }
+            Self::Digest(args) => {
+                let stale_days = args.stale_days.unwrap_or(project.config.digest.stale_days);
+                let digest = digest::render(project, Utc::now(), stale_days);
+                match args.command.or(project.config.digest.command.clone()) {
+                    Some(command) => {
+                        let failed = |err: io::Error| {
+                            FlistError::Command(format!(
+                                "Failed to run digest command {command}: {err}"
+                            ))
+                        };
+                        let mut child = shell::command(&command)
+                            .stdin(Stdio::piped())
+                            .spawn()
+                            .map_err(failed)?;
+                        child
+                            .stdin
+                            .take()
+                            .unwrap()
+                            .write_all(digest.as_bytes())
+                            .map_err(failed)?;
+                        child.wait().map_err(failed)?;
+                    }
+                    None => print!("{digest}"),
+                }
+            }
+            Self::Agenda(args) => {
+                let days = args.days.unwrap_or(agenda::DEFAULT_AGENDA_DAYS);
+                print!("{}", agenda::render(project, Utc::now(), days));
+            }
+            Self::List(args) => print!("{}", list::render(project, args.archive, args.format)),
+            Self::Report(args) => {
+                let report = report::render(project, Utc::now(), args.group, args.format);
+                match args.output {
+                    Some(path) => {
+                        fs::write(&path, report).map_err(FlistError::io("write", &path))?
+                    }
+                    None => print!("{report}"),
+                }
+            }
+            Self::Revert(args) => {
+                let event = journal::read(&project.root)
+                    .into_iter()
+                    .find(|event| event.id == args.event)
+                    .ok_or_else(|| {
+                        FlistError::Command(format!("No event #{} in the journal", args.event))
+                    })?;
+                if let Err(err) = project.revert(&event) {
+                    return Err(FlistError::Command(err.to_string()));
+                }
+                project.save()?;
+                println!(
+                    "Reverted #{}: {} {}",
+                    event.id,
+                    event.action(),
+                    event.entry_name()
+                );
+            }
+            Self::Compact(args) => {
+                let archived = project.archive.len();
+                project.archive.truncate(project.config.max_archive);
+                let trimmed = archived - project.archive.len();
+                project.save()?;
+                if project.dry_run {
+                    return Ok(());
+                }
+                let journal = project.root.join(journal::JOURNAL_FILE);
+                let dropped = journal::compact(&project.root, args.keep)
+                    .map_err(FlistError::io("compact", &journal))?;
+                println!("Removed {trimmed} archived entries and {dropped} journal events");
+            }
+            Self::TailLog(args) => trace::tail(&project.root, args.lines, args.follow),
+            Self::Bundle(args) => {
+                if project.dry_run {
+                    println!("Would write {}", args.out.display());
+                    return Ok(());
+                }
+                let passphrase = if args.encrypt {
+                    let passphrase =
+                        bundle::read_passphrase("Passphrase").map_err(FlistError::Command)?;
+                    let repeated = bundle::read_passphrase("Repeat passphrase")
+                        .map_err(FlistError::Command)?;
+                    if repeated != passphrase {
+                        return Err(FlistError::Command("The passphrases differ".to_string()));
+                    }
+                    Some(passphrase)
+                } else {
+                    None
+                };
+                let data = bundle::pack(&project.root, args.snapshots, passphrase.as_deref())
+                    .map_err(FlistError::Command)?;
+                fs::write(&args.out, data).map_err(FlistError::io("write", &args.out))?;
+                println!("Bundled the project into {}", args.out.display());
+            }
+            Self::Secret(args) => match args.action {
+                SecretAction::Set { name } => {
+                    if project.dry_run {
+                        println!("Would store secret {name}");
+                        return Ok(());
+                    }
+                    // piped in by scripts, typed in otherwise so that it isn't left in the shell's history
+                    let value = if io::stdin().is_terminal() {
+                        bundle::read_hidden(&format!("Value of {name}"))
+                            .map_err(FlistError::Command)?
+                    } else {
+                        let mut value = String::new();
+                        io::stdin().read_line(&mut value).map_err(|err| {
+                            FlistError::Command(format!("Failed to read stdin: {err}"))
+                        })?;
+                        value.trim_end_matches(['\r', '\n']).to_string()
+                    };
+                    secrets::set(&name, &value).map_err(FlistError::Command)?;
+                    println!("Stored secret {name}");
+                }
+                SecretAction::Delete { name } => {
+                    if project.dry_run {
+                        println!("Would delete secret {name}");
+                        return Ok(());
+                    }
+                    secrets::delete(&name).map_err(FlistError::Command)?;
+                    println!("Deleted secret {name}");
+                }
+            },
+            Self::Publish(args) => {
+                let dir = args
+                    .dir
+                    .or_else(|| {
+                        let publish = project.config.publish.as_ref()?;
+                        Some(project.root.join(&publish.dir))
+                    })
+                    .ok_or_else(|| {
+                        FlistError::Command(
+                            "No directory given, and none configured under [publish] in flist.toml"
+                                .to_string(),
+                        )
+                    })?;
+                let group = args
+                    .group
+                    .or(project.config.publish.as_ref().map(|publish| publish.group))
+                    .unwrap_or_default();
+                if project.dry_run {
+                    println!("Would write {}", dir.join("index.html").display());
+                    return Ok(());
+                }
+                let path = report::publish(project, &dir, group)
+                    .map_err(FlistError::io("publish to", &dir))?;
+                println!("Published {}", path.display());
+            }
+            Self::Diff(args) => {
+                let (lists, other) =
+                    diff::load(&project.root, &args.other).map_err(FlistError::Command)?;
+                let mut current = project.snapshots();
+                diff::restrict(&mut current, &lists);
+                print!("{}", diff::render(&other, &current));
+            }
+            Self::Log(args) => {
+                let events = journal::read(&project.root);
+                let events = events
+                    .iter()
+                    .rev()
+                    .filter(|event| args.entry.is_none_or(|id| event.entry_id == id))
+                    .take(args.count.unwrap_or(usize::MAX));
+                print!("{}", journal::render(events));
+            }
+            Self::Export(args) => {
+                let exported = match (args.format, &args.template) {
+                    (_, Some(template)) => {
+                        export::template(project, template).map_err(FlistError::Command)?
+                    }
+                    (Some(ExportFormat::Ics), None) => export::ics(project, Utc::now()),
+                    (None, None) => unreachable!("clap requires a format or a template"),
+                    (Some(ExportFormat::Symlinks), None) => {
+                        let dir = args.output.ok_or_else(|| {
+                            FlistError::Command(
+                                "The symlinks format needs an output directory".to_string(),
+                            )
+                        })?;
+                        let count = export::symlinks(project, &dir)
+                            .map_err(FlistError::io("export symlinks to", &dir))?;
+                        println!("Linked {count} entries in {}", dir.display());
+                        return Ok(());
+                    }
+                };
+                match args.output {
+                    Some(path) => {
+                        fs::write(&path, exported).map_err(FlistError::io("write", &path))?
+                    }
+                    None => print!("{exported}"),
+                }
             }
         }
+        Ok(())
     }
 }
 
+/// reads the project's config, unless it is locked by another running flist
+pub fn read_config(project_root: &Path, read_only: bool) -> Result<FlistConfig, FlistError> {
+    let lock_path = project_root.join("flist.lock");
+    if !read_only && lock_path.exists() {
+        // file is locked, we need to read the lock file, and attempt to establish a connection.
+        let lock: Lock = serde_json::from_str(
+            &fs::read_to_string(&lock_path).map_err(FlistError::io("read", &lock_path))?,
+        )
+        .map_err(|err| FlistError::malformed(&lock_path, err))?;
+        match lock {
+            Lock::WithListener(listener) => {
+                let addresses = listener
+                    .addresses()
+                    .map_err(|err| FlistError::malformed(&lock_path, err))?;
+                let stream = addresses.iter().find_map(|address| {
+                    TcpStream::connect_timeout(
+                        address,
+                        Duration::from_millis(LOCK_CONNECTION_TIMEOUT_MS),
+                    )
+                    .ok()
+                });
+                if let Some(stream) = stream {
+                    let fingerprint = listener.fingerprint;
+                    return Err(LockedProject::WithListener(Peer {
+                        stream,
+                        fingerprint,
+                    })
+                    .into());
+                }
+                // if no connection succeeded, the lock can be deleted
+            }
+            Lock::WithoutListener(LockedWithoutListener { time_locked }) => {
+                let diff: u64 = (time_locked - Utc::now())
+                    .num_seconds()
+                    .try_into()
+                    .unwrap_or_default();
+                if diff < SECS_OF_GRACE_FOR_NONLISTENING_LOCK {
+                    // if the lock was created less than a minute ago, we can't delete it
+                    return Err(LockedProject::WithoutListener(time_locked).into());
+                }
+            }
+        }
+        // if we made it this far, we can delete the lock
+        fs::remove_file(&lock_path).map_err(FlistError::io("delete", &lock_path))?;
+    }
+    let config_path = project_root.join("flist.toml");
+    if !config_path.exists() {
+        return Err(FlistError::NoProject(project_root.to_path_buf()));
+    }
+    let config = fs::read_to_string(&config_path).map_err(FlistError::io("read", &config_path))?;
+    toml::from_str(&config).map_err(|err| FlistError::malformed(&config_path, err))
+}
+
+fn send_insert(peer: Peer, request: InsertRequest, tracer: &Tracer) -> Result<(), FlistError> {
+    send_request(peer, "insert", 0, &request, tracer)
+}
+
+/// Sends the `kind` request to the running flist, refused unless it is on the `needs` protocol or later, 0 for any.
+pub fn send_request(
+    peer: Peer,
+    kind: &str,
+    needs: u32,
+    request: &impl Serialize,
+    tracer: &Tracer,
+) -> Result<(), FlistError> {
+    let unreachable =
+        |err: io::Error| FlistError::Remote(format!("Failed to reach the running flist: {err}"));
+    let addr = peer.addr().map_err(unreachable)?;
+    let peer = greet(peer, kind, needs, tracer)?;
+    let start = Instant::now();
+    let body = serde_json::to_string(request).expect("Failed to serialize request");
+    let mut stream = BufWriter::new(peer.secure().map_err(unreachable)?);
+    stream.write_all(body.as_bytes()).map_err(unreachable)?;
+    stream
+        .into_inner()
+        .map_err(|err| unreachable(err.into_error()))?
+        .finish()
+        .map_err(unreachable)?;
+    let peer = addr.to_string();
+    tracer.log(&format!(
+        "sent to {peer} in {:.1?}: {body}",
+        start.elapsed()
+    ));
+    Ok(())
+}
+
+/// Asks the running flist whether it accepts `request` as of the `needs` protocol, returns a new connection to it to send
+/// the request on.
+fn greet(peer: Peer, request: &str, needs: u32, tracer: &Tracer) -> Result<Peer, FlistError> {
+    let unreachable =
+        |err: io::Error| FlistError::Remote(format!("Failed to reach the running flist: {err}"));
+    let addr = peer.addr().map_err(unreachable)?;
+    let fingerprint = peer.fingerprint.clone();
+    match requests::hello(peer.secure().map_err(unreachable)?) {
+        Ok(Some(hello)) => {
+            tracer.log(&format!(
+                "{addr} is {} on protocol {}, accepting {}",
+                hello.project,
+                hello.protocol,
+                hello.requests.join(", ")
+            ));
+            if !hello.accepts(request) {
+                return Err(FlistError::Remote(format!(
+                    "The running flist of {} (protocol {}) does not accept {request} requests, this flist is on protocol {}, restart it with this version",
+                    hello.project,
+                    hello.protocol,
+                    requests::PROTOCOL_VERSION
+                )));
+            }
+            // older flists take the request, but ignore the parts of it that they don't know of
+            if hello.protocol < needs {
+                return Err(FlistError::Remote(format!(
+                    "The running flist of {} (protocol {}) would not carry out this {request} request as meant, it needs protocol {needs}, restart it with this version",
+                    hello.project,
+                    hello.protocol,
+                )));
+            }
+        }
+        // instances from before hello ignore it, they accept only inserts
+        _ if request == "insert" => tracer.log(&format!("{addr} did not answer hello")),
+        _ => return Err(FlistError::Remote(format!(
+            "The running flist is older than this one and does not accept {request} requests, restart it with this version"
+        ))),
+    }
+    let stream = TcpStream::connect(addr).map_err(unreachable)?;
+    Ok(Peer {
+        stream,
+        fingerprint,
+    })
+}
+
+fn insert(project: &mut Project, request: InsertRequest) -> Result<(), FlistError> {
+    insert_all(project, vec![request])
+}
+
+fn insert_all(project: &mut Project, requests: Vec<InsertRequest>) -> Result<(), FlistError> {
+    for request in requests {
+        if let Err(err) = project.capture(request.into(), InsertSource::Cli, None) {
+            return Err(FlistError::Command(err.to_string()));
+        }
+    }
+    project.save()?;
+    if project.dry_run {
+        return Ok(());
+    }
+    deliver_events(project);
+    Ok(())
+}
+
+/// runs the hooks and delivers the webhooks of the changes made by the command
+fn deliver_events(project: &mut Project) {
+    let events = project.take_events();
+    hooks::run(&project.config.hooks, &project.root, &events);
+    webhooks::deliver(
+        &project.config.webhooks,
+        &project.root,
+        &events,
+        &project.config.net(),
+    );
+}
+
 #[derive(Debug, Args)]
 pub struct NewArgs {
     /// The maximum number of archives to keep.
@@ -205,4 +867,284 @@ pub struct AddArgs {
     /// metadata to add to the entry
     #[arg(short, long)]
     pub metadata: Vec<String>,
+    /// when the entry is due, either relative (e.g. "3d") or absolute (e.g. "2023-10-01 18:30")
+    #[arg(long)]
+    pub due: Option<String>,
+    /// the section to add the entry to
+    #[arg(short, long)]
+    pub section: Option<String>,
+    /// tags to add to the entry
+    #[arg(short, long = "tag")]
+    pub tags: Vec<String>,
+    /// keep the entry only until the view is closed, it is never saved
+    #[arg(long)]
+    pub temp: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct AddIssueArgs {
+    /// the url of the issue or pull request
+    pub url: String,
+    /// the section to add the entry to
+    #[arg(short, long)]
+    pub section: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct CdArgs {
+    /// part of the entry's name, all directory entries are offered if omitted
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct OpenArgs {
+    /// an alias, or a query of `tag:<tag>`, `section:<section>`, `id:<id>`, and parts of the name
+    #[arg(required = true)]
+    pub query: Vec<String>,
+    /// open the matching snoozed and archived entries too
+    #[arg(long)]
+    pub all: bool,
+    /// open the entries without asking, however many match
+    #[arg(long)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct EntryArgs {
+    /// the name of the entry, or its index in the main list
+    pub entry: String,
+}
+
+#[derive(Debug, Args)]
+pub struct RenameArgs {
+    /// the name of the entry, or its index in the main list
+    pub entry: String,
+    /// the new name
+    pub name: String,
+    /// refuse the rename if the entry is no longer named this, e.g. when it was renamed since it was listed
+    #[arg(long)]
+    pub expect: Option<String>,
+    /// refuse the rename if the entry is past this revision, i.e. when it changed in any way since it was listed
+    #[arg(long)]
+    pub revision: Option<u64>,
+}
+
+impl From<RenameArgs> for RenameRequest {
+    fn from(args: RenameArgs) -> Self {
+        Self {
+            rename: args.entry,
+            name: args.name,
+            expected: args.expect,
+            revision: args.revision,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct EditLinkArgs {
+    /// the name of the entry, or its index in the main list
+    pub entry: String,
+    /// the new link, a path or a url
+    pub link: String,
+    /// refuse the change if the entry no longer links to this, e.g. when it was relinked since it was listed
+    #[arg(long)]
+    pub expect: Option<String>,
+    /// refuse the change if the entry is past this revision, i.e. when it changed in any way since it was listed
+    #[arg(long)]
+    pub revision: Option<u64>,
+}
+
+impl From<EditLinkArgs> for EditLinkRequest {
+    fn from(args: EditLinkArgs) -> Self {
+        Self {
+            edit_link: args.entry,
+            link: args.link.as_str().into(),
+            expected: args.expect.as_deref().map(Link::from),
+            revision: args.revision,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ShellInitArgs {
+    #[arg(value_enum)]
+    pub shell: Shell,
+}
+
+#[derive(Debug, Args)]
+pub struct DigestArgs {
+    /// a shell command to pipe the digest into, instead of printing it
+    #[arg(short, long)]
+    pub command: Option<String>,
+    /// entries added more than this many days ago are considered stale
+    #[arg(short, long)]
+    pub stale_days: Option<i64>,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    #[arg(short, long, value_enum, required_unless_present = "template")]
+    pub format: Option<ExportFormat>,
+    /// a line to write per entry, e.g. '{{name}}\t{{link}}\t{{tags}}'. The fields are id, name, link, added, due,
+    /// section, tags, metadata, and starred
+    #[arg(short, long, conflicts_with = "format")]
+    pub template: Option<String>,
+    /// the file to write to, instead of printing. For the symlinks format, the directory to fill
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    /// an iCalendar file of the entries with due dates
+    Ics,
+    /// a directory of numbered symlinks to the entries
+    Symlinks,
+}
+
+#[derive(Debug, Args)]
+pub struct AgendaArgs {
+    /// how many days ahead to list, including today
+    #[arg(short, long)]
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    #[arg(short, long, value_enum, default_value_t = ListFormat::Plain)]
+    pub format: ListFormat,
+    /// list the archived entries too, after the main ones
+    #[arg(short, long)]
+    pub archive: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    #[arg(short, long, value_enum, default_value_t = ReportGroup::Section)]
+    pub group: ReportGroup,
+    #[arg(short, long, value_enum, default_value_t = ReportFormat::Text)]
+    pub format: ReportFormat,
+    /// the file to write to, instead of printing
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct BundleArgs {
+    /// the file to write, e.g. list.flz
+    #[arg(short, long)]
+    pub out: PathBuf,
+    /// encrypt the bundle with a passphrase, read from FLIST_PASSPHRASE if it is set
+    #[arg(long)]
+    pub encrypt: bool,
+    /// include the saved snapshots of the entries
+    #[arg(long)]
+    pub snapshots: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct SecretArgs {
+    #[command(subcommand)]
+    pub action: SecretAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SecretAction {
+    /// store a secret, typed in or read from stdin, replacing any stored under the same name
+    Set { name: String },
+    /// delete a stored secret
+    Delete { name: String },
+}
+
+#[derive(Debug, Args)]
+pub struct UnbundleArgs {
+    /// the file made with `bundle`
+    pub bundle: PathBuf,
+    /// overwrite the project if the directory already has one
+    #[arg(short, long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct PublishArgs {
+    /// the directory to write index.html to, the one configured under [publish] if omitted
+    pub dir: Option<PathBuf>,
+    /// how to group the entries, as configured under [publish] if omitted
+    #[arg(short, long, value_enum)]
+    pub group: Option<ReportGroup>,
+}
+
+#[derive(Debug, Args)]
+pub struct RevertArgs {
+    /// the id of the event, as listed by `flist log`
+    pub event: u64,
+}
+
+#[derive(Debug, Args)]
+pub struct TailLogArgs {
+    /// how many of the last lines to print
+    #[arg(short = 'n', long, default_value_t = 20)]
+    pub lines: usize,
+    /// keep printing lines as they are logged
+    #[arg(short, long)]
+    pub follow: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct ImportArgs {
+    #[command(subcommand)]
+    pub source: Option<ImportSource>,
+    /// the file to read, stdin if omitted or -
+    pub file: Option<PathBuf>,
+    /// the section to add the entries to
+    #[arg(short, long)]
+    pub section: Option<String>,
+    /// tags to add to every entry
+    #[arg(short, long = "tag")]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ImportSource {
+    /// add the bookmarks of an html file exported from a browser
+    Bookmarks(BookmarksArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct BookmarksArgs {
+    /// the exported bookmarks file
+    pub file: PathBuf,
+    /// what the folders of the bookmarks become on their entries
+    #[arg(short, long, value_enum, default_value_t = FolderMapping::Tags)]
+    pub folders: FolderMapping,
+    /// the section to add the entries to
+    #[arg(short, long)]
+    pub section: Option<String>,
+    /// tags to add to every entry
+    #[arg(short, long = "tag")]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct CompactArgs {
+    /// how many of the latest journal events to keep
+    #[arg(short, long, default_value_t = 1000)]
+    pub keep: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// a copy of a saved list like `entries.json`, a copy of the project's directory, or a git revision
+    pub other: String,
+}
+
+#[derive(Debug, Args)]
+pub struct LogArgs {
+    /// only list changes to the entry with this id
+    #[arg(short, long)]
+    pub entry: Option<u64>,
+    /// how many changes to list
+    #[arg(short = 'n', long)]
+    pub count: Option<usize>,
 }
@@ -0,0 +1,134 @@
+use clap::ValueEnum;
+use scraper::{ElementRef, Html, Selector};
+
+// bookmarklets and browser-internal places like firefox's `place:` queries aren't links that can be opened
+const IMPORTED_SCHEMES: &[&str] = &["http://", "https://", "ftp://", "file://"];
+
+/// what the folders a bookmark was in become on its entry
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum FolderMapping {
+    /// a tag per folder
+    Tags,
+    /// a metadata line with the folder's path
+    Metadata,
+    /// nothing, the folders are dropped
+    None,
+}
+
+pub struct Bookmark {
+    pub name: String,
+    pub url: String,
+    /// the folders the bookmark is in, outermost first
+    pub folders: Vec<String>,
+}
+
+/// The bookmarks of a browser's export in the netscape bookmark format, which every major browser writes, in the
+/// order they appear in.
+pub fn parse(html: &str) -> Vec<Bookmark> {
+    let document = Html::parse_document(html);
+    document
+        .select(&Selector::parse("a[href]").unwrap())
+        .filter_map(|link| {
+            let url = link.value().attr("href")?.trim();
+            if !IMPORTED_SCHEMES
+                .iter()
+                .any(|scheme| url.starts_with(scheme))
+            {
+                return None;
+            }
+            let name = link.text().collect::<Vec<_>>().join(" ");
+            let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+            Some(Bookmark {
+                name: if name.is_empty() {
+                    url.to_string()
+                } else {
+                    name
+                },
+                url: url.to_string(),
+                folders: folders(link),
+            })
+        })
+        .collect()
+}
+
+// a folder is a list that follows its heading, the folders are the lists the link is nested in
+fn folders(link: ElementRef) -> Vec<String> {
+    let mut ret = link
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .filter(|element| element.value().name() == "dl")
+        .filter_map(|list| {
+            let heading = list.prev_siblings().find_map(ElementRef::wrap)?;
+            (heading.value().name() == "h3").then(|| heading.text().collect::<String>())
+        })
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+    ret.reverse();
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // trimmed from a firefox export, chrome and safari write the same structure
+    const EXPORT: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<!-- This is an automatically generated file.
+     It will be read and overwritten.
+     DO NOT EDIT! -->
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks Menu</H1>
+
+<DL><p>
+    <DT><A HREF="https://www.rust-lang.org/" ADD_DATE="1700000000">Rust</A>
+    <DT><A HREF="place:type=6&sort=14&maxResults=10" ADD_DATE="1700000000">Recent Tags</A>
+    <DT><H3 ADD_DATE="1700000000" LAST_MODIFIED="1700000000">Reading</H3>
+    <DL><p>
+        <DT><A HREF="https://example.com/article">An
+            article</A>
+        <DT><A HREF="javascript:void(document.body.style.background='red')">Bookmarklet</A>
+        <DT><H3>Later</H3>
+        <DL><p>
+            <DT><A HREF="ftp://files.example.com/paper.pdf"></A>
+            <DD>a description, which isn't part of the name
+        </DL><p>
+        <DT><A HREF="file:///home/user/notes.txt">Notes</A>
+    </DL><p>
+    <DT><A HREF="http://example.org">Top again</A>
+</DL>
+"#;
+
+    #[test]
+    fn parses_nested_folders_and_skips_other_schemes() {
+        let bookmarks = parse(EXPORT)
+            .into_iter()
+            .map(|bookmark| (bookmark.name, bookmark.url, bookmark.folders))
+            .collect::<Vec<_>>();
+        let expected = [
+            ("Rust", "https://www.rust-lang.org/", &[][..]),
+            (
+                "An article",
+                "https://example.com/article",
+                &["Reading"][..],
+            ),
+            (
+                // a bookmark without a name is named by its url
+                "ftp://files.example.com/paper.pdf",
+                "ftp://files.example.com/paper.pdf",
+                &["Reading", "Later"][..],
+            ),
+            ("Notes", "file:///home/user/notes.txt", &["Reading"][..]),
+            ("Top again", "http://example.org", &[][..]),
+        ]
+        .map(|(name, url, folders)| {
+            (
+                name.to_string(),
+                url.to_string(),
+                folders.iter().map(|folder| folder.to_string()).collect(),
+            )
+        });
+        assert_eq!(bookmarks, expected);
+    }
+}
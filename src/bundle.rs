@@ -0,0 +1,321 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Entry;
+use crate::journal::{List, JOURNAL_FILE};
+use crate::project::IDS_FILE;
+use crate::snapshot::SNAPSHOT_DIR;
+
+// encrypted bundles start with this, followed by the salt of the key, the nonce, and the sealed bundle
+const ENCRYPTED_MAGIC: &[u8] = b"FLZ-ENCRYPTED-1\n";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+// the files of a project that are bundled, besides the lists and the snapshots
+const PROJECT_FILES: [&str; 4] = ["flist.toml", "history.json", IDS_FILE, JOURNAL_FILE];
+const LISTS: [List; 3] = [List::Entries, List::Archive, List::Snoozed];
+
+/// the files of a project by their path relative to it
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Bundle {
+    files: BTreeMap<String, String>,
+}
+
+/// Packs the project's config, lists, history, journal, and, if `snapshots` is set, its snapshots into a single file,
+/// encrypted with a key derived from `passphrase` if there is one. Snapshot paths are stored relative to the project,
+/// so they point into it wherever it is unbundled.
+pub fn pack(root: &Path, snapshots: bool, passphrase: Option<&str>) -> Result<Vec<u8>, String> {
+    let mut bundle = Bundle::default();
+    for name in PROJECT_FILES {
+        if let Ok(text) = fs::read_to_string(root.join(name)) {
+            bundle.files.insert(name.to_string(), text);
+        }
+    }
+    let snapshot_dir = root
+        .join(SNAPSHOT_DIR)
+        .canonicalize()
+        .unwrap_or_else(|_| root.join(SNAPSHOT_DIR));
+    for list in LISTS {
+        let Ok(text) = fs::read_to_string(root.join(list.file_name())) else {
+            continue;
+        };
+        let mut entries = serde_json::from_str::<Vec<Entry>>(&text)
+            .map_err(|err| format!("Failed to read {}: {err}", list.file_name()))?;
+        for entry in &mut entries {
+            entry.snapshot = entry.snapshot.take().filter(|_| snapshots).map(|path| {
+                // snapshots taken before they were kept relative to the project have its absolute path
+                match Path::new(&path).strip_prefix(&snapshot_dir) {
+                    Ok(file) => Path::new(SNAPSHOT_DIR).join(file).display().to_string(),
+                    // snapshots outside the project are left where they are
+                    Err(_) => path,
+                }
+            });
+        }
+        bundle.files.insert(
+            list.file_name().to_string(),
+            serde_json::to_string(&entries).expect("Failed to serialize entries"),
+        );
+    }
+    if snapshots {
+        for file in fs::read_dir(&snapshot_dir).into_iter().flatten().flatten() {
+            if let Ok(text) = fs::read_to_string(file.path()) {
+                let name = Path::new(SNAPSHOT_DIR).join(file.file_name());
+                bundle.files.insert(name.display().to_string(), text);
+            }
+        }
+    }
+    let plain = serde_json::to_vec(&bundle).expect("Failed to serialize bundle");
+    match passphrase {
+        Some(passphrase) => encrypt(&plain, passphrase),
+        None => Ok(plain),
+    }
+}
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(ENCRYPTED_MAGIC)
+}
+
+/// The files of the bundle by their path relative to the project, `passphrase` is asked for if it is encrypted.
+pub fn unpack(
+    data: &[u8],
+    passphrase: impl FnOnce() -> Result<String, String>,
+) -> Result<BTreeMap<String, String>, String> {
+    let plain = if is_encrypted(data) {
+        decrypt(data, &passphrase()?)?
+    } else {
+        data.to_vec()
+    };
+    let bundle = serde_json::from_slice::<Bundle>(&plain)
+        .map_err(|err| format!("Not a flist bundle: {err}"))?;
+    if !bundle.files.contains_key("flist.toml") {
+        return Err("The bundle has no flist.toml".to_string());
+    }
+    Ok(bundle.files)
+}
+
+/// Writes the unpacked files into `dir`.
+pub fn write(files: BTreeMap<String, String>, dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let dir = dir.canonicalize()?;
+    for (name, text) in files {
+        let path = dir.join(&name);
+        // bundles come from elsewhere, they may only write inside the project
+        if Path::new(&name).is_absolute() || name.split(['/', '\\']).any(|part| part == "..") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("The bundle has a file outside the project: {name}"),
+            ));
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, text)?;
+    }
+    Ok(())
+}
+
+fn key(passphrase: &str, salt: &[u8]) -> Result<Key, String> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| format!("Failed to derive key from passphrase: {err}"))?;
+    Ok(key)
+}
+
+fn encrypt(plain: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let mut salt = [0; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let sealed = XChaCha20Poly1305::new(&key(passphrase, &salt)?)
+        .encrypt(&nonce, plain)
+        .map_err(|_| "Failed to encrypt bundle".to_string())?;
+    let mut ret = ENCRYPTED_MAGIC.to_vec();
+    ret.extend_from_slice(&salt);
+    ret.extend_from_slice(&nonce);
+    ret.extend_from_slice(&sealed);
+    Ok(ret)
+}
+
+fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let data = &data[ENCRYPTED_MAGIC.len()..];
+    if data.len() < SALT_LEN + NONCE_LEN {
+        return Err("The bundle is truncated".to_string());
+    }
+    let (salt, data) = data.split_at(SALT_LEN);
+    let (nonce, sealed) = data.split_at(NONCE_LEN);
+    XChaCha20Poly1305::new(&key(passphrase, salt)?)
+        .decrypt(XNonce::from_slice(nonce), sealed)
+        .map_err(|_| "Wrong passphrase, or the bundle is corrupted".to_string())
+}
+
+/// `FLIST_PASSPHRASE` if it is set, otherwise the passphrase typed in without echoing it.
+pub fn read_passphrase(prompt: &str) -> Result<String, String> {
+    if let Ok(passphrase) = env::var("FLIST_PASSPHRASE") {
+        return Ok(passphrase);
+    }
+    read_hidden(prompt)
+}
+
+/// a line typed in without echoing it, an error if it is cancelled with Esc or Ctrl+C
+pub fn read_hidden(prompt: &str) -> Result<String, String> {
+    eprint!("{prompt}: ");
+    let _ = io::stderr().flush();
+    enable_raw_mode().map_err(|err| format!("Failed to read {prompt}: {err}"))?;
+    let mut passphrase = String::new();
+    let cancelled = loop {
+        let Ok(Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        })) = event::read()
+        else {
+            continue;
+        };
+        match code {
+            KeyCode::Enter => break false,
+            KeyCode::Esc => break true,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => break true,
+            KeyCode::Backspace => {
+                passphrase.pop();
+            }
+            KeyCode::Char(c) => passphrase.push(c),
+            _ => {}
+        }
+    };
+    let restored = disable_raw_mode();
+    eprintln!();
+    if cancelled {
+        return Err("Cancelled".to_string());
+    }
+    restored.map_err(|err| format!("Failed to read {prompt}: {err}"))?;
+    Ok(passphrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// an empty directory of its own for each test, removed when dropped
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new(name: &str) -> Self {
+            let dir = env::temp_dir().join(format!("flist-bundle-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn files(files: &[(&str, &str)]) -> BTreeMap<String, String> {
+        files
+            .iter()
+            .map(|(name, text)| (name.to_string(), text.to_string()))
+            .collect()
+    }
+
+    fn project(name: &str) -> Scratch {
+        let scratch = Scratch::new(name);
+        fs::write(scratch.0.join("flist.toml"), "name = \"test\"\n").unwrap();
+        fs::write(scratch.0.join("history.json"), "[]").unwrap();
+        scratch
+    }
+
+    #[test]
+    fn write_keeps_files_inside_the_project() {
+        let scratch = Scratch::new("write");
+        let dir = scratch.0.join("project");
+        write(
+            files(&[("flist.toml", "a"), ("snapshots/page.html", "b")]),
+            &dir,
+        )
+        .unwrap();
+        assert_eq!(fs::read_to_string(dir.join("flist.toml")).unwrap(), "a");
+        assert_eq!(
+            fs::read_to_string(dir.join("snapshots/page.html")).unwrap(),
+            "b"
+        );
+    }
+
+    #[test]
+    fn write_rejects_paths_out_of_the_project() {
+        let scratch = Scratch::new("traversal");
+        let dir = scratch.0.join("project");
+        for name in ["../outside", "snapshots/../../outside", "..\\outside"] {
+            let err = write(files(&[(name, "x")]), &dir).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{name}");
+        }
+        let absolute = scratch.0.join("absolute").display().to_string();
+        assert!(write(files(&[(&absolute, "x")]), &dir).is_err());
+        assert!(!scratch.0.join("outside").exists());
+        assert!(!scratch.0.join("absolute").exists());
+    }
+
+    #[test]
+    fn plain_round_trip() {
+        let project = project("plain");
+        let data = pack(&project.0, false, None).unwrap();
+        assert!(!is_encrypted(&data));
+        let unpacked = unpack(&data, || panic!("a plain bundle needs no passphrase")).unwrap();
+        assert_eq!(
+            unpacked,
+            files(&[("flist.toml", "name = \"test\"\n"), ("history.json", "[]")])
+        );
+    }
+
+    #[test]
+    fn encrypted_round_trip() {
+        let project = project("encrypted");
+        let data = pack(&project.0, false, Some("hunter2")).unwrap();
+        assert!(is_encrypted(&data));
+        let unpacked = unpack(&data, || Ok("hunter2".to_string())).unwrap();
+        assert_eq!(unpacked["flist.toml"], "name = \"test\"\n");
+    }
+
+    #[test]
+    fn wrong_passphrase() {
+        let project = project("wrong-passphrase");
+        let data = pack(&project.0, false, Some("hunter2")).unwrap();
+        assert_eq!(
+            unpack(&data, || Ok("hunter3".to_string())).unwrap_err(),
+            "Wrong passphrase, or the bundle is corrupted"
+        );
+    }
+
+    #[test]
+    fn truncated_bundle() {
+        let project = project("truncated");
+        let data = pack(&project.0, false, Some("hunter2")).unwrap();
+        // cut within the salt and nonce
+        let header = &data[..ENCRYPTED_MAGIC.len() + SALT_LEN];
+        assert_eq!(
+            unpack(header, || Ok("hunter2".to_string())).unwrap_err(),
+            "The bundle is truncated"
+        );
+        // cut within the sealed bundle, which then fails to authenticate
+        let sealed = &data[..data.len() - 1];
+        assert_eq!(
+            unpack(sealed, || Ok("hunter2".to_string())).unwrap_err(),
+            "Wrong passphrase, or the bundle is corrupted"
+        );
+    }
+}
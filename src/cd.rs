@@ -0,0 +1,68 @@
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use crate::link::Link;
+use crate::project::Project;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// The path of the directory entry whose name matches `query`. When several entries match, the user picks one
+/// through stderr and stdin, so stdout only ever holds the chosen path.
+pub fn pick(project: &Project, query: Option<&str>) -> Option<String> {
+    let query = query.unwrap_or_default().to_lowercase();
+    let matches = project
+        .entries
+        .iter()
+        .filter_map(|entry| match &entry.link {
+            Link::Directory(path) if entry.name.to_lowercase().contains(&query) => {
+                Some((entry.name.as_str(), path.as_str()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    match matches.as_slice() {
+        [] => None,
+        [(_, path)] => Some(path.to_string()),
+        _ => {
+            let mut stderr = io::stderr();
+            for (idx, (name, path)) in matches.iter().enumerate() {
+                writeln!(stderr, "{:>3}) {name} ({path})", idx + 1).ok()?;
+            }
+            write!(stderr, "Directory number: ").ok()?;
+            stderr.flush().ok()?;
+            let mut choice = String::new();
+            io::stdin().lock().read_line(&mut choice).ok()?;
+            let choice = choice.trim().parse::<usize>().ok()?.checked_sub(1)?;
+            matches.get(choice).map(|(_, path)| path.to_string())
+        }
+    }
+}
+
+/// A shell function named `fcd` that jumps to a directory entry of the project at `root`.
+pub fn init_script(shell: Shell, root: &Path) -> String {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let root = quote(&root.to_string_lossy());
+    let exe = std::env::current_exe()
+        .map(|exe| quote(&exe.to_string_lossy()))
+        .unwrap_or_else(|_| "flist".to_string());
+    match shell {
+        Shell::Bash | Shell::Zsh => format!(
+            "fcd() {{\n    local dir\n    dir=\"$({exe} {root} cd \"$@\")\" && cd \"$dir\"\n}}\n"
+        ),
+        Shell::Fish => {
+            format!("function fcd\n    set -l dir ({exe} {root} cd $argv)\n    and cd $dir\nend\n")
+        }
+    }
+}
+
+/// single quotes `text`, in a way that all supported shells accept
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
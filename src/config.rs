@@ -1,7 +1,23 @@
-use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use chrono::{DateTime, Local, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::{link::Link, requests::InsertRequest};
+use crate::{
+    download::DEFAULT_DOWNLOADS_DIR,
+    events::EventKind,
+    fields::FieldValue,
+    link::{Link, PreferredFile},
+    naming::{self, NameStep},
+    net::{HttpConfig, Net, NetworkConfig},
+    report::ReportGroup,
+    requests::InsertRequest,
+    secrets::Secret,
+};
 
 pub const DEFAULT_MAX_ARCHIVE: usize = 100;
 
@@ -13,6 +29,46 @@ fn is_default_max_archive(max_archive: &usize) -> bool {
     *max_archive == DEFAULT_MAX_ARCHIVE
 }
 
+pub const DEFAULT_HISTORY_SIZE: usize = 20;
+
+fn default_history_size() -> usize {
+    DEFAULT_HISTORY_SIZE
+}
+
+fn is_default_history_size(history_size: &usize) -> bool {
+    *history_size == DEFAULT_HISTORY_SIZE
+}
+
+pub const DEFAULT_WATCH_MINUTES: u64 = 60;
+
+fn default_watch_minutes() -> u64 {
+    DEFAULT_WATCH_MINUTES
+}
+
+fn is_default_watch_minutes(watch_minutes: &u64) -> bool {
+    *watch_minutes == DEFAULT_WATCH_MINUTES
+}
+
+fn default_downloads_dir() -> String {
+    DEFAULT_DOWNLOADS_DIR.to_string()
+}
+
+fn is_default_downloads_dir(downloads_dir: &str) -> bool {
+    downloads_dir == DEFAULT_DOWNLOADS_DIR
+}
+
+fn is_default_name_steps(name_steps: &[NameStep]) -> bool {
+    *name_steps == naming::default_steps()
+}
+
+fn is_false(b: &bool) -> bool {
+    !b
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct FlistConfig {
     #[serde(
@@ -22,6 +78,109 @@ pub struct FlistConfig {
     pub max_archive: usize,
     #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
     pub preferred_suffixes: Vec<Vec<String>>,
+    #[serde(default, skip_serializing_if = "NotificationConfig::is_default")]
+    pub notifications: NotificationConfig,
+    #[serde(default, skip_serializing_if = "DigestConfig::is_default")]
+    pub digest: DigestConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<SectionConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+    /// a shell command that `e` pipes the selected entry into, e.g. `wl-copy` or a mail composer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub share_command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub providers: Vec<ProviderConfig>,
+    /// whether to estimate the reading time of url entries in the background
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub reading_time: bool,
+    /// directories to search for files that moved away from their entry's link
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relink_roots: Vec<String>,
+    /// short names for queries, e.g. `standup = "tag:standup"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+    /// how many recently opened entries to remember
+    #[serde(
+        default = "default_history_size",
+        skip_serializing_if = "is_default_history_size"
+    )]
+    pub history_size: usize,
+    #[serde(default, skip_serializing_if = "DirectoryEnter::is_default")]
+    pub enter_on_directory: DirectoryEnter,
+    #[serde(default, skip_serializing_if = "UrlEnter::is_default")]
+    pub enter_on_url: UrlEnter,
+    /// keys `F1`-`F12` or `1`-`9` that switch to a view of the entries matching an alias or a query
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub view_keys: BTreeMap<String, String>,
+    /// custom fields shown as columns of the entry list
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<String>,
+    #[serde(default, skip_serializing_if = "RulesConfig::is_default")]
+    pub rules: RulesConfig,
+    /// whether the view saves after every change, otherwise changes are saved with Ctrl+S or when quitting
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub autosave: bool,
+    #[serde(default, skip_serializing_if = "LimitsConfig::is_default")]
+    pub limits: LimitsConfig,
+    /// where the view listens for remote requests, any free port if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listener_port: Option<ListenerPort>,
+    /// whether entries added from outside the view, remotely, from the command line, or pasted, go to the inbox to be
+    /// triaged instead of straight into the list
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub inbox: bool,
+    /// where new entries land in the main list, by where they come from
+    #[serde(default, skip_serializing_if = "InsertConfig::is_default")]
+    pub insert: InsertConfig,
+    /// whether the selection in the main list jumps to entries as they are added remotely
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub follow_remote: bool,
+    /// keys of the view moved to other keys, by the name of their action, e.g. `archive = "x"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<String, String>,
+    /// whether the view runs away from the user's screen, so that Enter copies entries rather than opening them,
+    /// detected from ssh and the display when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_session: Option<bool>,
+    /// where the view keeps a published page of the entries up to date
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish: Option<PublishConfig>,
+    /// how often the view checks the pages of watched entries for changes, and feeds for new items
+    #[serde(
+        default = "default_watch_minutes",
+        skip_serializing_if = "is_default_watch_minutes"
+    )]
+    pub watch_minutes: u64,
+    /// the folder under the project that url entries are downloaded into
+    #[serde(
+        default = "default_downloads_dir",
+        skip_serializing_if = "is_default_downloads_dir"
+    )]
+    pub downloads_dir: String,
+    /// whether downloaded entries link to the downloaded file instead of the url, which is kept in their notes
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub relink_downloads: bool,
+    /// tags suggested for entries whose link matches, e.g. `code` for links to github.com
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tag_rules: Vec<TagRule>,
+    /// how an inferred name that another entry already has, like index.html or README.md, is told apart
+    #[serde(default, skip_serializing_if = "DuplicateNames::is_default")]
+    pub duplicate_names: DuplicateNames,
+    /// how pasted urls are named, each step is tried in order until one gives a name
+    #[serde(
+        default = "naming::default_steps",
+        skip_serializing_if = "is_default_name_steps"
+    )]
+    pub name_steps: Vec<NameStep>,
+    /// the proxy, headers, and cookies that requests are made with
+    #[serde(default, skip_serializing_if = "HttpConfig::is_default")]
+    pub http: HttpConfig,
+    /// whether and where flist may make requests, for every feature that does
+    #[serde(default, skip_serializing_if = "NetworkConfig::is_default")]
+    pub network: NetworkConfig,
 }
 
 impl Default for FlistConfig {
@@ -29,6 +188,39 @@ impl Default for FlistConfig {
         Self {
             max_archive: default_max_archive(),
             preferred_suffixes: Vec::new(),
+            notifications: NotificationConfig::default(),
+            digest: DigestConfig::default(),
+            sections: Vec::new(),
+            webhooks: Vec::new(),
+            hooks: HooksConfig::default(),
+            share_command: None,
+            providers: Vec::new(),
+            reading_time: false,
+            relink_roots: Vec::new(),
+            aliases: BTreeMap::new(),
+            history_size: default_history_size(),
+            enter_on_directory: DirectoryEnter::default(),
+            enter_on_url: UrlEnter::default(),
+            view_keys: BTreeMap::new(),
+            columns: Vec::new(),
+            rules: RulesConfig::default(),
+            autosave: true,
+            limits: LimitsConfig::default(),
+            listener_port: None,
+            inbox: false,
+            insert: InsertConfig::default(),
+            follow_remote: false,
+            keys: BTreeMap::new(),
+            remote_session: None,
+            publish: None,
+            watch_minutes: DEFAULT_WATCH_MINUTES,
+            downloads_dir: DEFAULT_DOWNLOADS_DIR.to_string(),
+            relink_downloads: false,
+            tag_rules: Vec::new(),
+            duplicate_names: DuplicateNames::default(),
+            name_steps: naming::default_steps(),
+            http: HttpConfig::default(),
+            network: NetworkConfig::default(),
         }
     }
 }
@@ -38,25 +230,762 @@ impl FlistConfig {
         Self {
             max_archive,
             preferred_suffixes,
+            notifications: NotificationConfig::default(),
+            digest: DigestConfig::default(),
+            sections: Vec::new(),
+            webhooks: Vec::new(),
+            hooks: HooksConfig::default(),
+            share_command: None,
+            providers: Vec::new(),
+            reading_time: false,
+            relink_roots: Vec::new(),
+            aliases: BTreeMap::new(),
+            history_size: default_history_size(),
+            enter_on_directory: DirectoryEnter::default(),
+            enter_on_url: UrlEnter::default(),
+            view_keys: BTreeMap::new(),
+            columns: Vec::new(),
+            rules: RulesConfig::default(),
+            autosave: true,
+            limits: LimitsConfig::default(),
+            listener_port: None,
+            inbox: false,
+            insert: InsertConfig::default(),
+            follow_remote: false,
+            keys: BTreeMap::new(),
+            remote_session: None,
+            publish: None,
+            watch_minutes: DEFAULT_WATCH_MINUTES,
+            downloads_dir: DEFAULT_DOWNLOADS_DIR.to_string(),
+            relink_downloads: false,
+            tag_rules: Vec::new(),
+            duplicate_names: DuplicateNames::default(),
+            name_steps: naming::default_steps(),
+            http: HttpConfig::default(),
+            network: NetworkConfig::default(),
+        }
+    }
+
+    /// the network settings, for the features that make requests
+    pub fn net(&self) -> Net {
+        Net::new(&self.network, &self.http)
+    }
+
+    pub fn section(&self, name: &str) -> Option<&SectionConfig> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// the bound view keys and their queries, number keys first and then function keys, in keyboard order
+    pub fn view_keys(&self) -> Vec<(&str, &str)> {
+        let mut ret = self
+            .view_keys
+            .iter()
+            .map(|(key, query)| (key.as_str(), query.as_str()))
+            .collect::<Vec<_>>();
+        ret.sort_by_key(|(key, _)| match key.strip_prefix('F') {
+            Some(number) => (1, number.parse().unwrap_or(u32::MAX)),
+            None => (0, key.parse().unwrap_or(u32::MAX)),
+        });
+        ret
+    }
+
+    /// the tags of the rules the entry's link matches, that the entry doesn't have yet, in the order of the rules
+    pub fn suggested_tags(&self, entry: &Entry) -> Vec<String> {
+        let mut ret = Vec::<String>::new();
+        for tag in self
+            .tag_rules
+            .iter()
+            .filter(|rule| rule.matches(&entry.link))
+            .flat_map(|rule| rule.tags.iter())
+        {
+            if !entry.tags.contains(tag) && !ret.contains(tag) {
+                ret.push(tag.clone());
+            }
+        }
+        ret
+    }
+}
+
+/// Tags to suggest for links that match all of the rule's conditions. A rule without conditions matches nothing.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TagRule {
+    /// matches urls of this host or its subdomains
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// matches files, and urls whose path, with this extension, compared ignoring case
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extension: Option<String>,
+    /// matches files and directories inside a directory of this name at any depth, compared ignoring case
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl TagRule {
+    fn matches(&self, link: &Link) -> bool {
+        if self.host.is_none() && self.extension.is_none() && self.directory.is_none() {
+            return false;
+        }
+        let (host, path) = match link {
+            Link::Url(_) | Link::Feed(_) => {
+                let Ok(url) = reqwest::Url::parse(link.feed_url().unwrap_or(link.as_str())) else {
+                    return false;
+                };
+                (
+                    url.host_str().map(str::to_lowercase),
+                    url.path().to_string(),
+                )
+            }
+            Link::File(path) | Link::Directory(path) => (None, path.clone()),
+        };
+        let path = Path::new(&path);
+        if let Some(expected) = &self.host {
+            let expected = expected.to_lowercase();
+            let Some(host) = host else {
+                return false;
+            };
+            if host != expected && !host.ends_with(&format!(".{expected}")) {
+                return false;
+            }
+        }
+        if let Some(expected) = &self.extension {
+            let expected = expected.trim_start_matches('.');
+            if !path
+                .extension()
+                .is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case(expected))
+            {
+                return false;
+            }
+        }
+        if let Some(expected) = &self.directory {
+            let is_local = matches!(link, Link::File(_) | Link::Directory(_));
+            if !is_local
+                || !path.parent().is_some_and(|parent| {
+                    parent
+                        .iter()
+                        .any(|dir| dir.to_string_lossy().eq_ignore_ascii_case(expected))
+                })
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq)]
+pub struct NotificationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// a local time range in which notifications are held back, e.g. "22:00-07:00"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<String>,
+}
+
+impl NotificationConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn is_quiet(&self, now: DateTime<Utc>) -> bool {
+        let Some((start, end)) = self.quiet_hours.as_ref().and_then(|quiet_hours| {
+            let (start, end) = quiet_hours.split_once('-')?;
+            Some((
+                NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?,
+                NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?,
+            ))
+        }) else {
+            return false;
+        };
+        let now = now.with_timezone(&Local).time();
+        if start <= end {
+            start <= now && now < end
+        } else {
+            // the range wraps around midnight
+            now >= start || now < end
+        }
+    }
+}
+
+/// constraints that new entries must satisfy, for curated lists that are shared
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct RulesConfig {
+    /// the fewest tags an entry may have
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_tags: Option<usize>,
+    /// whether entries must be in a section
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub require_section: bool,
+    /// custom fields every entry must have
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_fields: Vec<String>,
+    /// directories that file and directory links must be under, any directory if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub link_roots: Vec<String>,
+    /// whether url entries are allowed
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub allow_urls: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn is_true(b: &bool) -> bool {
+    *b
+}
+
+impl Default for RulesConfig {
+    fn default() -> Self {
+        Self {
+            min_tags: None,
+            require_section: false,
+            required_fields: Vec::new(),
+            link_roots: Vec::new(),
+            allow_urls: true,
+        }
+    }
+}
+
+impl RulesConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// a description of each rule the entry breaks
+    pub fn violations(&self, entry: &Entry) -> Vec<String> {
+        let mut ret = Vec::new();
+        if let Some(min_tags) = self.min_tags {
+            if entry.tags.len() < min_tags {
+                ret.push(format!("must have at least {min_tags} tag(s)"));
+            }
+        }
+        if self.require_section && entry.section.is_none() {
+            ret.push("must be in a section".to_string());
+        }
+        for field in &self.required_fields {
+            if entry.field(field).is_none() {
+                ret.push(format!("must have a {field} field"));
+            }
+        }
+        match &entry.link {
+            Link::Url(_) | Link::Feed(_) if !self.allow_urls => {
+                ret.push("can't be a url".to_string())
+            }
+            Link::Url(_) | Link::Feed(_) => {}
+            Link::File(path) | Link::Directory(path) => {
+                let path = Path::new(path);
+                if !self.link_roots.is_empty()
+                    && !self.link_roots.iter().any(|root| path.starts_with(root))
+                {
+                    ret.push(format!(
+                        "link must be under {}",
+                        self.link_roots.join(" or ")
+                    ));
+                }
+            }
+        }
+        ret
+    }
+}
+
+pub const DEFAULT_STALE_DAYS: i64 = 30;
+
+fn default_stale_days() -> i64 {
+    DEFAULT_STALE_DAYS
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct DigestConfig {
+    /// a shell command the digest is piped into instead of being printed, e.g. to email it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// entries older than this are listed as stale
+    #[serde(default = "default_stale_days")]
+    pub stale_days: i64,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            command: None,
+            stale_days: default_stale_days(),
+        }
+    }
+}
+
+impl DigestConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// a fixed port, or a range of ports of which the first free one is used
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListenerPort {
+    Port(u16),
+    /// the first and last port, written as e.g. "7878-7888" in flist.toml
+    Range(u16, u16),
+}
+
+impl ListenerPort {
+    /// the ports to try in order
+    pub fn ports(&self) -> RangeInclusive<u16> {
+        match self {
+            Self::Port(port) => *port..=*port,
+            Self::Range(start, end) => *start..=*end,
+        }
+    }
+
+    fn parse_range(range: &str) -> Result<Self, String> {
+        let (start, end) = range
+            .split_once('-')
+            .and_then(|(start, end)| Some((start.trim().parse().ok()?, end.trim().parse().ok()?)))
+            .ok_or_else(|| {
+                format!("listener_port {range:?} is not a port or a range like \"7878-7888\"")
+            })?;
+        if start > end {
+            return Err(format!("listener_port {range:?} ends before it starts"));
+        }
+        Ok(Self::Range(start, end))
+    }
+}
+
+impl<'de> Deserialize<'de> for ListenerPort {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Written {
+            Port(u16),
+            Range(String),
+        }
+        match Written::deserialize(deserializer)? {
+            Written::Port(port) => Ok(Self::Port(port)),
+            Written::Range(range) => Self::parse_range(&range).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl Serialize for ListenerPort {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Port(port) => port.serialize(serializer),
+            Self::Range(start, end) => format!("{start}-{end}").serialize(serializer),
+        }
+    }
+}
+
+pub const DEFAULT_WARN_ENTRIES: usize = 1000;
+pub const DEFAULT_WARN_FILE_KB: u64 = 1024;
+
+fn default_warn_entries() -> usize {
+    DEFAULT_WARN_ENTRIES
+}
+
+fn default_warn_file_kb() -> u64 {
+    DEFAULT_WARN_FILE_KB
+}
+
+/// how large the project may get before flist warns that it will slow down
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct LimitsConfig {
+    /// the most main and snoozed entries
+    #[serde(default = "default_warn_entries")]
+    pub entries: usize,
+    /// the largest size, in kilobytes, of each of the saved lists and the journal
+    #[serde(default = "default_warn_file_kb")]
+    pub file_kb: u64,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            entries: default_warn_entries(),
+            file_kb: default_warn_file_kb(),
+        }
+    }
+}
+
+impl LimitsConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// a static page of the entries, rewritten by the view whenever it saves
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PublishConfig {
+    /// the directory to write index.html to, relative to the project
+    pub dir: String,
+    #[serde(default)]
+    pub group: ReportGroup,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SectionConfig {
+    pub name: String,
+    /// the most entries the section may hold, unlimited if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<usize>,
+    #[serde(default)]
+    pub overflow: OverflowPolicy,
+}
+
+/// where a new entry lands in the main list
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum InsertPosition {
+    Top,
+    Bottom,
+    /// right after the selected entry, as its sibling and in its section, or at the top if nothing is selected
+    AfterSelection,
+    /// at the top of the inbox, to be triaged
+    Inbox,
+}
+
+/// where an entry being added comes from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InsertSource {
+    Paste,
+    /// the command line of a project that isn't open
+    Cli,
+    /// a request to the running view, including the command line of another flist
+    Remote,
+}
+
+/// Where new entries land, by where they come from, each falling back on `default`. Without either, pasted entries
+/// land after the selection and the rest at the top, or all of them in the inbox if it is enabled.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct InsertConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<InsertPosition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paste: Option<InsertPosition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cli: Option<InsertPosition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<InsertPosition>,
+}
+
+impl InsertConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn position(&self, source: InsertSource, inbox: bool) -> InsertPosition {
+        let configured = match source {
+            InsertSource::Paste => self.paste,
+            InsertSource::Cli => self.cli,
+            InsertSource::Remote => self.remote,
+        };
+        configured.or(self.default).unwrap_or(match source {
+            _ if inbox => InsertPosition::Inbox,
+            InsertSource::Paste => InsertPosition::AfterSelection,
+            InsertSource::Cli | InsertSource::Remote => InsertPosition::Top,
+        })
+    }
+}
+
+/// what Enter does on directory entries, Ctrl+Enter does the other
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DirectoryEnter {
+    #[default]
+    OpenDirectory,
+    /// open the preferred file if there is exactly one, otherwise the directory
+    PreferredFile,
+}
+
+impl DirectoryEnter {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// what Enter does on url entries, Ctrl+Enter does the other
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum UrlEnter {
+    #[default]
+    Open,
+    /// copy the url, for sessions where a browser would open out of reach, e.g. over ssh
+    Copy,
+}
+
+impl UrlEnter {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// how an inferred name that another entry already has is changed
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DuplicateNames {
+    /// followed by the parent directory of the target or the host of the url, e.g. "README.md (flist)"
+    #[default]
+    Context,
+    /// followed by a number, e.g. "README.md (2)"
+    Numbered,
+    /// left as it is
+    Keep,
+}
+
+impl DuplicateNames {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// what happens when an entry is added to a full section
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverflowPolicy {
+    /// refuse the new entry
+    #[default]
+    Block,
+    /// archive the section's oldest entry to make room
+    ArchiveOldest,
+    /// put the new entry in another section instead, e.g. `overflow = { spill = "Later" }`
+    Spill(String),
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WebhookConfig {
+    /// often carries a token, so it can be kept in the keyring
+    pub url: Secret,
+    /// the events to post, all events if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<EventKind>,
+}
+
+impl WebhookConfig {
+    pub fn wants(&self, kind: EventKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+}
+
+/// an external command that lists live entries, see `providers::fetch`
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ProviderConfig {
+    pub name: String,
+    pub command: String,
+}
+
+/// shell commands run after something happens to an entry, with the entry's fields in `FLIST_*` environment variables
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct HooksConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_add: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_open: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_archive: Option<String>,
+}
+
+impl HooksConfig {
+    pub fn is_empty(&self) -> bool {
+        self.on_add.is_none() && self.on_open.is_none() && self.on_archive.is_none()
+    }
+
+    pub fn command(&self, kind: EventKind) -> Option<&str> {
+        match kind {
+            EventKind::Added => self.on_add.as_deref(),
+            EventKind::Opened => self.on_open.as_deref(),
+            EventKind::Archived => self.on_archive.as_deref(),
+            EventKind::Due => None,
         }
     }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Entry {
+    /// unique within the project, 0 for entries that were not yet added to a project
+    #[serde(default)]
+    pub id: u64,
     pub name: String,
     pub link: Link,
     pub time_added: DateTime<Utc>,
     pub metadata: Vec<String>,
+    /// free text about the entry, which may span lines
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snoozed_until: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<DateTime<Utc>>,
+    /// whether a notification was already shown for the entry being due
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub due_reminded: bool,
+    /// ids of related entries
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub see_also: Vec<u64>,
+    /// id of the entry this one is nested under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<u64>,
+    /// whether the entry's children are hidden
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub collapsed: bool,
+    /// the name of the section the entry is in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// the estimated minutes to read the linked page, once fetched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading_minutes: Option<u32>,
+    /// path of a saved readable copy of the linked page, relative to the project unless it was taken before snapshots
+    /// were kept in it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<String>,
+    /// a hash of the content of a file target, so that relinking can tell the moved file from others of its name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_hash: Option<u64>,
+    /// path of a downloaded copy of the linked url
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download: Option<String>,
+    /// starred entries are listed in the favorites view, wherever they are
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub starred: bool,
+    /// pinned entries are shown above their unpinned siblings, whatever order the list is in
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub pinned: bool,
+    /// user-defined fields, e.g. a ticket number or an isbn
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, FieldValue>,
+    /// who added the entry, for projects on shared drives
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub added_by: Option<String>,
+    /// who last changed the entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified_by: Option<String>,
+    /// how many times the entry was changed, so that an edit can tell whether the entry changed since it was read
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub revision: u64,
+    /// whether the entry is waiting in the inbox to be kept or discarded
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub inbox: bool,
+    /// temporary entries live until the view is closed, they are never saved
+    #[serde(skip)]
+    pub ephemeral: bool,
+    /// set on url entries whose page is checked for changes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch: Option<Watch>,
+    /// when the items of a feed entry were last expanded, later items are unread
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feed_read: Option<DateTime<Utc>>,
+    /// the unread items of a feed entry, once the feed was fetched
+    #[serde(skip)]
+    pub unread: Option<usize>,
+    /// why the page of a pasted url couldn't be read for its name, until the view is closed
+    #[serde(skip)]
+    pub name_error: Option<String>,
+    /// the provider that listed the entry, provided entries are never saved
+    #[serde(skip)]
+    pub provider: Option<String>,
+}
+
+/// the content of a watched page, as hashes
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct Watch {
+    /// when the entry was last opened
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seen: Option<u64>,
+    /// when the page was last checked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest: Option<u64>,
+}
+
+impl Watch {
+    /// whether the page changed since the entry was last opened
+    pub fn changed(&self) -> bool {
+        matches!((self.seen, self.latest), (Some(seen), Some(latest)) if seen != latest)
+    }
 }
 
 impl From<InsertRequest> for Entry {
     fn from(req: InsertRequest) -> Self {
         Self {
+            id: 0,
             name: req.name,
             link: req.link,
             time_added: Utc::now(),
             metadata: req.metadata,
+            notes: String::new(),
+            snoozed_until: None,
+            due: req.due,
+            due_reminded: false,
+            see_also: Vec::new(),
+            parent: None,
+            collapsed: false,
+            section: req.section,
+            tags: req.tags,
+            reading_minutes: None,
+            snapshot: None,
+            target_hash: None,
+            download: None,
+            starred: false,
+            pinned: false,
+            fields: BTreeMap::new(),
+            added_by: None,
+            modified_by: None,
+            revision: 0,
+            inbox: false,
+            ephemeral: req.temp,
+            watch: None,
+            feed_read: None,
+            unread: None,
+            name_error: None,
+            provider: None,
+        }
+    }
+}
+
+/// the parts of the entry that an insert request carries to another flist, its notes, fields, and the rest stay behind,
+/// so entries are only ever copied over a request
+impl From<Entry> for InsertRequest {
+    fn from(entry: Entry) -> Self {
+        Self {
+            name: entry.name,
+            link: entry.link,
+            metadata: entry.metadata,
+            due: entry.due,
+            section: entry.section,
+            tags: entry.tags,
+            temp: entry.ephemeral,
+        }
+    }
+}
+
+impl Entry {
+    /// the value of the custom field, whose name is matched ignoring case
+    pub fn field(&self, name: &str) -> Option<&FieldValue> {
+        self.fields
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// the entry's download or snapshot if it has one, otherwise the preferred file of its link, snapshots are kept
+    /// relative to the `root` of the project
+    pub fn preferred_file<'a>(
+        &self,
+        root: &Path,
+        preffered_suffixes: impl IntoIterator<Item = &'a Vec<String>>,
+    ) -> io::Result<Option<PreferredFile>> {
+        match (&self.download, &self.snapshot) {
+            (Some(download), _) => Link::File(download.clone()).preferred_file(preffered_suffixes),
+            (None, Some(snapshot)) => Ok(Some(PreferredFile::new(
+                Link::File(root.join(snapshot).display().to_string()),
+                Some("html".to_string()),
+            ))),
+            (None, None) => self.link.preferred_file(preffered_suffixes),
         }
     }
 }
@@ -75,18 +1004,41 @@ impl Lock {
         })
     }
 
-    pub fn with_listener(hostname: String, listener_port: u16) -> Self {
+    /// `addresses` must not be empty
+    pub fn with_listener(addresses: &[SocketAddr], fingerprint: &str) -> Self {
         Self::WithListener(LockedWithListener {
-            hostname,
-            listener_port,
+            hostname: addresses[0].ip().to_string(),
+            listener_port: addresses[0].port(),
+            addresses: addresses.to_vec(),
+            fingerprint: Some(fingerprint.to_string()),
         })
     }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct LockedWithListener {
+    /// the first of the addresses, for versions of flist that only read one
     pub hostname: String,
     pub listener_port: u16,
+    /// every address the listener is bound to, as the loopbacks available differ between systems
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub addresses: Vec<SocketAddr>,
+    /// the sha256 of the certificate the listener serves, clients refuse any other. None from before TLS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+}
+
+impl LockedWithListener {
+    /// the addresses to try connecting to, in order
+    pub fn addresses(&self) -> Result<Vec<SocketAddr>, String> {
+        if !self.addresses.is_empty() {
+            return Ok(self.addresses.clone());
+        }
+        self.hostname
+            .parse::<IpAddr>()
+            .map(|ip| vec![SocketAddr::from((ip, self.listener_port))])
+            .map_err(|err| format!("Invalid hostname {}: {err}", self.hostname))
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
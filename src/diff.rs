@@ -0,0 +1,117 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crate::config::Entry;
+use crate::git;
+use crate::journal::{self, List, Snapshot};
+
+const LISTS: [List; 3] = [List::Entries, List::Archive, List::Snoozed];
+
+fn parse(text: &str, file_name: &str) -> Result<Vec<Entry>, String> {
+    serde_json::from_str(text).map_err(|err| format!("Failed to parse {file_name}: {err}"))
+}
+
+/// The entries of another state of the project, which is one of:
+/// * a saved list, like a copy of `entries.json`, compared against the list of the same name
+/// * a directory with the saved lists, like a copy of the project
+/// * a git revision of the repository the project is in
+///
+/// Returns the lists it has, and their entries by id.
+pub fn load(root: &Path, other: &str) -> Result<(Vec<List>, HashMap<u64, Snapshot>), String> {
+    let path = Path::new(other);
+    let mut lists = Vec::new();
+    if path.is_file() {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let list = LISTS
+            .into_iter()
+            .find(|list| list.file_name() == file_name)
+            .unwrap_or(List::Entries);
+        let text =
+            fs::read_to_string(path).map_err(|err| format!("Failed to read {other}: {err}"))?;
+        lists.push((list, parse(&text, &file_name)?));
+    } else if path.is_dir() {
+        for list in LISTS {
+            if let Ok(text) = fs::read_to_string(path.join(list.file_name())) {
+                lists.push((list, parse(&text, list.file_name())?));
+            }
+        }
+    } else {
+        for list in LISTS {
+            if let Some(text) = git::show(root, other, list.file_name()) {
+                lists.push((list, parse(&text, list.file_name())?));
+            }
+        }
+    }
+    if lists.is_empty() {
+        return Err(format!(
+            "{other} is not a saved list, a directory with saved lists, or a git revision of the project"
+        ));
+    }
+    let snapshots = journal::snapshots(
+        lists
+            .iter()
+            .map(|(list, entries)| (*list, entries.as_slice())),
+    );
+    Ok((lists.into_iter().map(|(list, _)| list).collect(), snapshots))
+}
+
+/// Leaves out the entries of other lists, so that comparing with a single list doesn't list the entries of the others
+/// as added.
+pub fn restrict(current: &mut HashMap<u64, Snapshot>, lists: &[List]) {
+    current.retain(|_, snapshot| lists.contains(&snapshot.list));
+}
+
+/// A line per entry that differs between `other` and `current`, keyed by id.
+pub fn render(other: &HashMap<u64, Snapshot>, current: &HashMap<u64, Snapshot>) -> String {
+    let ids = other
+        .keys()
+        .chain(current.keys())
+        .copied()
+        .collect::<BTreeSet<_>>();
+    let mut ret = String::new();
+    for id in ids {
+        let line = match (other.get(&id), current.get(&id)) {
+            (None, Some(now)) => format!("added {}", now.entry.name),
+            (Some(then), None) => format!("removed {}", then.entry.name),
+            (Some(then), Some(now)) => {
+                let mut changes = Vec::new();
+                if then.entry.name != now.entry.name {
+                    changes.push(format!("renamed {} to {}", then.entry.name, now.entry.name));
+                }
+                if then.list != now.list {
+                    changes.push(format!(
+                        "moved from {} to {}",
+                        then.list.file_name(),
+                        now.list.file_name()
+                    ));
+                }
+                if then.entry.section != now.entry.section {
+                    changes.push(format!(
+                        "moved from section {} to {}",
+                        then.entry.section.as_deref().unwrap_or("(none)"),
+                        now.entry.section.as_deref().unwrap_or("(none)")
+                    ));
+                }
+                let others = journal::changed_fields(&then.entry, &now.entry)
+                    .into_iter()
+                    .filter(|field| field != "name" && field != "section")
+                    .collect::<Vec<_>>();
+                if !others.is_empty() {
+                    changes.push(format!("changed {}", others.join(", ")));
+                }
+                if changes.is_empty() {
+                    continue;
+                }
+                format!("{}: {}", now.entry.name, changes.join(", "))
+            }
+            (None, None) => unreachable!(),
+        };
+        writeln!(ret, "{id}: {line}").unwrap();
+    }
+    if ret.is_empty() {
+        ret.push_str("No differences\n");
+    }
+    ret
+}
@@ -0,0 +1,94 @@
+use std::fmt::Write;
+
+use chrono::{DateTime, Duration, Local, Utc};
+
+use crate::config::Entry;
+use crate::project::Project;
+
+/// A plaintext summary of recent additions, due entries and stale entries.
+pub fn render(project: &Project, now: DateTime<Utc>, stale_days: i64) -> String {
+    let today = now.with_timezone(&Local).date_naive();
+    let yesterday = today.pred_opt().unwrap();
+    let stale_before = now - Duration::days(stale_days);
+
+    let added_yesterday = project
+        .entries
+        .iter()
+        .chain(project.archive.iter())
+        .chain(project.snoozed.iter())
+        .filter(|entry| entry.time_added.with_timezone(&Local).date_naive() == yesterday)
+        .collect::<Vec<_>>();
+    let overdue = project
+        .entries
+        .iter()
+        .filter(|entry| {
+            entry
+                .due
+                .is_some_and(|due| due.with_timezone(&Local).date_naive() < today)
+        })
+        .collect::<Vec<_>>();
+    let due_today = project
+        .entries
+        .iter()
+        .filter(|entry| {
+            entry
+                .due
+                .is_some_and(|due| due.with_timezone(&Local).date_naive() == today)
+        })
+        .collect::<Vec<_>>();
+    let stale = project
+        .entries
+        .iter()
+        .filter(|entry| entry.time_added < stale_before)
+        .collect::<Vec<_>>();
+
+    let mut ret = format!("Flist digest for {}\n", today.format("%Y-%m-%d"));
+    write_section(&mut ret, "Added yesterday", &added_yesterday, |_| None);
+    write_section(&mut ret, "Overdue", &overdue, |entry| {
+        entry
+            .due
+            .map(|due| format!("due {}", due.with_timezone(&Local).format("%Y-%m-%d")))
+    });
+    write_section(&mut ret, "Due today", &due_today, |entry| {
+        entry
+            .due
+            .map(|due| format!("due {}", due.with_timezone(&Local).format("%H:%M")))
+    });
+    write_section(
+        &mut ret,
+        &format!("Stale (added over {stale_days} days ago)"),
+        &stale,
+        |entry| {
+            Some(format!(
+                "added {}",
+                entry.time_added.with_timezone(&Local).format("%Y-%m-%d")
+            ))
+        },
+    );
+    ret
+}
+
+fn write_section(
+    out: &mut String,
+    title: &str,
+    entries: &[&Entry],
+    annotation: impl Fn(&Entry) -> Option<String>,
+) {
+    writeln!(out, "\n{title} ({}):", entries.len()).unwrap();
+    if entries.is_empty() {
+        writeln!(out, "  (none)").unwrap();
+    }
+    for entry in entries {
+        match annotation(entry) {
+            Some(annotation) => writeln!(
+                out,
+                "  - {} [{}] {}",
+                entry.name,
+                annotation,
+                entry.link.as_str()
+            ),
+            None => writeln!(out, "  - {} {}", entry.name, entry.link.as_str()),
+        }
+        .unwrap();
+    }
+}
@@ -0,0 +1,124 @@
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::net::Net;
+use crate::target::format_size;
+
+pub const DEFAULT_DOWNLOADS_DIR: &str = "downloads";
+// only connecting is bounded, large files may take long to download
+const DOWNLOAD_CONNECT_TIMEOUT: Duration = Duration::from_millis(10000);
+const DOWNLOAD_UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/117.0.0.0 Safari/537.36";
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// how far along a running download is
+#[derive(Debug, Clone)]
+pub struct Progress {
+    pub name: String,
+    pub done: u64,
+    /// None if the server didn't say how large the file is
+    pub total: Option<u64>,
+}
+
+impl Progress {
+    pub fn describe(&self) -> String {
+        match self.total {
+            Some(total) if total > 0 => format!(
+                "Downloading {}... {}% of {}",
+                self.name,
+                self.done * 100 / total,
+                format_size(total)
+            ),
+            _ => format!("Downloading {}... {}", self.name, format_size(self.done)),
+        }
+    }
+}
+
+/// Downloads the content at `url` into `dir` under the project root, reporting the bytes downloaded so far to
+/// `progress` after every chunk. Returns the absolute path of the downloaded file.
+pub fn download(
+    project_root: &Path,
+    dir: &str,
+    url: &str,
+    net: &Net,
+    mut progress: impl FnMut(u64, Option<u64>),
+) -> Result<String, String> {
+    let client = net
+        .client_builder(DOWNLOAD_CONNECT_TIMEOUT, DOWNLOAD_UA)?
+        .connect_timeout(net.timeout(DOWNLOAD_CONNECT_TIMEOUT))
+        .timeout(None)
+        .build()
+        .map_err(|err| format!("Failed to download {url}: {err}"))?;
+    let mut resp = net
+        .get(&client, url)?
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .map_err(|err| format!("Failed to download {url}: {err}"))?;
+    let total = resp.content_length();
+
+    let dir = project_root.join(dir);
+    fs::create_dir_all(&dir).map_err(|err| format!("Failed to create {}: {err}", dir.display()))?;
+    let path = free_path(&dir, &file_name(resp.url().as_str()));
+    // written aside first, so that an interrupted download doesn't look like a whole file
+    let partial = PathBuf::from(format!("{}.part", path.display()));
+    let mut file = File::create(&partial)
+        .map_err(|err| format!("Failed to create {}: {err}", partial.display()))?;
+    let mut buffer = vec![0; CHUNK_SIZE];
+    let mut done = 0;
+    loop {
+        let read = resp
+            .read(&mut buffer)
+            .map_err(|err| format!("Failed to download {url}: {err}"))?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read])
+            .map_err(|err| format!("Failed to write {}: {err}", partial.display()))?;
+        done += read as u64;
+        progress(done, total);
+    }
+    drop(file);
+    fs::rename(&partial, &path)
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    // file links must be absolute
+    let path = path
+        .canonicalize()
+        .map_err(|err| format!("Failed to resolve {}: {err}", path.display()))?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+// the last segment of the url's path, without its query
+fn file_name(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let name = path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !matches!(c, '\\' | ':' | '*' | '"' | '<' | '>' | '|'))
+        .collect::<String>();
+    // the host of a url without a path is no file name
+    if name.is_empty() || path.trim_end_matches('/').ends_with(&format!("//{name}")) {
+        "download".to_string()
+    } else {
+        name
+    }
+}
+
+/// the path in `dir` for `name`, numbered so that earlier files aren't overwritten
+pub fn free_path(dir: &Path, name: &str) -> PathBuf {
+    let path = dir.join(name);
+    if !path.exists() {
+        return path;
+    }
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{ext}")),
+        _ => (name, String::new()),
+    };
+    (1..)
+        .map(|n| dir.join(format!("{stem} ({n}){ext}")))
+        .find(|path| !path.exists())
+        .unwrap()
+}
@@ -1,8 +1,148 @@
-use std::net::TcpStream;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, Utc};
 
+use crate::tls::Peer;
+
+#[derive(Debug)]
 pub enum LockedProject {
-    WithListener(TcpStream),
+    WithListener(Peer),
     WithoutListener(DateTime<Utc>),
 }
+
+/// why flist stopped, printed instead of a panic so that the terminal is left as it was
+#[derive(Debug)]
+pub enum FlistError {
+    /// another flist holds the project's lock
+    Locked(LockedProject),
+    /// there is no flist.toml in the directory
+    NoProject(PathBuf),
+    /// a file of the project couldn't be read or written
+    Io {
+        action: &'static str,
+        path: PathBuf,
+        err: io::Error,
+    },
+    /// a file of the project isn't valid json or toml
+    Malformed { path: PathBuf, reason: String },
+    /// the terminal couldn't be set up for the view, or restored after it
+    Terminal(io::Error),
+    /// the running flist couldn't be reached, or refused the request
+    Remote(String),
+    /// the certificate that remote requests are served with couldn't be made, with why
+    Certificate(String),
+    /// the command couldn't be carried out as given, e.g. a query that matches nothing
+    Command(String),
+}
+
+impl FlistError {
+    /// for `map_err`, the failure to `action` the file at `path`
+    pub fn io(action: &'static str, path: &Path) -> impl FnOnce(io::Error) -> Self {
+        let path = path.to_path_buf();
+        move |err| Self::Io { action, path, err }
+    }
+
+    pub fn malformed(path: &Path, reason: impl Display) -> Self {
+        Self::Malformed {
+            path: path.to_path_buf(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl Display for FlistError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Locked(LockedProject::WithListener(_)) => write!(f, "Project is open"),
+            Self::Locked(LockedProject::WithoutListener(time)) => {
+                let time: DateTime<Local> = (*time).into();
+                write!(
+                    f,
+                    "Project is locked, last lock was at {}, if no flist has it open, delete its flist.lock",
+                    time.format("%Y-%m-%d %H:%M:%S")
+                )
+            }
+            Self::NoProject(root) => write!(
+                f,
+                "No flist.toml found in {}, create a project there with `flist {} new`",
+                root.display(),
+                root.display()
+            ),
+            Self::Io { action, path, err } => {
+                write!(f, "Failed to {action} {}: {err}", path.display())
+            }
+            Self::Malformed { path, reason } => write!(
+                f,
+                "{} is malformed ({reason}), fix it by hand or run `flist fsck`",
+                path.display()
+            ),
+            Self::Terminal(err) => write!(f, "Failed to set up the terminal: {err}"),
+            Self::Certificate(reason) => write!(
+                f,
+                "Failed to make a certificate for remote requests: {reason}"
+            ),
+            Self::Remote(message) | Self::Command(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<LockedProject> for FlistError {
+    fn from(locked: LockedProject) -> Self {
+        Self::Locked(locked)
+    }
+}
+
+/// an entry could not be added because its section is full and refuses overflow
+#[derive(Debug)]
+pub struct SectionFull(pub String);
+
+impl Display for SectionFull {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Section {} is full", self.0)
+    }
+}
+
+/// an entry could not be added to the project
+#[derive(Debug)]
+pub enum InsertError {
+    SectionFull(SectionFull),
+    /// the entry breaks the rules of the project's config, these are the rules it breaks
+    Invalid(Vec<String>),
+}
+
+impl From<SectionFull> for InsertError {
+    fn from(err: SectionFull) -> Self {
+        Self::SectionFull(err)
+    }
+}
+
+impl Display for InsertError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SectionFull(err) => err.fmt(f),
+            Self::Invalid(violations) => {
+                write!(f, "Entry breaks the rules: {}", violations.join("; "))
+            }
+        }
+    }
+}
+
+/// a journal event could not be reverted, because of later changes to its entry
+#[derive(Debug)]
+pub enum RevertError {
+    /// the entry was deleted since
+    Gone(u64),
+    /// the deleted entry was added back since
+    Exists(u64),
+}
+
+impl Display for RevertError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gone(id) => write!(f, "Entry {id} no longer exists"),
+            Self::Exists(id) => write!(f, "Entry {id} exists again"),
+        }
+    }
+}
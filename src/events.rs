@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::Entry;
+
+/// things that happen to entries that might interest the outside world
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EventKind {
+    Added,
+    Archived,
+    Due,
+    Opened,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProjectEvent {
+    pub kind: EventKind,
+    /// the entry as it was when the event happened
+    pub entry: Entry,
+}
@@ -0,0 +1,188 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, Local, Utc};
+
+use crate::config::Entry;
+use crate::link::Link;
+use crate::project::Project;
+
+const ICS_DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+// lines longer than this many bytes must be folded
+const ICS_LINE_LIMIT: usize = 75;
+
+/// An iCalendar file with an event for every main or snoozed entry that has a due date.
+pub fn ics(project: &Project, now: DateTime<Utc>) -> String {
+    // uids must be unique across projects that might share a calendar
+    let project_name = project
+        .root
+        .canonicalize()
+        .ok()
+        .and_then(|root| {
+            root.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_default();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//flist//flist//EN".to_string(),
+    ];
+    for entry in project.entries.iter().chain(project.snoozed.iter()) {
+        let Some(due) = entry.due else {
+            continue;
+        };
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}.{}@flist", entry.id, escape(&project_name)));
+        lines.push(format!("DTSTAMP:{}", now.format(ICS_DATE_FORMAT)));
+        lines.push(format!("DTSTART:{}", due.format(ICS_DATE_FORMAT)));
+        lines.push(format!("SUMMARY:{}", escape(&entry.name)));
+        if let Link::Url(url) = &entry.link {
+            lines.push(format!("URL:{url}"));
+        }
+        lines.push(format!("DESCRIPTION:{}", escape(entry.link.as_str())));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+/// A line per main entry, made by filling the `{{field}}` placeholders of `template` with the entry's fields. `\t`
+/// and `\n` in the template stand for a tab and a newline.
+pub fn template(project: &Project, template: &str) -> Result<String, String> {
+    let template = template.replace("\\t", "\t").replace("\\n", "\n");
+    let mut ret = String::new();
+    for entry in &project.entries {
+        let mut rest = template.as_str();
+        while let Some(start) = rest.find("{{") {
+            ret.push_str(&rest[..start]);
+            let Some(len) = rest[start + 2..].find("}}") else {
+                return Err(format!("Unclosed placeholder in {template:?}"));
+            };
+            let field = rest[start + 2..start + 2 + len].trim();
+            ret.push_str(&template_field(entry, field)?);
+            rest = &rest[start + 2 + len + 2..];
+        }
+        ret.push_str(rest);
+        ret.push('\n');
+    }
+    Ok(ret)
+}
+
+fn template_field(entry: &Entry, field: &str) -> Result<String, String> {
+    let date = |time: Option<DateTime<Utc>>| {
+        time.map(|time| {
+            time.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+    };
+    Ok(match field {
+        "id" => entry.id.to_string(),
+        "name" => entry.name.clone(),
+        "link" => entry.link.as_str().to_string(),
+        "added" => date(Some(entry.time_added)),
+        "due" => date(entry.due),
+        "section" => entry.section.clone().unwrap_or_default(),
+        "tags" => entry.tags.join(","),
+        "metadata" => entry.metadata.join("; "),
+        "starred" => entry.starred.to_string(),
+        _ => {
+            return Err(format!(
+                "Unknown field {field}, expected one of id, name, link, added, due, section, tags, metadata, starred"
+            ))
+        }
+    })
+}
+
+/// Fills `dir` with a symlink per main entry, numbered to keep their order. Url entries become `.url` shortcut files.
+/// Links and shortcuts from a previous export are replaced, other files in the directory are left alone.
+pub fn symlinks(project: &Project, dir: &Path) -> io::Result<usize> {
+    fs::create_dir_all(dir)?;
+    for child in fs::read_dir(dir)? {
+        let child = child?;
+        let is_previous_export = child.file_type()?.is_symlink()
+            || child.path().extension().is_some_and(|ext| ext == "url");
+        if is_previous_export {
+            fs::remove_file(child.path())?;
+        }
+    }
+    let width = project.entries.len().to_string().len();
+    for (idx, entry) in project.entries.iter().enumerate() {
+        let prefix = format!("{:0width$} {}", idx + 1, file_name_safe(&entry.name));
+        match &entry.link {
+            Link::Url(_) | Link::Feed(_) => fs::write(
+                dir.join(format!("{prefix}.url")),
+                format!(
+                    "[InternetShortcut]\r\nURL={}\r\n",
+                    entry.link.feed_url().unwrap_or(entry.link.as_str())
+                ),
+            )?,
+            Link::File(path) | Link::Directory(path) => {
+                let target = Path::new(path);
+                // keep the extension, so the link opens with the right program
+                let name = match target.extension() {
+                    Some(ext) if !prefix.ends_with(&format!(".{}", ext.to_string_lossy())) => {
+                        format!("{prefix}.{}", ext.to_string_lossy())
+                    }
+                    _ => prefix,
+                };
+                symlink(target, &dir.join(name))?;
+            }
+        }
+    }
+    Ok(project.entries.len())
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    if target.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
+#[cfg(not(windows))]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// replaces characters that can't appear in file names on some platforms
+fn file_name_safe(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect::<String>()
+        .trim()
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// splits a line into continuation lines that fit the limit, without splitting characters
+fn fold(line: &str) -> String {
+    let mut ret = String::with_capacity(line.len());
+    let mut current_len = 0;
+    for c in line.chars() {
+        if current_len + c.len_utf8() > ICS_LINE_LIMIT {
+            ret.push_str("\r\n ");
+            // the leading space counts towards the continuation line's length
+            current_len = 1;
+        }
+        ret.push(c);
+        current_len += c.len_utf8();
+    }
+    ret
+}
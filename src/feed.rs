@@ -0,0 +1,76 @@
+use std::cmp::Reverse;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::net::Net;
+
+const FEED_TIMEOUT: Duration = Duration::from_millis(10000);
+const FEED_UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/117.0.0.0 Safari/537.36";
+/// links starting with this are feeds, the rest of the link is the url of the feed
+pub const FEED_PREFIX: &str = "feed:";
+/// how many items expanding a feed adds at most, the most recent ones
+pub const EXPAND_LIMIT: usize = 20;
+
+#[derive(Debug, Clone)]
+pub struct Feed {
+    pub title: Option<String>,
+    /// newest first
+    pub items: Vec<FeedItem>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FeedItem {
+    pub title: String,
+    pub link: String,
+    pub published: Option<DateTime<Utc>>,
+}
+
+impl FeedItem {
+    /// Whether the item came out after the feed was last read. Items without a date are only unread if the feed was
+    /// never read.
+    pub fn is_unread(&self, read: Option<DateTime<Utc>>) -> bool {
+        match (read, self.published) {
+            (None, _) => true,
+            (Some(read), Some(published)) => published > read,
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// Fetches and parses the rss or atom feed at `url`. Items without a link are left out, as there is nothing to open.
+pub fn fetch(url: &str, net: &Net) -> Result<Feed, String> {
+    let client = net.client(FEED_TIMEOUT, FEED_UA)?;
+    let body = net
+        .get(&client, url)?
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.bytes())
+        .map_err(|err| format!("Failed to fetch feed {url}: {err}"))?;
+    let feed = feed_rs::parser::parse(body.as_ref())
+        .map_err(|err| format!("Failed to read feed {url}: {err}"))?;
+    let mut items = feed
+        .entries
+        .into_iter()
+        .filter_map(|item| {
+            let link = item.links.first()?.href.clone();
+            Some(FeedItem {
+                title: item
+                    .title
+                    .map_or_else(|| link.clone(), |title| title.content),
+                link,
+                published: item.published.or(item.updated),
+            })
+        })
+        .collect::<Vec<_>>();
+    items.sort_by_key(|item| Reverse(item.published));
+    Ok(Feed {
+        title: feed.title.map(|title| title.content),
+        items,
+    })
+}
+
+/// how many of the items came out after the feed was last read
+pub fn unread(items: &[FeedItem], read: Option<DateTime<Utc>>) -> usize {
+    items.iter().filter(|item| item.is_unread(read)).count()
+}
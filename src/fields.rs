@@ -0,0 +1,50 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The value of a custom field of an entry. Values typed in by the user are numbers or dates if they parse as such,
+/// and text otherwise.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum FieldValue {
+    Number(f64),
+    Date(NaiveDate),
+    Text(String),
+}
+
+impl FieldValue {
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        if let Ok(number) = text.parse() {
+            Self::Number(number)
+        } else if let Ok(date) = NaiveDate::parse_from_str(text, DATE_FORMAT) {
+            Self::Date(date)
+        } else {
+            Self::Text(text.to_string())
+        }
+    }
+
+    /// compares values of the same type, text is compared ignoring case
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a.partial_cmp(b),
+            (Self::Date(a), Self::Date(b)) => Some(a.cmp(b)),
+            (Self::Text(a), Self::Text(b)) => Some(a.to_lowercase().cmp(&b.to_lowercase())),
+            _ => None,
+        }
+    }
+}
+
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "{number}"),
+            Self::Date(date) => write!(f, "{}", date.format(DATE_FORMAT)),
+            Self::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
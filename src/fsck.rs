@@ -0,0 +1,220 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use chrono::Local;
+use serde_json::Value;
+
+use crate::config::{Entry, FlistConfig};
+use crate::errors::FlistError;
+use crate::journal::List;
+use crate::project;
+use crate::tree;
+
+const LISTS: [List; 3] = [List::Entries, List::Archive, List::Snoozed];
+
+/// The problems found in a project's saved lists, and the lists with them repaired.
+pub struct Check {
+    /// problems that `repair` fixes, each saying how
+    pub problems: Vec<String>,
+    /// problems that need fixing by hand, the files that have them are left as they are
+    pub unrepairable: Vec<String>,
+    lists: Vec<(List, Vec<Entry>)>,
+}
+
+pub fn check(root: &Path, config: &FlistConfig) -> Check {
+    let mut problems = Vec::new();
+    let mut unrepairable = Vec::new();
+    let mut lists = Vec::new();
+    for list in LISTS {
+        let file_name = list.file_name();
+        // a missing file is an empty list
+        let Ok(text) = fs::read_to_string(root.join(file_name)) else {
+            continue;
+        };
+        let items = match serde_json::from_str::<Vec<Value>>(&text) {
+            Ok(items) => items,
+            Err(err) => {
+                unrepairable.push(format!("{file_name} is not a list of entries: {err}"));
+                continue;
+            }
+        };
+        let mut entries = Vec::new();
+        for (idx, item) in items.into_iter().enumerate() {
+            match serde_json::from_value::<Entry>(item) {
+                Ok(entry) => entries.push(entry),
+                Err(err) => problems.push(format!(
+                    "{file_name}: item {idx} is not an entry ({err}), removing it"
+                )),
+            }
+        }
+        lists.push((list, entries));
+    }
+
+    // the same entry in two places is removed from the later, different entries with the same id are renumbered
+    let mut next_id = project::next_entry_id(
+        root,
+        lists
+            .iter()
+            .flat_map(|(_, entries)| entries.iter().map(|entry| entry.id)),
+    );
+    let mut seen: HashMap<u64, (List, String)> = HashMap::new();
+    for (list, entries) in &mut lists {
+        let list = *list;
+        entries.retain_mut(|entry| {
+            if entry.id == 0 {
+                return true;
+            }
+            match seen.get(&entry.id) {
+                None => {
+                    seen.insert(entry.id, (list, entry.link.as_str().to_string()));
+                    true
+                }
+                Some((first, link)) if link == entry.link.as_str() && *first == list => {
+                    problems.push(format!(
+                        "{} (id {}) is in {} twice, removing the copy",
+                        entry.name,
+                        entry.id,
+                        list.file_name()
+                    ));
+                    false
+                }
+                Some((first, link)) if link == entry.link.as_str() => {
+                    problems.push(format!(
+                        "{} (id {}) is in both {} and {}, removing it from {}",
+                        entry.name,
+                        entry.id,
+                        first.file_name(),
+                        list.file_name(),
+                        list.file_name()
+                    ));
+                    false
+                }
+                Some(_) => {
+                    problems.push(format!(
+                        "{} has the id {} of another entry, giving it the id {next_id}",
+                        entry.name, entry.id
+                    ));
+                    entry.id = next_id;
+                    next_id += 1;
+                    true
+                }
+            }
+        });
+    }
+
+    let ids = lists
+        .iter()
+        .flat_map(|(_, entries)| entries.iter().map(|entry| entry.id))
+        .collect::<HashSet<_>>();
+    let sections = config
+        .sections
+        .iter()
+        .map(|section| section.name.as_str())
+        .collect::<HashSet<_>>();
+    for entry in lists.iter_mut().flat_map(|(_, entries)| entries.iter_mut()) {
+        let see_also = entry.see_also.len();
+        entry.see_also.retain(|id| ids.contains(id));
+        if entry.see_also.len() < see_also {
+            problems.push(format!(
+                "{} links to missing entries, removing the links",
+                entry.name
+            ));
+        }
+        if entry.parent.is_some_and(|parent| !ids.contains(&parent)) {
+            problems.push(format!(
+                "{} is nested under a missing entry, moving it to the top level",
+                entry.name
+            ));
+            entry.parent = None;
+        }
+        // without configured sections, any section name goes
+        if let Some(section) = &entry.section {
+            if !sections.is_empty() && !sections.contains(section.as_str()) {
+                problems.push(format!(
+                    "{} is in the section {section}, which is not in flist.toml, removing it from the section",
+                    entry.name
+                ));
+                entry.section = None;
+            }
+        }
+    }
+
+    // the list shows the first entry of a cycle of nested entries at the top level, which repairing makes so
+    let flat = lists
+        .iter()
+        .flat_map(|(_, entries)| entries.iter())
+        .collect::<Vec<_>>();
+    let by_id = flat
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.id != 0)
+        .map(|(idx, entry)| (entry.id, idx))
+        .collect::<HashMap<_, _>>();
+    let parents = flat
+        .iter()
+        .map(|entry| entry.parent.and_then(|parent| by_id.get(&parent).copied()))
+        .collect::<Vec<_>>();
+    let cycle_roots = tree::cycle_roots(&parents)
+        .into_iter()
+        .collect::<HashSet<_>>();
+    let all_entries = lists.iter_mut().flat_map(|(_, entries)| entries.iter_mut());
+    for (idx, entry) in all_entries.enumerate() {
+        if cycle_roots.contains(&idx) {
+            problems.push(format!(
+                "{} is nested in a cycle of entries, moving it to the top level",
+                entry.name
+            ));
+            entry.parent = None;
+        }
+    }
+
+    Check {
+        problems,
+        unrepairable,
+        lists,
+    }
+}
+
+impl Check {
+    /// Writes the repaired lists, after copying the original files to a backup directory, which is returned.
+    pub fn repair(&self, root: &Path) -> io::Result<String> {
+        let backup = format!("fsck-backup-{}", Local::now().format("%Y%m%d-%H%M%S"));
+        fs::create_dir_all(root.join(&backup))?;
+        for (list, entries) in &self.lists {
+            let path = root.join(list.file_name());
+            fs::copy(&path, root.join(&backup).join(list.file_name()))?;
+            fs::write(path, serde_json::to_string(entries)?)?;
+        }
+        Ok(backup)
+    }
+}
+
+/// Prints the problems of the project, and offers to repair them unless `dry_run` is set.
+pub fn run(root: &Path, config: &FlistConfig, dry_run: bool) -> Result<(), FlistError> {
+    let check = check(root, config);
+    for problem in check.unrepairable.iter().chain(check.problems.iter()) {
+        println!("{problem}");
+    }
+    if check.problems.is_empty() {
+        if check.unrepairable.is_empty() {
+            println!("No problems found");
+        }
+        return Ok(());
+    }
+    if dry_run {
+        return Ok(());
+    }
+    print!("Repair {} problem(s)? [y/N] ", check.problems.len());
+    let mut answer = String::new();
+    io::stdout()
+        .flush()
+        .and_then(|_| io::stdin().read_line(&mut answer))
+        .map_err(|err| FlistError::Command(format!("Failed to read answer: {err}")))?;
+    if answer.trim().eq_ignore_ascii_case("y") {
+        let backup = check.repair(root).map_err(FlistError::io("repair", root))?;
+        println!("Repaired, the original files are in {backup}");
+    }
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone)]
+pub struct GitStatus {
+    /// "HEAD" when detached
+    pub branch: String,
+    /// whether there are uncommitted changes or untracked files
+    pub dirty: bool,
+    /// the web page of the origin remote, if it has one
+    pub remote_url: Option<String>,
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The status of the git repository at `dir`, None if `dir` is not the root of a repository (or git is missing).
+pub fn status(dir: &Path) -> Option<GitStatus> {
+    if !dir.join(".git").exists() {
+        return None;
+    }
+    let branch = git(dir, &["rev-parse", "--abbrev-ref", "HEAD"])?;
+    let dirty = !git(dir, &["status", "--porcelain"])?.is_empty();
+    Some(GitStatus {
+        branch,
+        dirty,
+        remote_url: remote_url(dir),
+    })
+}
+
+pub fn remote_url(dir: &Path) -> Option<String> {
+    web_url(&git(dir, &["remote", "get-url", "origin"])?)
+}
+
+/// the contents of the file at `path`, relative to `dir`, as of the revision `rev`
+pub fn show(dir: &Path, rev: &str, path: &str) -> Option<String> {
+    git(dir, &["show", &format!("{rev}:./{path}")])
+}
+
+/// converts clone urls like "git@github.com:owner/repo.git" to "https://github.com/owner/repo"
+fn web_url(remote: &str) -> Option<String> {
+    let remote = remote.trim_end_matches('/');
+    let remote = remote.strip_suffix(".git").unwrap_or(remote);
+    let (host, path) = if let Some(rest) = remote
+        .strip_prefix("https://")
+        .or_else(|| remote.strip_prefix("http://"))
+    {
+        // drop credentials, e.g. "user:token@host"
+        let rest = rest.rsplit_once('@').map_or(rest, |(_, rest)| rest);
+        rest.split_once('/')?
+    } else if let Some(rest) = remote
+        .strip_prefix("ssh://")
+        .or_else(|| remote.strip_prefix("git://"))
+    {
+        let rest = rest.rsplit_once('@').map_or(rest, |(_, rest)| rest);
+        let (host, path) = rest.split_once('/')?;
+        // drop the port, the web page is served on the default one
+        (host.split(':').next()?, path)
+    } else {
+        // scp-like syntax, "user@host:path"
+        let rest = remote.rsplit_once('@').map_or(remote, |(_, rest)| rest);
+        rest.split_once(':')?
+    };
+    Some(format!("https://{host}/{path}"))
+}
@@ -1,81 +1,435 @@
 use std::borrow::Cow;
 use std::cell::RefCell;
-use std::io::{self, Read};
+use std::io::{self, Read, Write};
 use std::net::{TcpListener, TcpStream};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use chrono::{Datelike, Duration as ChronoDuration, Local, Months, NaiveDate, Utc};
 
 use crossterm::event::{
     self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
-    KeyModifiers,
+    KeyModifiers, KeyboardEnhancementFlags, MouseButton, MouseEvent, MouseEventKind,
+    PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
 };
 use crossterm::execute;
 use crossterm::terminal::{
-    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+    disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+    LeaveAlternateScreen, SetTitle,
 };
 use ratatui::backend::{Backend, CrosstermBackend};
-use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs};
 use ratatui::{Frame, Terminal};
+use rustls::ServerConfig;
 
+use crate::agenda::{self, AgendaKind, DEFAULT_AGENDA_DAYS};
+use crate::args;
+use crate::config::{DirectoryEnter, Entry, FlistConfig, InsertSource, UrlEnter, Watch};
+use crate::download::{self, Progress};
+use crate::errors::{FlistError, LockedProject};
+use crate::feed::{self, FeedItem};
+use crate::fields::FieldValue;
+use crate::git::{self, GitStatus};
+use crate::hooks;
+use crate::issues;
+use crate::journal::{self, JournalEvent};
+use crate::keys::KeyMap;
+use crate::limits;
 use crate::link::Link;
 use crate::lock::LockFile;
-use crate::project::Project;
-use crate::requests::{InsertRequest, RemoteRequest};
+use crate::macros;
+use crate::media;
+use crate::naming::{self, NameStep};
+use crate::net::Net;
+use crate::opener;
+use crate::project::{EntryLocation, Project};
+use crate::providers;
+use crate::query::{self, Query, CONFIRM_OPEN_ABOVE};
+use crate::reading;
+use crate::refresh::{TitleRefresh, REFRESH_WORKERS};
+use crate::registry;
+use crate::relink::{self, Candidate};
+use crate::reminders::Reminders;
+use crate::report;
+use crate::requests::{
+    EditLinkRequest, HelloResponse, InsertRequest, RemoteRequest, RenameRequest,
+};
+use crate::share;
+use crate::snapshot;
+use crate::target::{self, TargetStat};
+use crate::tls;
+use crate::trace::Tracer;
+use crate::tree;
+use crate::watch;
+use crate::webhooks;
+use crate::when::parse_when;
 
 use cli_clipboard::{ClipboardContext, ClipboardProvider};
+use qrcode::render::unicode::Dense1x2;
+use qrcode::QrCode;
 
-pub fn main(project: Project, listener: TcpListener, lockfile: LockFile) {
+/// views the projects, each with its own lock and listener, in tabs
+pub fn main(
+    projects: Vec<(Project, Vec<TcpListener>, Arc<ServerConfig>, LockFile)>,
+    accessible: bool,
+    trace_protocol: bool,
+) -> Result<(), FlistError> {
     let mut stdout = io::stdout();
-    enable_raw_mode().expect("Failed to enable raw mode");
+    // a panic would leave the terminal in raw mode under its message, it is restored first
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+        default_hook(info);
+    }));
+    enable_raw_mode().map_err(FlistError::Terminal)?;
     execute!(
         stdout,
         EnterAlternateScreen,
         EnableMouseCapture,
         SetTitle("Flist")
     )
-    .expect("Failed to enter alternate screen");
+    .map_err(FlistError::Terminal)?;
+    // without the enhanced keyboard protocol, most unix terminals send Ctrl+Enter as a plain Enter
+    let enhanced_keys = supports_keyboard_enhancement().unwrap_or(false);
+    if enhanced_keys {
+        execute!(
+            stdout,
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )
+        .map_err(FlistError::Terminal)?;
+    }
+    let ctrl_enter = enhanced_keys || cfg!(windows);
 
     let mut terminal =
-        Terminal::new(CrosstermBackend::new(stdout)).expect("Failed to create terminal");
+        Terminal::new(CrosstermBackend::new(stdout)).map_err(FlistError::Terminal)?;
 
     let tick_rate = Duration::from_millis(100);
-    let app = App::new(project, lockfile, ClipboardContext::new().ok());
-    start_listener_thread(&app, listener);
-    let result = run_app(&mut terminal, app, tick_rate);
+    let tabbed = projects.len() > 1;
+    let mut apps = projects
+        .into_iter()
+        .map(|(project, listeners, tls, lockfile)| {
+            let mut app = App::new(
+                project,
+                lockfile,
+                ClipboardContext::new().ok(),
+                accessible,
+                ctrl_enter,
+            );
+            app.tabbed = tabbed;
+            app.tracer = Tracer::new(&app.project.root, trace_protocol);
+            if let (Some(port), Ok(addr)) =
+                (&app.project.config.listener_port, listeners[0].local_addr())
+            {
+                if !port.ports().contains(&addr.port()) {
+                    app.notice = Some(format!(
+                        "The configured listener port is taken, listening on {} instead",
+                        addr.port()
+                    ));
+                }
+            }
+            if app.remote {
+                let alternate = if ctrl_enter { "Ctrl+Enter" } else { "o" };
+                app.notice.get_or_insert_with(|| {
+                    format!(
+                        "Remote session: Enter copies entries to the terminal's clipboard and shows urls as QR codes, {alternate} opens them here"
+                    )
+                });
+            } else if let Some(opener) = opener::missing_opener() {
+                app.notice.get_or_insert_with(|| {
+                    format!(
+                        "{opener} was not found, entries that can't be opened are copied to the clipboard"
+                    )
+                });
+            }
+            for listener in listeners {
+                start_listener_thread(&app, listener, tls.clone());
+            }
+            refresh_providers(&app.project, &app.background);
+            app
+        })
+        .collect::<Vec<_>>();
+    let result = run_app(&mut terminal, &mut apps, tick_rate);
 
-    disable_raw_mode().expect("Failed to disable raw mode");
+    if enhanced_keys {
+        execute!(terminal.backend_mut(), PopKeyboardEnhancementFlags)
+            .map_err(FlistError::Terminal)?;
+    }
+    disable_raw_mode().map_err(FlistError::Terminal)?;
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
         DisableMouseCapture
     )
-    .expect("Failed to leave alternate screen");
-    terminal.show_cursor().expect("Failed to show cursor");
+    .map_err(FlistError::Terminal)?;
+    terminal.show_cursor().map_err(FlistError::Terminal)?;
 
-    result.expect("Failed to run app");
+    for target in apps.iter().flat_map(|app| &app.unopened) {
+        println!("Could not open {target}");
+    }
+    result.map_err(FlistError::Terminal)
 }
 
 type PendingMessages = Arc<Mutex<Vec<ListenerMessages>>>;
 
-fn handle_stream(mut stream: TcpStream, pending_messages: PendingMessages) {
+// entries added this many days ago or later are triaged when the inbox is empty
+const NEW_ENTRY_DAYS: i64 = 7;
+
+// how many entries of a remote batch are added each tick, so that large batches don't freeze the view
+const BATCH_CHUNK: usize = 50;
+
+// how many of the latest archived entries the archive peek shows
+const ARCHIVE_PEEK: usize = 10;
+
+// a pause this long in typing ends the type-ahead
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1500);
+
+// a second click on the same row within this long opens it
+const DOUBLE_CLICK: Duration = Duration::from_millis(500);
+
+/// the entries of remote batches that were not added yet
+struct Batch {
+    requests: VecDeque<InsertRequest>,
+    // the number of entries in the batches so far, to show progress against
+    total: usize,
+    // whether anything changed since the batch started, it is saved once the batch is done
+    changed: bool,
+}
+
+/// Work done outside of the ui thread, its results are delivered as messages to be applied on the next tick.
+#[derive(Clone)]
+struct BackgroundWork {
+    messages: PendingMessages,
+    in_flight: Arc<AtomicUsize>,
+    // the downloads that are running, by entry id
+    downloads: Arc<Mutex<BTreeMap<u64, Progress>>>,
+    // the last bulk refresh of titles, which might be over
+    refresh: Arc<Mutex<Option<Arc<TitleRefresh>>>>,
+}
+
+impl BackgroundWork {
+    fn new() -> Self {
+        Self {
+            messages: Arc::new(Mutex::new(Vec::new())),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            downloads: Arc::new(Mutex::new(BTreeMap::new())),
+            refresh: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn spawn(&self, job: impl FnOnce() -> Option<ListenerMessages> + Send + 'static) {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let this = self.clone();
+        std::thread::spawn(move || {
+            if let Some(message) = job() {
+                this.messages.lock().unwrap().push(message);
+            }
+            this.in_flight.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+
+    /// Downloads the url of the entry in the background, with its progress tracked for the status line.
+    fn download(&self, id: u64, name: String, url: String, root: PathBuf, dir: String, net: Net) {
+        if self.downloads.lock().unwrap().contains_key(&id) {
+            return;
+        }
+        let progress = Progress {
+            name,
+            done: 0,
+            total: None,
+        };
+        self.downloads.lock().unwrap().insert(id, progress);
+        let downloads = self.downloads.clone();
+        self.spawn(move || {
+            let path = download::download(&root, &dir, &url, &net, |done, total| {
+                if let Some(progress) = downloads.lock().unwrap().get_mut(&id) {
+                    progress.done = done;
+                    progress.total = total;
+                }
+            });
+            downloads.lock().unwrap().remove(&id);
+            Some(ListenerMessages::Downloaded { id, path })
+        });
+    }
+
+    /// the progress of the first running download, if any
+    fn download_progress(&self) -> Option<String> {
+        let downloads = self.downloads.lock().unwrap();
+        let (_, progress) = downloads.iter().next()?;
+        Some(match downloads.len() {
+            1 => progress.describe(),
+            n => format!("{} (and {} more)", progress.describe(), n - 1),
+        })
+    }
+
+    /// Fetches the names of the entries on a few workers, each waiting its turn for the host of its entry. Does nothing
+    /// and returns false if a refresh is already running.
+    fn refresh_titles(&self, links: Vec<(u64, Link)>, steps: Vec<NameStep>, net: Net) -> bool {
+        let mut running = self.refresh.lock().unwrap();
+        if running
+            .as_ref()
+            .is_some_and(|refresh| !refresh.is_finished())
+        {
+            return false;
+        }
+        let refresh = Arc::new(TitleRefresh::new(links));
+        *running = Some(refresh.clone());
+        drop(running);
+        for _ in 0..REFRESH_WORKERS {
+            let (refresh, steps, net) = (refresh.clone(), steps.clone(), net.clone());
+            let messages = self.messages.clone();
+            // counted before the worker starts, so that the refresh isn't over before any worker took an entry
+            refresh.start_worker();
+            self.spawn(move || {
+                while let Some((id, link)) = refresh.next() {
+                    let details = infer_url_details(&link, &steps, &net);
+                    messages
+                        .lock()
+                        .unwrap()
+                        .push(ListenerMessages::RefreshedTitle { id, details });
+                    refresh.finish_one();
+                }
+                refresh.stop_worker();
+                None
+            });
+        }
+        true
+    }
+
+    /// cancels the running refresh of titles, returns false if there is none
+    fn cancel_refresh(&self) -> bool {
+        match self.refresh.lock().unwrap().as_ref() {
+            Some(refresh) if !refresh.is_finished() && !refresh.is_cancelled() => {
+                refresh.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// the progress bar of the running refresh of titles, if any
+    fn refresh_progress(&self) -> Option<String> {
+        let refresh = self.refresh.lock().unwrap();
+        let refresh = refresh.as_ref().filter(|refresh| !refresh.is_finished())?;
+        Some(refresh.describe())
+    }
+
+    /// the number of jobs still running, plus the number of messages waiting to be applied
+    fn pending(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst) + self.messages.lock().unwrap().len()
+    }
+}
+
+fn refresh_providers(project: &Project, background: &BackgroundWork) {
+    for provider in project.config.providers.iter().cloned() {
+        let root = project.root.clone();
+        background.spawn(move || {
+            let entries = providers::fetch(&provider, &root);
+            Some(ListenerMessages::Provided {
+                provider: provider.name,
+                entries,
+            })
+        });
+    }
+}
+
+fn handle_stream(
+    stream: TcpStream,
+    tls: Arc<ServerConfig>,
+    tracer: &Tracer,
+    project_name: &str,
+) -> Option<ListenerMessages> {
+    let start = Instant::now();
+    let peer = stream
+        .peer_addr()
+        .map_or_else(|_| "unknown".to_string(), |addr| addr.to_string());
+    let mut stream = match tls::accept(tls, stream) {
+        Ok(stream) => stream,
+        Err(err) => {
+            tracer.log(&format!("failed to accept {peer}: {err}"));
+            return None;
+        }
+    };
+    // clients probing whether the project is open connect without sending anything
+    if stream.probed() {
+        tracer.log(&format!("{peer} connected without a request"));
+        return None;
+    }
     let mut buffer = String::new();
-    stream.read_to_string(&mut buffer).unwrap();
+    if let Err(err) = stream.read_to_string(&mut buffer) {
+        tracer.log(&format!("failed to read from {peer}: {err}"));
+        return None;
+    }
     if buffer.is_empty() {
-        return;
+        tracer.log(&format!("{peer} connected without a request"));
+        return None;
+    }
+    let elapsed = start.elapsed();
+    match serde_json::from_str::<RemoteRequest>(&buffer) {
+        Ok(RemoteRequest::Hello(hello)) => {
+            let response = serde_json::to_string(&HelloResponse::new(project_name.to_string()))
+                .expect("Failed to serialize hello response");
+            let sent = stream
+                .write_all(response.as_bytes())
+                .and_then(|()| stream.finish());
+            tracer.log(&format!(
+                "received from {peer} on protocol {} in {elapsed:.1?}: {buffer}, answered {}: {response}",
+                hello.hello,
+                if sent.is_ok() { "" } else { "(failed)" }
+            ));
+            None
+        }
+        Ok(RemoteRequest::Insert(request)) => {
+            tracer.log(&format!("received from {peer} in {elapsed:.1?}: {buffer}"));
+            Some(ListenerMessages::Insert(request))
+        }
+        Ok(RemoteRequest::InsertMany(requests)) => {
+            tracer.log(&format!("received from {peer} in {elapsed:.1?}: {buffer}"));
+            Some(ListenerMessages::InsertMany(requests))
+        }
+        Ok(RemoteRequest::Archive(request)) => {
+            tracer.log(&format!("received from {peer} in {elapsed:.1?}: {buffer}"));
+            Some(ListenerMessages::Archive(request.archive))
+        }
+        Ok(RemoteRequest::Remove(request)) => {
+            tracer.log(&format!("received from {peer} in {elapsed:.1?}: {buffer}"));
+            Some(ListenerMessages::Remove(request.remove))
+        }
+        Ok(RemoteRequest::Rename(request)) => {
+            tracer.log(&format!("received from {peer} in {elapsed:.1?}: {buffer}"));
+            Some(ListenerMessages::Rename(request))
+        }
+        Ok(RemoteRequest::EditLink(request)) => {
+            tracer.log(&format!("received from {peer} in {elapsed:.1?}: {buffer}"));
+            Some(ListenerMessages::EditLink(request))
+        }
+        Err(err) => {
+            tracer.log(&format!(
+                "ignored from {peer} in {elapsed:.1?}, not a request ({err}): {buffer}"
+            ));
+            None
+        }
     }
-    let Ok(request) = serde_json::from_str::<RemoteRequest>(&buffer) else {return;};
-    pending_messages.lock().unwrap().push(request.into());
 }
 
-fn start_listener_thread(app: &App, listener: TcpListener) {
-    let pending_messages = app.pending_messages.clone();
+fn start_listener_thread(app: &App, listener: TcpListener, tls: Arc<ServerConfig>) {
+    let background = app.background.clone();
+    let tracer = app.tracer.clone();
+    let project_name = project_title(&app.project);
     std::thread::spawn(move || {
         for stream in listener.incoming().flatten() {
-            let pending_messages = pending_messages.clone();
-            std::thread::spawn(move || handle_stream(stream, pending_messages));
+            let tracer = tracer.clone();
+            let project_name = project_name.clone();
+            let tls = tls.clone();
+            background.spawn(move || handle_stream(stream, tls, &tracer, &project_name));
         }
     });
 }
@@ -84,608 +438,7145 @@ struct App {
     project: Project,
     _lockfile: LockFile,
 
-    pending_messages: PendingMessages,
+    background: BackgroundWork,
+    // set once the user asked to quit, while we wait for background work to finish
+    quitting: bool,
+    // whether changes are saved as they are made, they wait for Ctrl+S otherwise
+    autosave: bool,
+    // whether there are changes that were not saved yet
+    dirty: bool,
+    // set when the user asked to quit with unsaved changes, until they choose whether to save them
+    confirming_quit: bool,
+    // how the project is getting too large, checked on every save
+    size_warnings: Vec<String>,
+    // whether the diagnostics overlay, toggled with Ctrl+D, is shown
+    debug: bool,
+    // how long the last frame and the last save took, for the diagnostics overlay
+    frame_time: Duration,
+    save_time: Duration,
+    reminders: Reminders,
+    // entries whose reading time was already requested in this session
+    estimated: HashSet<u64>,
+    // when the pages of watched entries were last checked, and the entries checked since
+    watch_checked: Option<(Instant, HashSet<u64>)>,
+    // the stats of file and directory targets by link, None while they are computed or if they are missing
+    target_stats: HashMap<String, Option<TargetStat>>,
+    // the git status of directory targets by link, None while it is computed or if they aren't repositories
+    git_statuses: HashMap<String, Option<GitStatus>>,
 
     select_state: SelectState,
     clipboard: Option<RefCell<ClipboardContext>>,
+
+    accessible: bool,
+    // whether the terminal tells Ctrl+Enter apart from Enter, `o` does the same either way
+    ctrl_enter: bool,
+    // whether other projects are open in tabs alongside this one
+    tabbed: bool,
+    // the last state change, spelled out for screen readers
+    announcement: Option<String>,
+    // a problem to show until the next key press
+    notice: Option<String>,
+    // the keys pressed since macro recording started
+    recording: Option<Vec<KeyEvent>>,
+    // logs remote requests and their outcomes with --trace-protocol
+    tracer: Tracer,
+    // a remote batch that is being added a chunk per tick
+    batch: Option<Batch>,
+    // paths and urls that couldn't be opened, printed once the view closes
+    unopened: Vec<String>,
+    // the id of the entry last added remotely, to jump to with J
+    last_remote: Option<u64>,
+    // whether the latest archived entries are shown over the list, until the next key
+    peek_archive: bool,
+    // the letters typed after ' to go to an entry, and when the last one was typed
+    typed: Option<(String, Instant)>,
+    // the keys moved in the project's config
+    keymap: KeyMap,
+    // where the rows of the list were last drawn, and the index in the list of each row from the top, for the mouse
+    drawn_list: Option<(Rect, Vec<usize>)>,
+    // the row last clicked and when, a second click soon after opens it
+    last_click: Option<(usize, Instant)>,
+    // whether the entry being dragged was picked up with the mouse, so that letting go of it drops it
+    mouse_drag: bool,
+    // whether the view runs away from the user's screen, see `remote_session`
+    remote: bool,
+    // the url last copied in a remote session, shown as a QR code until the next key
+    qr_code: Option<String>,
+    // the entries being sent to the project of another tab, while its tab is picked
+    sending: Option<Sending>,
+}
+
+impl Drop for App {
+    fn drop(&mut self) {
+        // a panic may have come between a change and its save, keep the change for the next start to replay
+        if std::thread::panicking() {
+            let _ = self.project.stash_unsaved();
+        }
+    }
 }
 
 impl App {
-    fn new(project: Project, lockfile: LockFile, clipboard: Option<ClipboardContext>) -> Self {
+    fn new(
+        project: Project,
+        lockfile: LockFile,
+        clipboard: Option<ClipboardContext>,
+        accessible: bool,
+        ctrl_enter: bool,
+    ) -> Self {
+        let autosave = project.config.autosave;
+        let remote = remote_session(&project.config);
+        let size_warnings = limits::warnings(&project);
+        let (keymap, keys_error) = match KeyMap::new(&project.config.keys) {
+            Ok(keymap) => (keymap, None),
+            Err(err) => (KeyMap::default(), Some(err)),
+        };
         Self {
             project,
             _lockfile: lockfile,
-            pending_messages: Arc::new(Mutex::new(Vec::new())),
+            background: BackgroundWork::new(),
+            quitting: false,
+            autosave,
+            dirty: false,
+            confirming_quit: false,
+            size_warnings,
+            debug: false,
+            frame_time: Duration::ZERO,
+            save_time: Duration::ZERO,
+            reminders: Reminders::default(),
+            estimated: HashSet::new(),
+            watch_checked: None,
+            target_stats: HashMap::new(),
+            git_statuses: HashMap::new(),
             select_state: SelectState::Entry(0),
             clipboard: clipboard.map(RefCell::new),
+            accessible,
+            ctrl_enter,
+            tabbed: false,
+            announcement: None,
+            notice: keys_error,
+            recording: None,
+            tracer: Tracer::default(),
+            batch: None,
+            unopened: Vec::new(),
+            last_remote: None,
+            peek_archive: false,
+            typed: None,
+            keymap,
+            drawn_list: None,
+            last_click: None,
+            mouse_drag: false,
+            remote,
+            qr_code: None,
+            sending: None,
+        }
+    }
+
+    /// the background work still running or waiting to be applied, and the entries of batches still to be added
+    fn pending(&self) -> usize {
+        self.background.pending() + self.batch.as_ref().map_or(0, |batch| batch.requests.len())
+    }
+
+    /// queues the entries to be added over the next ticks
+    fn queue_batch(&mut self, requests: Vec<InsertRequest>) {
+        let batch = self.batch.get_or_insert_with(|| Batch {
+            requests: VecDeque::new(),
+            total: 0,
+            changed: false,
+        });
+        batch.total += requests.len();
+        batch.requests.extend(requests);
+    }
+
+    /// the work done every tick, whether or not the project is in the current tab
+    fn tick(&mut self) {
+        if self
+            .typed
+            .as_ref()
+            .is_some_and(|(_, at)| at.elapsed() >= TYPE_AHEAD_TIMEOUT)
+        {
+            self.typed = None;
+        }
+        self.apply_messages();
+        let now = Utc::now();
+        let resurfaced = self.project.resurface_snoozed(now);
+        if resurfaced > 0 {
+            for entry in &self.project.entries[..resurfaced] {
+                self.reminders.entry_resurfaced(entry);
+            }
+            self.save();
+            self.clamp_selection();
+        }
+        let newly_due = self.project.mark_due(now);
+        if !newly_due.is_empty() {
+            for entry in &newly_due {
+                self.reminders.entry_due(entry);
+            }
+            self.save();
+        }
+        self.reminders
+            .flush(&self.project.config.notifications, now);
+        self.dispatch_events();
+        self.estimate_reading_times();
+        self.check_watched();
+        self.hash_targets();
+        self.request_target_stat();
+        self.request_git_status();
+    }
+
+    fn dispatch_events(&mut self) {
+        let events = self.project.take_events();
+        let config = &self.project.config;
+        if events.is_empty() || (config.webhooks.is_empty() && config.hooks.is_empty()) {
+            return;
+        }
+        let webhooks = config.webhooks.clone();
+        let hook_config = config.hooks.clone();
+        let root = self.project.root.clone();
+        let net = config.net();
+        self.background.spawn(move || {
+            hooks::run(&hook_config, &root, &events);
+            webhooks::deliver(&webhooks, &root, &events, &net);
+            None
+        });
+    }
+
+    fn estimate_reading_times(&mut self) {
+        if !self.project.config.reading_time || !self.project.config.network.enabled {
+            return;
+        }
+        for entry in &self.project.entries {
+            let Link::Url(url) = &entry.link else {
+                continue;
+            };
+            if entry.reading_minutes.is_some() || !self.estimated.insert(entry.id) {
+                continue;
+            }
+            let (id, url, net) = (entry.id, url.clone(), self.project.config.net());
+            self.background.spawn(move || {
+                let minutes = reading::estimate_minutes(&url, &net)?;
+                Some(ListenerMessages::ReadingTime { id, minutes })
+            });
+        }
+    }
+
+    /// checks the pages of watched entries and the items of feeds every `watch_minutes`, and those of newly added
+    /// entries right away
+    fn check_watched(&mut self) {
+        if !self.project.config.network.enabled {
+            return;
+        }
+        let interval = Duration::from_secs(self.project.config.watch_minutes * 60);
+        if self
+            .watch_checked
+            .as_ref()
+            .is_none_or(|(at, _)| at.elapsed() >= interval)
+        {
+            self.watch_checked = Some((Instant::now(), HashSet::new()));
+        }
+        let (_, checked) = self.watch_checked.as_mut().unwrap();
+        for entry in &self.project.entries {
+            if let Some(url) = entry.link.feed_url() {
+                if !checked.insert(entry.id) {
+                    continue;
+                }
+                let (id, url, net) = (entry.id, url.to_string(), self.project.config.net());
+                self.background.spawn(move || {
+                    Some(ListenerMessages::Feed {
+                        id,
+                        items: feed::fetch(&url, &net).map(|feed| feed.items),
+                        expand: false,
+                    })
+                });
+                continue;
+            }
+            let Link::Url(url) = &entry.link else {
+                continue;
+            };
+            if entry.watch.is_none() || !checked.insert(entry.id) {
+                continue;
+            }
+            let (id, url, net) = (entry.id, url.clone(), self.project.config.net());
+            self.background.spawn(move || {
+                let hash = watch::content_hash(&url, &net)?;
+                Some(ListenerMessages::Watched { id, hash })
+            });
+        }
+    }
+
+    /// hashes the content of file entries that have no hash yet, e.g. newly added ones, to relink them by it later
+    fn hash_targets(&mut self) {
+        for entry in &self.project.entries {
+            if !matches!(entry.link, Link::File(_)) || entry.target_hash.is_some() {
+                continue;
+            }
+            let link = entry.link.as_str().to_string();
+            if self.target_stats.contains_key(&link) {
+                continue;
+            }
+            self.target_stats.insert(link.clone(), None);
+            let target = entry.link.clone();
+            self.background.spawn(move || {
+                Some(ListenerMessages::TargetStat {
+                    link,
+                    stat: target::stat(&target),
+                    hash: target::content_hash(&target),
+                })
+            });
+        }
+    }
+
+    fn request_target_stat(&mut self) {
+        let Some(entry) = self.select_state.selected_entry(&self.project) else {
+            return;
+        };
+        if let Link::Url(_) | Link::Feed(_) = entry.link {
+            return;
+        }
+        let link = entry.link.as_str().to_string();
+        if self.target_stats.contains_key(&link) {
+            return;
+        }
+        self.target_stats.insert(link.clone(), None);
+        let target = entry.link.clone();
+        // entries added before their content was hashed get a hash while their target is still there to relink by
+        let hash = entry.target_hash.is_none();
+        self.background.spawn(move || {
+            Some(ListenerMessages::TargetStat {
+                link,
+                stat: target::stat(&target),
+                hash: hash.then(|| target::content_hash(&target)).flatten(),
+            })
+        });
+    }
+
+    fn request_git_status(&mut self) {
+        let Some(entry) = self.select_state.selected_entry(&self.project) else {
+            return;
+        };
+        let Link::Directory(dir) = &entry.link else {
+            return;
+        };
+        if self.git_statuses.contains_key(dir) {
+            return;
+        }
+        self.git_statuses.insert(dir.clone(), None);
+        let dir = dir.clone();
+        self.background.spawn(move || {
+            let status = git::status(Path::new(&dir));
+            Some(ListenerMessages::GitStatus { dir, status })
+        });
+    }
+
+    /// saves the project, or marks it as changed if it is only saved on demand
+    fn save(&mut self) {
+        if self.autosave {
+            self.timed_save();
+            self.size_warnings = limits::warnings(&self.project);
+        } else {
+            self.dirty = true;
+        }
+    }
+
+    fn save_now(&mut self) {
+        self.timed_save();
+        self.size_warnings = limits::warnings(&self.project);
+        self.dirty = false;
+    }
+
+    fn timed_save(&mut self) {
+        let start = Instant::now();
+        if let Err(err) = self.project.save() {
+            self.notice = Some(err.to_string());
+        }
+        if let Some(publish) = &self.project.config.publish {
+            let dir = self.project.root.join(&publish.dir);
+            if let Err(err) = report::publish(&self.project, &dir, publish.group) {
+                self.notice = Some(format!("Failed to publish to {}: {err}", dir.display()));
+            }
+        }
+        self.save_time = start.elapsed();
+    }
+
+    /// adds an entry sent by another flist, returns whether it was added
+    fn insert_remote(&mut self, request: InsertRequest) -> bool {
+        let name = request.name.clone();
+        let selected = match self.select_state {
+            SelectState::Entry(id) => main_idx(&self.project, id),
+            _ => None,
+        };
+        match self
+            .project
+            .capture(request.into(), InsertSource::Remote, selected)
+        {
+            Ok(idx) => {
+                self.tracer.log(&format!("added {name}"));
+                self.last_remote = Some(self.project.entries[idx].id);
+                true
+            }
+            Err(err) => {
+                self.tracer.log(&format!("refused to add {name}: {err}"));
+                self.notice = Some(err.to_string());
+                false
+            }
+        }
+    }
+
+    /// keeps a selected row within its list after the list shrank
+    fn clamp_selection(&mut self) {
+        if let Some(state) = self.select_state.clamp(&self.project) {
+            self.select_state = state;
+        }
+    }
+
+    /// The mouse in the list: the wheel scrolls, a click selects a row of the main list or the archive and a second
+    /// click soon after opens it, and dragging an entry of the main list moves it among its siblings. Returns the key
+    /// that the mouse stands for, if any.
+    fn mouse_event(&mut self, mouse: MouseEvent) -> Option<Event> {
+        let key = |code| Some(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        let row = self.drawn_list.as_ref().and_then(|(area, rows)| {
+            let inside = (area.x..area.x + area.width).contains(&mouse.column)
+                && (area.y..area.y + area.height).contains(&mouse.row);
+            inside
+                .then(|| rows.get((mouse.row - area.y) as usize).copied())
+                .flatten()
+        });
+        match mouse.kind {
+            MouseEventKind::ScrollUp => key(KeyCode::Up),
+            MouseEventKind::ScrollDown => key(KeyCode::Down),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let row = row?;
+                let double = self
+                    .last_click
+                    .take()
+                    .is_some_and(|(clicked, at)| clicked == row && at.elapsed() < DOUBLE_CLICK);
+                self.select_state = match self.select_state {
+                    SelectState::Entry(_) => SelectState::entry(&self.project, row),
+                    SelectState::Archive(_) => SelectState::archived(&self.project, row),
+                    _ => return None,
+                };
+                if double {
+                    return key(KeyCode::Enter);
+                }
+                self.last_click = Some((row, Instant::now()));
+                None
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                let row = row?;
+                match self.select_state {
+                    SelectState::Entry(id) if self.project.sort == tree::Sort::Manual => {
+                        let idx = idx_of(&self.project.entries, id);
+                        if idx != row {
+                            self.select_state = SelectState::Drag {
+                                dragged_id: id_at(&self.project.entries, idx),
+                                new_position: idx,
+                                column: None,
+                            };
+                            self.mouse_drag = true;
+                            self.drag_to(row);
+                        }
+                    }
+                    SelectState::Drag { .. } if self.mouse_drag => self.drag_to(row),
+                    _ => {}
+                }
+                None
+            }
+            MouseEventKind::Up(MouseButton::Left) if std::mem::take(&mut self.mouse_drag) => {
+                key(KeyCode::Enter)
+            }
+            _ => None,
+        }
+    }
+
+    // moves the dragged entry towards the row, a sibling at a time as the keys do, as far as its siblings reach
+    fn drag_to(&mut self, row: usize) {
+        let SelectState::Drag {
+            dragged_id,
+            new_position,
+            column: None,
+        } = self.select_state
+        else {
+            return;
+        };
+        let forward = row > new_position;
+        let mut position = new_position;
+        while let Some(next) = tree::sibling(
+            &drag_order(&self.project, dragged_id, position),
+            position,
+            forward,
+        ) {
+            if (forward && next > row) || (!forward && next < row) {
+                break;
+            }
+            position = next;
+        }
+        self.select_state = SelectState::Drag {
+            dragged_id,
+            new_position: position,
+            column: None,
+        };
+    }
+
+    /// Type-ahead in the main list and the archive: after `'`, the letters typed until a pause go to the next entry whose
+    /// name starts with them, or else contains them. Returns whether the event was used.
+    fn type_ahead(&mut self, ev: &Event) -> bool {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = ev
+        else {
+            return false;
+        };
+        let typed = self
+            .typed
+            .take()
+            .filter(|(_, at)| at.elapsed() < TYPE_AHEAD_TIMEOUT);
+        if !matches!(
+            self.select_state,
+            SelectState::Entry(_) | SelectState::Archive(_)
+        ) {
+            return false;
+        }
+        let prefix = match (typed, code) {
+            (None, KeyCode::Char('\'')) => String::new(),
+            (None, _) => return false,
+            (Some(_), KeyCode::Esc) => return true,
+            (Some((mut prefix, _)), KeyCode::Backspace) => {
+                prefix.pop();
+                prefix
+            }
+            (Some((mut prefix, _)), KeyCode::Char(c))
+                if !modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                prefix.push(*c);
+                self.go_to_typed(&prefix);
+                prefix
+            }
+            // any other key ends the type-ahead and acts as usual
+            (Some(_), _) => return false,
+        };
+        self.typed = Some((prefix, Instant::now()));
+        true
+    }
+
+    // the first entry from the selected one on whose name starts with `prefix`, or else contains it, ignoring case. A
+    // single letter starts after the selected entry, so that typing it again goes through the entries it starts.
+    fn go_to_typed(&mut self, prefix: &str) {
+        let (entries, selected) = match self.select_state {
+            SelectState::Entry(id) => (&self.project.entries, id),
+            SelectState::Archive(id) => (&self.project.archive, id),
+            _ => return,
+        };
+        let selected = idx_of(entries, selected);
+        let prefix = prefix.to_lowercase();
+        let start = if prefix.chars().count() == 1 {
+            (selected + 1).min(entries.len())
+        } else {
+            selected
+        };
+        let names = (start..entries.len())
+            .chain(0..start)
+            .map(|idx| (idx, entries[idx].name.to_lowercase()))
+            .collect::<Vec<_>>();
+        let found = names
+            .iter()
+            .find(|(_, name)| name.starts_with(&prefix))
+            .or_else(|| names.iter().find(|(_, name)| name.contains(&prefix)))
+            .map(|(idx, _)| *idx);
+        match (found, &self.select_state) {
+            (Some(idx), SelectState::Entry(_)) => {
+                self.project.reveal(idx);
+                self.select_state = SelectState::entry(&self.project, idx);
+            }
+            (Some(idx), _) => self.select_state = SelectState::archived(&self.project, idx),
+            (None, _) => self.notice = Some(format!("No entry matches {prefix}")),
+        }
+        if self.accessible {
+            self.announcement = Some(self.select_state.describe(&self.project));
+        }
+    }
+
+    /// the entries that V sends to another project: the matches of a kept search, or else the selected entry
+    fn marked(&self) -> Vec<u64> {
+        match &self.select_state {
+            SelectState::Entry(id) => self
+                .project
+                .entries
+                .get(idx_of(&self.project.entries, *id))
+                .map(|entry| entry.id)
+                .into_iter()
+                .collect(),
+            SelectState::Search {
+                input,
+                typing: false,
+                ..
+            } => search_matches(&self.project, input)
+                .into_iter()
+                .map(|idx| self.project.entries[idx].id)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// selects the entry last added remotely, expanding the entries it is nested under
+    fn jump_to_remote(&mut self) {
+        let location = self.last_remote.and_then(|id| self.project.locate(id));
+        let Some(EntryLocation::Entries(idx)) = location else {
+            self.notice = Some("The entry added remotely is no longer in the list".to_string());
+            return;
+        };
+        self.project.reveal(idx);
+        self.select_state = SelectState::entry(&self.project, idx);
+        if self.accessible {
+            self.announcement = Some(self.select_state.describe(&self.project));
+        }
+    }
+
+    /// archives or deletes the entry that a remote request names, returns whether it was found
+    fn remove_remote(&mut self, target: &str, archive: bool) -> bool {
+        let id = match self.project.find(target, !archive) {
+            Ok(id) => id,
+            Err(err) => {
+                self.tracer
+                    .log(&format!("refused to remove {target}: {err}"));
+                self.notice = Some(err);
+                return false;
+            }
+        };
+        // the selection stays on the row of an entry removed from under it
+        let row = match self.select_state {
+            SelectState::Entry(selected) if selected == id => main_idx(&self.project, id),
+            _ => None,
+        };
+        if archive {
+            self.project.complete(id);
+            self.tracer.log(&format!("archived {target}"));
+        } else {
+            self.project.remove(id);
+            self.tracer.log(&format!("removed {target}"));
+        }
+        if let Some(row) = row {
+            let last = self.project.entries.len().saturating_sub(1);
+            self.select_state = SelectState::entry(&self.project, row.min(last));
+        }
+        true
+    }
+
+    /// Edits the entry at `target` for a remote request, refusing edits that conflict with the entry as it is now.
+    /// Returns whether the entry was edited.
+    fn edit_remote(
+        &mut self,
+        target: &str,
+        edit: impl FnOnce(&mut Project, u64) -> Result<(), String>,
+    ) -> bool {
+        let edited = self
+            .project
+            .find(target, true)
+            .and_then(|id| edit(&mut self.project, id));
+        match edited {
+            Ok(()) => {
+                self.tracer.log(&format!("edited {target}"));
+                true
+            }
+            Err(err) => {
+                self.tracer.log(&format!("refused to edit {target}: {err}"));
+                self.notice = Some(err);
+                false
+            }
+        }
+    }
+
+    /// applies the event to the current state, returns whether the user asked to exit
+    fn handle_event(&mut self, ev: Event) -> bool {
+        // the mouse stands for the keys before they are moved, moved keys act as the keys they replace, and what is
+        // typed is left as it is
+        let ev = if let Event::Mouse(mouse) = ev {
+            match self.mouse_event(mouse) {
+                Some(ev) => ev,
+                None => return false,
+            }
+        } else if self.select_state.is_text_input() || self.typed.is_some() {
+            ev
+        } else {
+            match self.keymap.translate(ev) {
+                Some(ev) => ev,
+                None => return false,
+            }
+        };
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char('d'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: KeyEventKind::Press,
+            ..
+        }) = ev
+        {
+            self.debug = !self.debug;
+            return false;
+        }
+        // the notes editor saves its notes along with the project
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char('s'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: KeyEventKind::Press,
+            ..
+        }) = ev
+        {
+            if !matches!(self.select_state, SelectState::Notes { .. }) {
+                self.save_now();
+                self.notice = Some("Saved".to_string());
+                return false;
+            }
+        }
+        if self.type_ahead(&ev) {
+            return false;
+        }
+        // Esc does nothing else in the list
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Esc,
+            kind: KeyEventKind::Press,
+            ..
+        }) = ev
+        {
+            if matches!(self.select_state, SelectState::Entry(_))
+                && self.background.cancel_refresh()
+            {
+                self.notice = Some("Cancelling title refresh".to_string());
+                return false;
+            }
+        }
+        // the QR code closes on the next key, which does nothing else
+        if self.qr_code.is_some()
+            && matches!(
+                ev,
+                Event::Key(KeyEvent {
+                    kind: KeyEventKind::Press,
+                    ..
+                })
+            )
+        {
+            self.qr_code = None;
+            return false;
+        }
+        // the peek closes on the next key, which then acts as it would without it
+        let peeking = matches!(
+            ev,
+            Event::Key(KeyEvent {
+                kind: KeyEventKind::Press,
+                ..
+            })
+        ) && std::mem::take(&mut self.peek_archive);
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char('P'),
+            kind: KeyEventKind::Press,
+            ..
+        }) = ev
+        {
+            if matches!(self.select_state, SelectState::Entry(_))
+                && !self.project.archive.is_empty()
+            {
+                self.peek_archive = !peeking;
+                return false;
+            }
+        }
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char('J'),
+            kind: KeyEventKind::Press,
+            ..
+        }) = ev
+        {
+            if matches!(self.select_state, SelectState::Entry(_)) && self.last_remote.is_some() {
+                self.jump_to_remote();
+                return false;
+            }
+        }
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char('V'),
+            kind: KeyEventKind::Press,
+            ..
+        }) = ev
+        {
+            let marked = self.marked();
+            if !marked.is_empty() {
+                let root = self.project.root.canonicalize().ok();
+                let registered = registry::projects()
+                    .into_iter()
+                    .filter(|registered| Some(registered) != root.as_ref())
+                    .collect::<Vec<_>>();
+                if self.tabbed || !registered.is_empty() {
+                    self.sending = Some(Sending {
+                        ids: marked,
+                        registered,
+                        row: 0,
+                    });
+                } else {
+                    self.notice = Some(
+                        "No other projects to send to, projects are listed once they were opened"
+                            .to_string(),
+                    );
+                }
+                return false;
+            }
+        }
+        let on_event =
+            self.select_state
+                .on_event(ev, &mut self.project, &self.clipboard, &self.background);
+        self.notice = on_event.notice;
+        self.unopened.extend(on_event.unopened);
+        // a screen reader can't show it
+        self.qr_code = on_event.qr_code.filter(|_| !self.accessible);
+        if on_event.save {
+            self.save();
+        }
+        match on_event.next_state {
+            None => false,
+            Some(NextState::Exit) => true,
+            Some(NextState::State(new_state)) => {
+                if self.accessible {
+                    self.announcement = Some(new_state.describe(&self.project));
+                }
+                self.select_state = new_state;
+                false
+            }
+        }
+    }
+
+    /// starts or stops recording with Q, replays with @, returns whether the key was handled
+    fn handle_macro_key(&mut self, ev: &Event) -> bool {
+        let Event::Key(key) = ev else {
+            return false;
+        };
+        if key.kind != KeyEventKind::Press {
+            return false;
+        }
+        match key.code {
+            KeyCode::Char('Q') if !self.select_state.is_text_input() && self.typed.is_none() => {
+                match self.recording.take() {
+                    None => {
+                        self.recording = Some(Vec::new());
+                        self.notice = Some("Recording macro, press Q to stop".to_string());
+                    }
+                    Some(keys) => {
+                        self.notice = Some(match macros::save(&self.project.root, &keys) {
+                            Ok(()) => format!("Recorded macro of {} keys", keys.len()),
+                            Err(err) => err,
+                        });
+                    }
+                }
+                true
+            }
+            KeyCode::Char('@')
+                if !self.select_state.is_text_input()
+                    && self.recording.is_none()
+                    && self.typed.is_none() =>
+            {
+                let keys = match macros::load(&self.project.root) {
+                    Ok(keys) => keys,
+                    Err(err) => {
+                        self.notice = Some(err);
+                        return true;
+                    }
+                };
+                if keys.is_empty() {
+                    self.notice = Some("No macro recorded, press Q to record one".to_string());
+                }
+                for key in keys {
+                    // a replayed exit only stops the replay
+                    if self.handle_event(Event::Key(key)) {
+                        break;
+                    }
+                }
+                true
+            }
+            _ => {
+                if let Some(recording) = &mut self.recording {
+                    recording.push(*key);
+                }
+                false
+            }
         }
     }
 
     fn apply_messages(&mut self) {
         let messages = self
-            .pending_messages
+            .background
+            .messages
             .lock()
             .unwrap()
             .drain(..)
             .collect::<Vec<_>>();
+        let last_remote = self.last_remote;
         let mut should_save = false;
         for message in messages {
+            if self.accessible {
+                self.announcement = Some(message.describe());
+            }
             should_save |= message.apply(self);
         }
+        if let Some(mut batch) = self.batch.take() {
+            let chunk = batch.requests.len().min(BATCH_CHUNK);
+            for request in batch.requests.drain(..chunk).collect::<Vec<_>>() {
+                batch.changed |= self.insert_remote(request);
+            }
+            // changes made while a batch is added are saved along with it
+            batch.changed |= should_save;
+            should_save = false;
+            if batch.requests.is_empty() {
+                should_save = batch.changed;
+                if self.accessible {
+                    self.announcement = Some(format!("Added {} entries", batch.total));
+                }
+            } else {
+                self.batch = Some(batch);
+            }
+        }
         if should_save {
-            self.project.save();
+            self.save();
+        }
+        // messages may have shrunk any of the lists, a row past them would panic when drawn
+        self.clamp_selection();
+        if self.last_remote != last_remote
+            && self.project.config.follow_remote
+            && matches!(self.select_state, SelectState::Entry(_))
+        {
+            self.jump_to_remote();
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// What the view shows and what is selected in it.
+///
+/// States that point at entries hold their ids, which are resolved to indices into the lists when the state is drawn
+/// or handles a key, so that the selection stays on its entry whatever changes the lists in the meantime, e.g. remote
+/// requests, resurfacing snoozed entries, or moving entries between tabs. An id that is no longer in its list selects
+/// the list's first entry, and a prompt about an entry that is gone goes back to the main list.
+#[derive(Debug, Clone)]
 enum SelectState {
-    Entry(usize), // the usize will always be the index of the entry in the project, except if the project is empty, in which case it will be 0
-    Archive(usize),
+    // the id of the selected main entry, an id that isn't in the list, e.g. 0 when the list is empty, selects its first
+    // entry
+    Entry(u64),
+    Archive(u64),
     Drag {
-        dragged_entry_idx: usize,
+        dragged_id: u64,
         new_position: usize,
+        // dragging on the board moves the entry to another column rather than another position, this is the column
+        column: Option<usize>,
     },
-}
-
-impl SelectState {
-    fn on_event(
-        &self,
-        event: Event,
-        project: &mut Project,
-        clipboard: &Option<RefCell<ClipboardContext>>,
-    ) -> OnEvent {
-        if let Event::Key(KeyEvent {
-            code: KeyCode::Char('q'),
+    Snoozed(u64),
+    // prompting for a point in time to apply to the entry with `id`
+    TimePrompt {
+        id: u64,
+        input: String,
+        target: TimeTarget,
+    },
+    // choosing an entry to add to (or remove from) the see-also references of the entry with source_id
+    Link {
+        source_id: u64,
+        target_id: u64,
+    },
+    // choosing which of the selected entry's references to go to
+    FollowLink {
+        from: Box<SelectState>,
+        targets: Vec<u64>,
+        choice: usize,
+    },
+    // the recently opened entries
+    History(usize),
+    // the starred entries, wherever they are
+    Starred(usize),
+    // the entries waiting in the inbox, to be kept in the list or discarded
+    Inbox(usize),
+    // walking through the entries of `queue` one at a time from `pos`, deciding what to do with each
+    Triage {
+        queue: Vec<u64>,
+        pos: usize,
+        // the kind and text of what is being typed for the current entry
+        prompt: Option<(TriagePrompt, String)>,
+    },
+    // typing `<field>=<value>` to set a custom field of the entry with `id`, an empty value removes the field
+    FieldPrompt {
+        id: u64,
+        input: String,
+    },
+    // typing an alias or a query of main entries to open, `confirming` is the number of matches once Enter found more
+    // than can be opened without asking, `id` is the entry selected when it was opened
+    Palette {
+        id: u64,
+        input: String,
+        confirming: Option<usize>,
+    },
+    // editing the entry with `id`, `fields` are its name, its link, then a line per metadata string, the last one empty
+    // to add another on
+    Edit {
+        id: u64,
+        fields: Vec<String>,
+        focus: usize,
+    },
+    // editing the notes of the entry with `id` a line at a time, typing goes to the end of the line at `row`
+    Notes {
+        id: u64,
+        lines: Vec<String>,
+        row: usize,
+    },
+    // choosing a new target for the broken link of the main entry with `id`
+    Relink {
+        id: u64,
+        candidates: Vec<Candidate>,
+        choice: usize,
+    },
+    // the board layout, with a column per section
+    Board {
+        column: usize,
+        row: usize,
+    },
+    // a month grid of when entries were added or are due, with a day selected
+    Calendar(NaiveDate),
+    // the main entries added or due on a day
+    Day {
+        day: NaiveDate,
+        row: usize,
+    },
+    // due and snoozed entries of the coming week, the usize is the index of the selected item
+    Agenda(usize),
+    // entries listed by providers
+    Provided(usize),
+    // the main entries matching the query bound to a view key
+    View {
+        key: String,
+        row: usize,
+    },
+    // the main entries with a tag, Left and Right go through the other tags
+    Tag {
+        tag: String,
+        row: usize,
+    },
+    // the journal of saved changes, latest first, as it was read when it was opened
+    Journal {
+        events: Vec<JournalEvent>,
+        row: usize,
+    },
+    // the main entries whose name, link, or metadata contain `input`, narrowed as it is typed
+    Search {
+        input: String,
+        row: usize,
+        // whether keys are typed into the search, rather than act on the selected entry
+        typing: bool,
+    },
+    // moving the match at row `from` of the search to another row
+    SearchDrag {
+        input: String,
+        from: usize,
+        row: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TimeTarget {
+    Snooze,
+    Due,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TriagePrompt {
+    Tag,
+    Snooze,
+}
+
+impl TriagePrompt {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Tag => "Tag",
+            Self::Snooze => "Snooze until",
+        }
+    }
+}
+
+impl TimeTarget {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Snooze => "Snooze until",
+            Self::Due => "Due at",
+        }
+    }
+}
+
+impl SelectState {
+    /// the main list with the entry at `idx` selected
+    fn entry(project: &Project, idx: usize) -> Self {
+        Self::Entry(id_at(&project.entries, idx))
+    }
+
+    /// the archive with the entry at `idx` selected
+    fn archived(project: &Project, idx: usize) -> Self {
+        Self::Archive(id_at(&project.archive, idx))
+    }
+
+    /// the snoozed entries with the entry at `idx` selected
+    fn snoozed(project: &Project, idx: usize) -> Self {
+        Self::Snoozed(id_at(&project.snoozed, idx))
+    }
+
+    /// whether the state consumes typed characters, in which case they should not be treated as commands
+    fn is_text_input(&self) -> bool {
+        matches!(
+            self,
+            Self::TimePrompt { .. }
+                | Self::FieldPrompt { .. }
+                | Self::Palette { .. }
+                | Self::Edit { .. }
+                | Self::Notes { .. }
+                | Self::Search { typing: true, .. }
+                | Self::Triage {
+                    prompt: Some(..),
+                    ..
+                }
+        )
+    }
+
+    /// whether view keys may switch away from the state, states in the middle of an action keep the keys to themselves
+    fn switches_views(&self) -> bool {
+        !self.is_text_input()
+            && !matches!(
+                self,
+                Self::Drag { .. }
+                    | Self::Link { .. }
+                    | Self::FollowLink { .. }
+                    | Self::Relink { .. }
+                    | Self::SearchDrag { .. }
+                    | Self::Triage { .. }
+            )
+    }
+
+    /// the entries to display, the index of the highlighted one, and the list's title
+    fn list_view<'a>(&self, project: &'a Project) -> (Cow<'a, [Entry]>, usize, Cow<'static, str>) {
+        match self {
+            Self::Entry(id)
+            | Self::TimePrompt { id, .. }
+            | Self::FieldPrompt { id, .. }
+            | Self::Palette { id, .. }
+            | Self::Edit { id, .. }
+            | Self::Notes { id, .. }
+            | Self::Relink { id, .. } => (
+                Cow::Borrowed(project.entries.as_slice()),
+                idx_of(&project.entries, *id),
+                entries_title(project),
+            ),
+            Self::Archive(id) => (
+                Cow::Borrowed(project.archive.as_slice()),
+                idx_of(&project.archive, *id),
+                "Archive".into(),
+            ),
+            Self::Drag {
+                dragged_id,
+                column: Some(column),
+                ..
+            } => (
+                Cow::Owned(project.get(*dragged_id).cloned().into_iter().collect()),
+                0,
+                format!(
+                    "Move to {}",
+                    column_title(project, &board_columns(project)[*column])
+                )
+                .into(),
+            ),
+            Self::Drag {
+                dragged_id,
+                new_position,
+                column: None,
+            } => (
+                Cow::Owned(drag_order(project, *dragged_id, *new_position)),
+                *new_position,
+                entries_title(project),
+            ),
+            Self::Snoozed(id) => (
+                Cow::Borrowed(project.snoozed.as_slice()),
+                idx_of(&project.snoozed, *id),
+                "Snoozed".into(),
+            ),
+            Self::Link { target_id, .. } => (
+                Cow::Borrowed(project.entries.as_slice()),
+                idx_of(&project.entries, *target_id),
+                "Link to entry".into(),
+            ),
+            Self::FollowLink { from, .. } => from.list_view(project),
+            // a single column of the board, as the board can't be presented linearly
+            Self::Board { column, row } => {
+                let section = &board_columns(project)[*column];
+                let entries = column_indices(project, section)
+                    .into_iter()
+                    .map(|idx| project.entries[idx].clone())
+                    .collect();
+                (
+                    Cow::Owned(entries),
+                    *row,
+                    column_title(project, section).into(),
+                )
+            }
+            Self::Calendar(day) => (
+                Cow::Owned(entries_on(project, *day)),
+                usize::MAX,
+                calendar_summary(project, *day).into(),
+            ),
+            Self::Provided(selected_idx) => (
+                Cow::Borrowed(project.provided.as_slice()),
+                *selected_idx,
+                "Provided".into(),
+            ),
+            Self::Starred(selected_idx) => (
+                Cow::Owned(project.starred().into_iter().cloned().collect()),
+                *selected_idx,
+                "Starred".into(),
+            ),
+            Self::Inbox(selected_idx) => (
+                Cow::Owned(
+                    project
+                        .inbox()
+                        .into_iter()
+                        .map(|idx| project.entries[idx].clone())
+                        .collect(),
+                ),
+                *selected_idx,
+                "Inbox".into(),
+            ),
+            // the entries still to triage, the current one first
+            Self::Triage { queue, pos, .. } => {
+                let entries = queue[*pos..]
+                    .iter()
+                    .filter_map(|id| main_idx(project, *id))
+                    .map(|idx| project.entries[idx].clone())
+                    .collect::<Vec<_>>();
+                let title = format!("Triage, {} left", entries.len());
+                (Cow::Owned(entries), 0, title.into())
+            }
+            Self::History(selected_idx) => (
+                Cow::Owned(
+                    project
+                        .recently_opened()
+                        .into_iter()
+                        .map(|(entry, _)| entry.clone())
+                        .collect(),
+                ),
+                *selected_idx,
+                "Recently opened".into(),
+            ),
+            Self::Agenda(row) => (
+                Cow::Owned(
+                    agenda::items(project, Utc::now(), DEFAULT_AGENDA_DAYS)
+                        .into_iter()
+                        .map(|item| item.entry.clone())
+                        .collect(),
+                ),
+                *row,
+                "Agenda".into(),
+            ),
+            Self::Day { day, row } => (
+                Cow::Owned(entries_on(project, *day)),
+                *row,
+                format!("Added or due on {}", day.format("%x")).into(),
+            ),
+            Self::View { key, row } => (
+                Cow::Owned(view_entries(project, key).into_iter().cloned().collect()),
+                *row,
+                view_title(project, key).into(),
+            ),
+            Self::Tag { tag, row } => (
+                Cow::Owned(tagged_entries(project, tag).into_iter().cloned().collect()),
+                *row,
+                tag_title(project, tag).into(),
+            ),
+            Self::Journal { events, row } => (
+                Cow::Owned(
+                    events
+                        .iter()
+                        .filter_map(|event| event.after.as_ref().or(event.before.as_ref()))
+                        .map(|snapshot| snapshot.entry.clone())
+                        .collect(),
+                ),
+                *row,
+                "Journal".into(),
+            ),
+            Self::Search { input, row, .. } => (
+                Cow::Owned(
+                    search_matches(project, input)
+                        .into_iter()
+                        .map(|idx| project.entries[idx].clone())
+                        .collect(),
+                ),
+                *row,
+                search_title(input).into(),
+            ),
+            Self::SearchDrag { input, from, row } => {
+                let mut entries = search_matches(project, input)
+                    .into_iter()
+                    .map(|idx| project.entries[idx].clone())
+                    .collect::<Vec<_>>();
+                if *from < entries.len() {
+                    let dragged = entries.remove(*from);
+                    entries.insert((*row).min(entries.len()), dragged);
+                }
+                (Cow::Owned(entries), *row, search_title(input).into())
+            }
+        }
+    }
+
+    /// the rows to display for the entries of `list_view`, only the main entries are shown as a tree
+    fn rows(&self, entries: &[Entry], sort: tree::Sort) -> Vec<tree::Row> {
+        match self {
+            Self::Archive(..)
+            | Self::Snoozed(..)
+            | Self::Board { .. }
+            | Self::Drag {
+                column: Some(_), ..
+            }
+            | Self::Calendar(..)
+            | Self::Day { .. }
+            | Self::Agenda(..)
+            | Self::History(..)
+            | Self::Starred(..)
+            | Self::Inbox(..)
+            | Self::Triage { .. }
+            | Self::View { .. }
+            | Self::Tag { .. }
+            | Self::Journal { .. }
+            | Self::Search { .. }
+            | Self::SearchDrag { .. }
+            | Self::Provided(..) => (0..entries.len())
+                .map(|idx| tree::Row {
+                    idx,
+                    depth: 0,
+                    has_children: false,
+                })
+                .collect(),
+            Self::FollowLink { from, .. } => from.rows(entries, sort),
+            _ => tree::rows(entries, sort),
+        }
+    }
+
+    /// the entry whose details are displayed
+    fn selected_entry<'a>(&self, project: &'a Project) -> Option<&'a Entry> {
+        match self {
+            Self::Entry(id) => project.entries.get(idx_of(&project.entries, *id)),
+            Self::TimePrompt { id, .. }
+            | Self::FieldPrompt { id, .. }
+            | Self::Palette { id, .. }
+            | Self::Edit { id, .. }
+            | Self::Notes { id, .. }
+            | Self::Relink { id, .. }
+            | Self::Link { source_id: id, .. }
+            | Self::Drag { dragged_id: id, .. } => {
+                main_idx(project, *id).map(|idx| &project.entries[idx])
+            }
+            Self::Archive(id) => project.archive.get(idx_of(&project.archive, *id)),
+            Self::Snoozed(id) => project.snoozed.get(idx_of(&project.snoozed, *id)),
+            Self::FollowLink { from, .. } => from.selected_entry(project),
+            Self::Board { column, row } => {
+                let section = &board_columns(project)[*column];
+                column_indices(project, section)
+                    .get(*row)
+                    .map(|idx| &project.entries[*idx])
+            }
+            Self::Calendar(..) => None,
+            Self::Provided(idx) => project.provided.get(*idx),
+            Self::Starred(idx) => project.starred().get(*idx).copied(),
+            Self::Inbox(idx) => project
+                .inbox()
+                .get(*idx)
+                .map(|entry_idx| &project.entries[*entry_idx]),
+            Self::Triage { queue, pos, .. } => {
+                main_idx(project, queue[*pos]).map(|idx| &project.entries[idx])
+            }
+            Self::History(idx) => project.recently_opened().get(*idx).map(|(entry, _)| *entry),
+            Self::Agenda(row) => agenda::items(project, Utc::now(), DEFAULT_AGENDA_DAYS)
+                .get(*row)
+                .map(|item| item.entry),
+            Self::Day { day, row } => {
+                let id = entries_on(project, *day).get(*row)?.id;
+                project.get(id)
+            }
+            Self::View { key, row } => view_entries(project, key).get(*row).copied(),
+            Self::Tag { tag, row } => tagged_entries(project, tag).get(*row).copied(),
+            Self::Journal { events, row } => project.get(events.get(*row)?.entry_id),
+            Self::Search { input, row, .. }
+            | Self::SearchDrag {
+                input, from: row, ..
+            } => search_matches(project, input)
+                .get(*row)
+                .map(|idx| &project.entries[*idx]),
+        }
+    }
+
+    /// the board, with the entry at `entry_idx` selected if there is one
+    fn board_at(project: &Project, entry_idx: usize) -> Self {
+        let Some(entry) = project.entries.get(entry_idx) else {
+            return Self::Board { column: 0, row: 0 };
+        };
+        let columns = board_columns(project);
+        let column = columns
+            .iter()
+            .position(|column| column.holds(project, entry))
+            .unwrap_or(0);
+        let row = column_indices(project, &columns[column])
+            .iter()
+            .position(|idx| *idx == entry_idx)
+            .unwrap_or(0);
+        Self::Board { column, row }
+    }
+
+    /// selects the entry with `id`, expanding its parents if it is nested
+    fn jump_to(project: &mut Project, id: u64) -> Self {
+        match project.locate(id) {
+            Some(EntryLocation::Entries(idx)) => {
+                project.reveal(idx);
+                Self::Entry(id)
+            }
+            Some(EntryLocation::Archive(_)) => Self::Archive(id),
+            Some(EntryLocation::Snoozed(_)) => Self::Snoozed(id),
+            None => Self::Entry(0),
+        }
+    }
+
+    /// the triage of `queue` from `pos`, skipping entries that left the list, or the list once none are left
+    fn triage(project: &Project, queue: Vec<u64>, pos: usize) -> Self {
+        match queue[pos.min(queue.len())..]
+            .iter()
+            .position(|id| main_idx(project, *id).is_some())
+        {
+            Some(skipped) => Self::Triage {
+                queue,
+                pos: pos + skipped,
+                prompt: None,
+            },
+            None => Self::Entry(0),
+        }
+    }
+
+    /// moves on to the next entry of the triage after a decision about the current one
+    fn triage_next(project: &Project, queue: &[u64], pos: usize) -> OnEvent {
+        match Self::triage(project, queue.to_vec(), pos + 1) {
+            Self::Entry(id) => {
+                OnEvent::with_saving(Self::Entry(id)).with_notice("Triage done".to_string())
+            }
+            next => OnEvent::with_saving(next),
+        }
+    }
+
+    /// goes to the see-also references of the selected entry, asking which one if there are several
+    fn follow_links(&self, project: &mut Project) -> OnEvent {
+        let Some(entry) = self.selected_entry(project) else {
+            return OnEvent::ignore();
+        };
+        let targets = entry
+            .see_also
+            .iter()
+            .copied()
+            .filter(|id| project.locate(*id).is_some())
+            .collect::<Vec<_>>();
+        match targets.as_slice() {
+            [] => OnEvent::ignore(),
+            [target] => OnEvent::with_saving(Self::jump_to(project, *target)),
+            _ => OnEvent::without_saving(Self::FollowLink {
+                from: Box::new(self.clone()),
+                targets,
+                choice: 0,
+            }),
+        }
+    }
+
+    fn on_event(
+        &self,
+        event: Event,
+        project: &mut Project,
+        clipboard: &Option<RefCell<ClipboardContext>>,
+        background: &BackgroundWork,
+    ) -> OnEvent {
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char('q'),
+            ..
+        }) = event
+        {
+            if !self.is_text_input() {
+                return OnEvent::exit();
+            }
+        }
+        if let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
             ..
         }) = event
         {
-            return OnEvent::exit();
+            if let Some(key) = view_key_name(code) {
+                if self.switches_views() && project.config.view_keys.contains_key(&key) {
+                    // pressing the key of the current view goes back to all entries
+                    return OnEvent::without_saving(match self {
+                        Self::View { key: current, .. } if *current == key => Self::Entry(0),
+                        _ => Self::View { key, row: 0 },
+                    });
+                }
+            }
         }
         match self {
-            Self::Entry(selected_idx) => {
-                let selected_idx = *selected_idx;
+            Self::Entry(selected_id) => {
+                let selected_idx = idx_of(&project.entries, *selected_id);
                 match event {
+                    // a sorted list shows the entries in an order of its own, moving them only changes the manual one
                     Event::Key(KeyEvent {
-                        code: KeyCode::Up,
-                        kind: KeyEventKind::Press,
-                        ..
-                    }) if !project.entries.is_empty() && selected_idx > 0 => {
-                        OnEvent::without_saving(Self::Entry(selected_idx - 1))
-                    }
-                    Event::Key(KeyEvent {
-                        code: KeyCode::Down,
+                        code,
+                        modifiers,
                         kind: KeyEventKind::Press,
                         ..
-                    }) if !project.entries.is_empty()
-                        && selected_idx < project.entries.len() - 1 =>
+                    }) if project.sort != tree::Sort::Manual
+                        && (code == KeyCode::Char('d')
+                            || matches!(code, KeyCode::Up | KeyCode::Down)
+                                && modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::SHIFT)) =>
                     {
-                        OnEvent::without_saving(Self::Entry(selected_idx + 1))
+                        OnEvent::ignore().with_notice(format!(
+                            "The list is sorted by {}, press s until it is in manual order to move entries",
+                            project.sort.label()
+                        ))
                     }
                     Event::Key(KeyEvent {
-                        code: KeyCode::Delete,
+                        code: KeyCode::Char('s'),
+                        modifiers: KeyModifiers::NONE,
                         kind: KeyEventKind::Press,
                         ..
-                    }) if !project.entries.is_empty() => {
-                        project.archive_entry(selected_idx);
-                        let new_idx = if !project.entries.is_empty()
-                            && selected_idx == project.entries.len()
-                        {
-                            selected_idx - 1
-                        } else {
-                            selected_idx
-                        };
-                        OnEvent::with_saving(Self::Entry(new_idx))
+                    }) => {
+                        project.sort = project.sort.next();
+                        OnEvent::without_saving(Self::entry(project, selected_idx))
                     }
+                    // moving the entry a single place among its siblings, as a drag of one step would
                     Event::Key(KeyEvent {
-                        code: KeyCode::Char('a'),
-                        kind: KeyEventKind::Press,
-                        ..
-                    }) if !project.archive.is_empty() => OnEvent::without_saving(Self::Archive(0)),
-                    Event::Key(KeyEvent {
-                        code: KeyCode::Char('d'),
+                        code: code @ (KeyCode::Up | KeyCode::Down),
+                        modifiers,
                         kind: KeyEventKind::Press,
                         ..
-                    }) if !project.entries.is_empty() => OnEvent::without_saving(Self::Drag {
-                        dragged_entry_idx: selected_idx,
-                        new_position: selected_idx,
-                    }),
+                    }) if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::SHIFT)
+                        && !project.entries.is_empty() =>
+                    {
+                        tree::sibling(&project.entries, selected_idx, code == KeyCode::Down)
+                            .map_or_else(OnEvent::ignore, |new_position| {
+                                project.move_entry(selected_idx, new_position);
+                                OnEvent::with_saving(Self::entry(project, new_position))
+                            })
+                    }
                     Event::Key(KeyEvent {
-                        code: KeyCode::Home,
+                        code: KeyCode::Up,
                         kind: KeyEventKind::Press,
                         ..
-                    }) => OnEvent::without_saving(Self::Entry(0)),
+                    }) if !project.entries.is_empty() => {
+                        tree::step(&project.entries, selected_idx, false, project.sort)
+                            .map_or_else(OnEvent::ignore, |idx| {
+                                OnEvent::without_saving(Self::entry(project, idx))
+                            })
+                    }
                     Event::Key(KeyEvent {
-                        code: KeyCode::End,
+                        code: KeyCode::Down,
                         kind: KeyEventKind::Press,
                         ..
                     }) if !project.entries.is_empty() => {
-                        OnEvent::without_saving(Self::Entry(project.entries.len() - 1))
+                        tree::step(&project.entries, selected_idx, true, project.sort)
+                            .map_or_else(OnEvent::ignore, |idx| {
+                                OnEvent::without_saving(Self::entry(project, idx))
+                            })
                     }
                     Event::Key(KeyEvent {
-                        code: KeyCode::Enter,
+                        code: KeyCode::Right,
                         kind: KeyEventKind::Press,
-                        modifiers,
                         ..
                     }) if !project.entries.is_empty() => {
-                        let entry = &project.entries[selected_idx];
-                        if modifiers.contains(KeyModifiers::CONTROL) {
-                            if let Ok(Some(pref)) = entry
-                                .link
-                                .preferred_file(project.config.preferred_suffixes.iter())
-                            {
-                                pref.open();
-                            } else {
-                                entry.link.explore()
-                            }
+                        if project.entries[selected_idx].collapsed {
+                            project.set_collapsed(selected_idx, false);
+                            OnEvent::with_saving(Self::entry(project, selected_idx))
                         } else {
-                            entry.link.explore()
-                        };
-                        OnEvent::ignore()
+                            tree::first_child(&project.entries, selected_idx)
+                                .map_or_else(OnEvent::ignore, |idx| {
+                                    OnEvent::without_saving(Self::entry(project, idx))
+                                })
+                        }
                     }
                     Event::Key(KeyEvent {
-                        code: KeyCode::Char('v'),
-                        modifiers: KeyModifiers::CONTROL,
+                        code: KeyCode::Left,
                         kind: KeyEventKind::Press,
                         ..
-                    }) => {
-                        if let Some(clipboard) = &clipboard {
-                            if let Ok(contents) = clipboard.borrow_mut().get_contents() {
-                                let link = Link::from(contents.as_str());
-                                let name = link.infer_name();
-                                let request = InsertRequest {
-                                    name,
-                                    link,
-                                    metadata: Vec::new(),
-                                };
-                                let new_idx = if project.entries.is_empty() {
-                                    0
-                                } else {
-                                    selected_idx + 1
-                                };
-                                project.insert_entry_at(request.into(), new_idx);
-                                OnEvent::with_saving(Self::Entry(new_idx))
-                            } else {
-                                OnEvent::ignore()
-                            }
+                    }) if !project.entries.is_empty() => {
+                        if !project.entries[selected_idx].collapsed
+                            && tree::first_child(&project.entries, selected_idx).is_some()
+                        {
+                            project.set_collapsed(selected_idx, true);
+                            OnEvent::with_saving(Self::entry(project, selected_idx))
                         } else {
-                            OnEvent::ignore()
+                            tree::parent(&project.entries, selected_idx)
+                                .map_or_else(OnEvent::ignore, |idx| {
+                                    OnEvent::without_saving(Self::entry(project, idx))
+                                })
                         }
                     }
-                    _ => OnEvent::ignore(),
-                }
-            }
-            Self::Archive(selected_idx) => {
-                let selected_idx = *selected_idx;
-                match event {
                     Event::Key(KeyEvent {
-                        code: KeyCode::Up,
+                        code: KeyCode::Char('>'),
                         kind: KeyEventKind::Press,
                         ..
-                    }) if selected_idx > 0 => {
-                        OnEvent::without_saving(Self::Archive(selected_idx - 1))
+                    }) if !project.entries.is_empty() => {
+                        if project.indent(selected_idx) {
+                            OnEvent::with_saving(Self::entry(project, selected_idx))
+                        } else {
+                            OnEvent::ignore()
+                        }
                     }
                     Event::Key(KeyEvent {
-                        code: KeyCode::Down,
+                        code: KeyCode::Char('<'),
                         kind: KeyEventKind::Press,
                         ..
-                    }) if selected_idx < project.archive.len() - 1 => {
-                        OnEvent::without_saving(Self::Archive(selected_idx + 1))
-                    }
+                    }) if !project.entries.is_empty() => project
+                        .outdent(selected_idx)
+                        .map_or_else(OnEvent::ignore, |idx| {
+                            OnEvent::with_saving(Self::entry(project, idx))
+                        }),
                     Event::Key(KeyEvent {
                         code: KeyCode::Delete,
                         kind: KeyEventKind::Press,
                         ..
-                    }) => {
-                        project.remove_from_archive(selected_idx);
-                        OnEvent::with_saving(if project.archive.is_empty() {
-                            Self::Entry(0)
-                        } else if selected_idx == project.archive.len() {
-                            Self::Archive(selected_idx - 1)
-                        } else {
-                            Self::Archive(selected_idx)
-                        })
+                    }) if !project.entries.is_empty() => {
+                        // keep the selection on the same row
+                        let row = tree::rows(&project.entries, project.sort)
+                            .iter()
+                            .position(|row| row.idx == selected_idx)
+                            .unwrap_or(0);
+                        project.archive_entry(selected_idx);
+                        let rows = tree::rows(&project.entries, project.sort);
+                        let new_idx = rows.get(row).or(rows.last()).map_or(0, |row| row.idx);
+                        OnEvent::with_saving(Self::entry(project, new_idx))
                     }
                     Event::Key(KeyEvent {
                         code: KeyCode::Char('a'),
                         kind: KeyEventKind::Press,
                         ..
-                    }) => OnEvent::without_saving(Self::Entry(0)),
+                    }) if !project.archive.is_empty() => OnEvent::without_saving(Self::Archive(0)),
                     Event::Key(KeyEvent {
-                        code: KeyCode::Char('r'),
+                        code: KeyCode::Char('S'),
                         kind: KeyEventKind::Press,
                         ..
-                    }) => {
-                        project.restore_from_archive(selected_idx);
-                        OnEvent::with_saving(Self::Entry(0))
+                    }) if !project.entries.is_empty() => {
+                        let entry = &project.entries[selected_idx];
+                        let Link::Url(url) = &entry.link else {
+                            return OnEvent::ignore()
+                                .with_notice("Only url entries can be snapshotted".to_string());
+                        };
+                        let (id, url, root) = (entry.id, url.clone(), project.root.clone());
+                        let net = project.config.net();
+                        background.spawn(move || {
+                            Some(ListenerMessages::Snapshot {
+                                id,
+                                path: snapshot::take(&root, &url, &net),
+                            })
+                        });
+                        OnEvent::ignore().with_notice("Saving snapshot...".to_string())
                     }
                     Event::Key(KeyEvent {
-                        code: KeyCode::Home,
+                        code: KeyCode::Char('+'),
                         kind: KeyEventKind::Press,
                         ..
-                    }) => OnEvent::without_saving(Self::Archive(0)),
+                    }) if !project.entries.is_empty() => {
+                        let suggested = project
+                            .config
+                            .suggested_tags(&project.entries[selected_idx]);
+                        if suggested.is_empty() {
+                            return OnEvent::ignore();
+                        }
+                        for tag in &suggested {
+                            project.add_tag(selected_idx, tag);
+                        }
+                        OnEvent::with_saving(Self::entry(project, selected_idx))
+                            .with_notice(format!("Tagged {}", suggested.join(", ")))
+                    }
                     Event::Key(KeyEvent {
-                        code: KeyCode::End,
+                        code: KeyCode::Char('N'),
                         kind: KeyEventKind::Press,
                         ..
-                    }) => OnEvent::without_saving(Self::Archive(project.entries.len() - 1)),
+                    }) => {
+                        if !project.config.network.enabled {
+                            return OnEvent::ignore().with_notice(
+                                "Network requests are disabled in flist.toml".to_string(),
+                            );
+                        }
+                        let links = project
+                            .entries
+                            .iter()
+                            .filter(|entry| matches!(entry.link, Link::Url(_) | Link::Feed(_)))
+                            .map(|entry| (entry.id, entry.link.clone()))
+                            .collect::<Vec<_>>();
+                        if links.is_empty() {
+                            return OnEvent::ignore()
+                                .with_notice("No url entries to refresh".to_string());
+                        }
+                        let steps = project.config.name_steps.clone();
+                        if !background.refresh_titles(links, steps, project.config.net()) {
+                            return OnEvent::ignore()
+                                .with_notice("Titles are already being refreshed".to_string());
+                        }
+                        OnEvent::ignore()
+                    }
                     Event::Key(KeyEvent {
-                        code: KeyCode::Enter,
+                        code: KeyCode::Char('L'),
                         kind: KeyEventKind::Press,
-                        modifiers,
                         ..
                     }) if !project.entries.is_empty() => {
-                        let entry = &project.archive[selected_idx];
-                        if modifiers.contains(KeyModifiers::CONTROL) {
-                            if let Ok(Some(pref)) = entry
-                                .link
-                                .preferred_file(project.config.preferred_suffixes.iter())
-                            {
-                                pref.open();
-                            } else {
-                                entry.link.explore()
-                            }
-                        } else {
-                            entry.link.explore()
+                        let entry = &project.entries[selected_idx];
+                        let Link::Url(url) = &entry.link else {
+                            return OnEvent::ignore()
+                                .with_notice("Only url entries can be downloaded".to_string());
                         };
+                        background.download(
+                            entry.id,
+                            entry.name.clone(),
+                            url.clone(),
+                            project.root.clone(),
+                            project.config.downloads_dir.clone(),
+                            project.config.net(),
+                        );
                         OnEvent::ignore()
                     }
-                    _ => OnEvent::ignore(),
-                }
-            }
-            Self::Drag {
-                dragged_entry_idx,
-                new_position,
-            } => {
-                let dragged_entry_idx = *dragged_entry_idx;
-                let new_position = *new_position;
-                match event {
                     Event::Key(KeyEvent {
-                        code: KeyCode::Up,
+                        code: KeyCode::Char('d'),
                         kind: KeyEventKind::Press,
                         ..
-                    }) if new_position > 0 => OnEvent::without_saving(Self::Drag {
-                        dragged_entry_idx,
-                        new_position: new_position - 1,
+                    }) if !project.entries.is_empty() => OnEvent::without_saving(Self::Drag {
+                        dragged_id: project.entries[selected_idx].id,
+                        new_position: selected_idx,
+                        column: None,
                     }),
                     Event::Key(KeyEvent {
-                        code: KeyCode::Down,
+                        code: KeyCode::Char('z'),
                         kind: KeyEventKind::Press,
                         ..
-                    }) if new_position < project.entries.len() - 1 => {
-                        OnEvent::without_saving(Self::Drag {
-                            dragged_entry_idx,
-                            new_position: new_position + 1,
+                    }) if !project.entries.is_empty() => {
+                        OnEvent::without_saving(Self::TimePrompt {
+                            id: project.entries[selected_idx].id,
+                            input: String::new(),
+                            target: TimeTarget::Snooze,
                         })
                     }
                     Event::Key(KeyEvent {
-                        code: KeyCode::Home,
+                        code: KeyCode::Char('D'),
                         kind: KeyEventKind::Press,
                         ..
-                    }) => OnEvent::without_saving(Self::Drag {
-                        dragged_entry_idx,
-                        new_position: 0,
-                    }),
+                    }) if !project.entries.is_empty() => {
+                        OnEvent::without_saving(Self::TimePrompt {
+                            id: project.entries[selected_idx].id,
+                            input: String::new(),
+                            target: TimeTarget::Due,
+                        })
+                    }
                     Event::Key(KeyEvent {
-                        code: KeyCode::End,
+                        code: KeyCode::Char('F'),
                         kind: KeyEventKind::Press,
                         ..
-                    }) => OnEvent::without_saving(Self::Drag {
-                        dragged_entry_idx,
-                        new_position: project.entries.len() - 1,
-                    }),
+                    }) if !project.entries.is_empty() => {
+                        OnEvent::without_saving(Self::FieldPrompt {
+                            id: project.entries[selected_idx].id,
+                            input: String::new(),
+                        })
+                    }
                     Event::Key(KeyEvent {
-                        code: KeyCode::Enter,
+                        code: KeyCode::Char('Z'),
                         kind: KeyEventKind::Press,
                         ..
-                    }) => {
+                    }) if !project.snoozed.is_empty() => OnEvent::without_saving(Self::Snoozed(0)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('b'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::board_at(project, selected_idx)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('c'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Calendar(Local::now().date_naive())),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('A'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Agenda(0)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('R'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.config.providers.is_empty() => {
+                        OnEvent::without_saving(Self::Provided(0))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('m'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.entries.is_empty() && !project.config.sections.is_empty() => {
+                        let next = next_section(project, selected_idx);
+                        match project.set_section(selected_idx, next) {
+                            Ok(new_idx) => OnEvent::with_saving(Self::entry(project, new_idx)),
+                            Err(err) => OnEvent::ignore().with_notice(err.to_string()),
+                        }
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('*'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.entries.is_empty() => {
+                        project.toggle_star(project.entries[selected_idx].id);
+                        OnEvent::with_saving(Self::entry(project, selected_idx))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('p'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.entries.is_empty() => {
+                        project.toggle_pin(project.entries[selected_idx].id);
+                        OnEvent::with_saving(Self::entry(project, selected_idx))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('f'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.starred().is_empty() => {
+                        OnEvent::without_saving(Self::Starred(0))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('i'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.inbox().is_empty() => OnEvent::without_saving(Self::Inbox(0)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('W'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.entries.is_empty() => {
+                        let Link::Url(_) = &project.entries[selected_idx].link else {
+                            return OnEvent::ignore()
+                                .with_notice("Only url entries can be watched".to_string());
+                        };
+                        project.toggle_watch(selected_idx);
+                        OnEvent::with_saving(Self::entry(project, selected_idx))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('X'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.entries.is_empty() => {
+                        let entry = &project.entries[selected_idx];
+                        let Some(url) = entry.link.feed_url() else {
+                            return OnEvent::ignore()
+                                .with_notice("Only feed entries can be expanded".to_string());
+                        };
+                        let (id, url, net) = (entry.id, url.to_string(), project.config.net());
+                        background.spawn(move || {
+                            Some(ListenerMessages::Feed {
+                                id,
+                                items: feed::fetch(&url, &net).map(|feed| feed.items),
+                                expand: true,
+                            })
+                        });
+                        OnEvent::ignore().with_notice("Fetching feed...".to_string())
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('K'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if project
+                        .entries
+                        .get(selected_idx)
+                        .is_some_and(|entry| entry.ephemeral) =>
+                    {
+                        project.keep_temporary(selected_idx);
+                        OnEvent::with_saving(Self::entry(project, selected_idx))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('E'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.entries.is_empty() => {
+                        let entry = &project.entries[selected_idx];
+                        let fields = [entry.name.clone(), entry.link.as_str().to_string()]
+                            .into_iter()
+                            .chain(entry.metadata.iter().cloned())
+                            .chain([String::new()])
+                            .collect();
+                        OnEvent::without_saving(Self::Edit {
+                            id: entry.id,
+                            fields,
+                            focus: EDIT_NAME,
+                        })
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('n'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.entries.is_empty() => {
+                        let lines = project.entries[selected_idx]
+                            .notes
+                            .split('\n')
+                            .map(str::to_string)
+                            .collect::<Vec<_>>();
+                        OnEvent::without_saving(Self::Notes {
+                            id: project.entries[selected_idx].id,
+                            row: lines.len() - 1,
+                            lines,
+                        })
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('e'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.entries.is_empty() => {
+                        let Some(command) = &project.config.share_command else {
+                            return OnEvent::ignore()
+                                .with_notice("No share_command in flist.toml".to_string());
+                        };
+                        let entry = &project.entries[selected_idx];
+                        match share::share(command, &project.root, entry) {
+                            Ok(()) => {
+                                OnEvent::ignore().with_notice(format!("Shared {}", entry.name))
+                            }
+                            Err(err) => OnEvent::ignore().with_notice(err),
+                        }
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('T'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match Self::triage(project, triage_queue(project), 0) {
+                        Self::Entry(..) => OnEvent::ignore()
+                            .with_notice("Nothing in the inbox or added this week".to_string()),
+                        triage => OnEvent::without_saving(triage),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('h'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.recently_opened().is_empty() => {
+                        OnEvent::without_saving(Self::History(0))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('H'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let events = journal::read(&project.root)
+                            .into_iter()
+                            .rev()
+                            .collect::<Vec<_>>();
+                        if events.is_empty() {
+                            OnEvent::ignore().with_notice("The journal is empty".to_string())
+                        } else {
+                            OnEvent::without_saving(Self::Journal { events, row: 0 })
+                        }
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char(':'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Palette {
+                        id: *selected_id,
+                        input: String::new(),
+                        confirming: None,
+                    }),
+                    // starts at the first tag of the selected entry
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('t'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let tag = project
+                            .entries
+                            .get(selected_idx)
+                            .and_then(|entry| entry.tags.first().cloned())
+                            .or_else(|| entry_tags(project).into_iter().next());
+                        match tag {
+                            Some(tag) => OnEvent::without_saving(Self::Tag { tag, row: 0 }),
+                            None => {
+                                OnEvent::ignore().with_notice("No entries are tagged".to_string())
+                            }
+                        }
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('/'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.entries.is_empty() => OnEvent::without_saving(Self::Search {
+                        input: String::new(),
+                        row: 0,
+                        typing: true,
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('G'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.entries.is_empty() => {
+                        let Link::Directory(dir) = &project.entries[selected_idx].link else {
+                            return OnEvent::ignore();
+                        };
+                        match git::remote_url(Path::new(dir)) {
+                            Some(url) if remote_session(&project.config) => {
+                                let unopened = Unopened {
+                                    target: url,
+                                    reason: None,
+                                    remote: true,
+                                };
+                                OnEvent::ignore().with_unopened([unopened], clipboard)
+                            }
+                            Some(url) => {
+                                let unopened =
+                                    Link::Url(url.clone())
+                                        .explore()
+                                        .err()
+                                        .map(|reason| Unopened {
+                                            target: url,
+                                            reason: Some(reason),
+                                            remote: false,
+                                        });
+                                OnEvent::ignore().with_unopened(unopened, clipboard)
+                            }
+                            None => OnEvent::ignore()
+                                .with_notice("The directory has no git remote".to_string()),
+                        }
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('l'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if project.entries.len() > 1 => {
+                        let id = project.entries[selected_idx].id;
+                        OnEvent::without_saving(Self::Link {
+                            source_id: id,
+                            target_id: id,
+                        })
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('g'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => self.follow_links(project),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::entry(project,                         tree::first(&project.entries, project.sort).unwrap_or(0),)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::entry(project,                         tree::last(&project.entries, project.sort).unwrap_or(0),)),
+                    Event::Key(KeyEvent {
+                        code: code @ (KeyCode::Enter | KeyCode::Char('o')),
+                        kind: KeyEventKind::Press,
+                        modifiers,
+                        ..
+                    }) if !project.entries.is_empty() => {
+                        let entry = &project.entries[selected_idx];
+                        let entry_id = entry.id;
+                        if relink::is_broken(&entry.link) {
+                            let missing = entry.link.as_str().to_string();
+                            let roots = project.config.relink_roots.clone();
+                            let hash = entry.target_hash;
+                            background.spawn(move || {
+                                let candidates = relink::candidates(&missing, &roots, hash);
+                                Some(ListenerMessages::RelinkCandidates {
+                                    id: entry_id,
+                                    candidates,
+                                })
+                            });
+                            return OnEvent::ignore().with_notice(format!(
+                                "{} is missing, searching for it...",
+                                entry.link.as_str()
+                            ));
+                        }
+                        let opened =
+                            open_entry(entry, project, is_alternate_open(code, modifiers));
+                        project.opened(entry_id);
+                        OnEvent::ignore().with_unopened(opened.err(), clipboard)
+                    }
+                    // Alt+V pastes a temporary entry
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('v'),
+                        modifiers,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if modifiers == KeyModifiers::CONTROL || modifiers == KeyModifiers::ALT => {
+                        if let Some(clipboard) = &clipboard {
+                            if let Ok(contents) = clipboard.borrow_mut().get_contents() {
+                                let link = Link::from(contents.as_str());
+                                let name = if let Link::Url(url) | Link::Feed(url) = &link {
+                                    // fetching the title might take a while, we use the url until it arrives
+                                    let link = link.clone();
+                                    let steps = project.config.name_steps.clone();
+                                    let net = project.config.net();
+                                    background.spawn(move || {
+                                        let details = infer_url_details(&link, &steps, &net);
+                                        (details.name != link.as_str() || details.error.is_some())
+                                            .then(|| ListenerMessages::InferredName {
+                                                link: link.as_str().to_string(),
+                                                details,
+                                            })
+                                    });
+                                    url.clone()
+                                } else {
+                                    project.unique_name(link.infer_name(), &link)
+                                };
+                                let request = InsertRequest {
+                                    name,
+                                    link,
+                                    metadata: Vec::new(),
+                                    due: None,
+                                    section: None,
+                                    tags: Vec::new(),
+                                    temp: modifiers == KeyModifiers::ALT,
+                                };
+                                let entry = Entry::from(request);
+                                let suggested = project.config.suggested_tags(&entry);
+                                match project.capture(
+                                    entry,
+                                    InsertSource::Paste,
+                                    Some(selected_idx),
+                                ) {
+                                    Ok(new_idx) if !suggested.is_empty() => OnEvent::with_saving(
+                                        Self::entry(project, new_idx),
+                                    )
+                                    .with_notice(format!(
+                                        "Suggested tags: {}, + to add them",
+                                        suggested.join(", ")
+                                    )),
+                                    Ok(new_idx) => OnEvent::with_saving(Self::entry(project, new_idx)),
+                                    Err(err) => OnEvent::ignore().with_notice(err.to_string()),
+                                }
+                            } else {
+                                OnEvent::ignore()
+                            }
+                        } else {
+                            OnEvent::ignore()
+                        }
+                    }
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Archive(selected_id) => {
+                // an emptied archive has nothing left to act on
+                if project.archive.is_empty() {
+                    return OnEvent::without_saving(Self::Entry(0));
+                }
+                let selected_idx = idx_of(&project.archive, *selected_id);
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if selected_idx > 0 => {
+                        OnEvent::without_saving(Self::archived(project, selected_idx - 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if selected_idx < project.archive.len() - 1 => {
+                        OnEvent::without_saving(Self::archived(project, selected_idx + 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('*'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        project.toggle_star(project.archive[selected_idx].id);
+                        OnEvent::with_saving(Self::archived(project, selected_idx))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Delete,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        project.remove_from_archive(selected_idx);
+                        OnEvent::with_saving(if project.archive.is_empty() {
+                            Self::Entry(0)
+                        } else if selected_idx == project.archive.len() {
+                            Self::archived(project, selected_idx - 1)
+                        } else {
+                            Self::archived(project, selected_idx)
+                        })
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('a'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Entry(0)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('r'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        project.restore_from_archive(selected_idx);
+                        OnEvent::with_saving(Self::Entry(0))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('g'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => self.follow_links(project),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Archive(0)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        OnEvent::without_saving(Self::archived(project, project.archive.len() - 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: code @ (KeyCode::Enter | KeyCode::Char('o')),
+                        kind: KeyEventKind::Press,
+                        modifiers,
+                        ..
+                    }) if !project.entries.is_empty() => {
+                        let entry = &project.archive[selected_idx];
+                        let entry_id = entry.id;
+                        let opened = open_entry(entry, project, is_alternate_open(code, modifiers));
+                        project.opened(entry_id);
+                        OnEvent::ignore().with_unopened(opened.err(), clipboard)
+                    }
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Drag {
+                dragged_id,
+                new_position,
+                column: None,
+            } => {
+                let dragged_id = *dragged_id;
+                let Some(dragged_entry_idx) = main_idx(project, dragged_id) else {
+                    return OnEvent::without_saving(Self::Entry(0));
+                };
+                let new_position = *new_position;
+                match event {
+                    // entries are only dragged among their siblings, so positions are those of siblings in the
+                    // reordered list
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => tree::sibling(
+                        &drag_order(project, dragged_id, new_position),
+                        new_position,
+                        false,
+                    )
+                    .map_or_else(OnEvent::ignore, |new_position| {
+                        OnEvent::without_saving(Self::Drag {
+                            dragged_id,
+                            new_position,
+                            column: None,
+                        })
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => tree::sibling(
+                        &drag_order(project, dragged_id, new_position),
+                        new_position,
+                        true,
+                    )
+                    .map_or_else(OnEvent::ignore, |new_position| {
+                        OnEvent::without_saving(Self::Drag {
+                            dragged_id,
+                            new_position,
+                            column: None,
+                        })
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Drag {
+                        dragged_id,
+                        new_position: tree::sibling_bounds(
+                            &drag_order(project, dragged_id, new_position),
+                            new_position,
+                        )
+                        .0,
+                        column: None,
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Drag {
+                        dragged_id,
+                        new_position: tree::sibling_bounds(
+                            &drag_order(project, dragged_id, new_position),
+                            new_position,
+                        )
+                        .1,
+                        column: None,
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
                         project.move_entry(dragged_entry_idx, new_position);
-                        OnEvent::with_saving(Self::Entry(new_position))
+                        OnEvent::with_saving(Self::Entry(dragged_id))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Entry(dragged_id)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Snoozed(selected_id) => {
+                // resurfacing might have emptied the list
+                if project.snoozed.is_empty() {
+                    return OnEvent::without_saving(Self::Entry(0));
+                }
+                let selected_idx = idx_of(&project.snoozed, *selected_id);
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if selected_idx > 0 => {
+                        OnEvent::without_saving(Self::snoozed(project, selected_idx - 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if selected_idx < project.snoozed.len() - 1 => {
+                        OnEvent::without_saving(Self::snoozed(project, selected_idx + 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Snoozed(0)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        OnEvent::without_saving(Self::snoozed(project, project.snoozed.len() - 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('u'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        project.unsnooze(selected_idx);
+                        OnEvent::with_saving(Self::Entry(0))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('Z'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Entry(0)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let opened = open_entry(&project.snoozed[selected_idx], project, false);
+                        project.opened(project.snoozed[selected_idx].id);
+                        OnEvent::ignore().with_unopened(opened.err(), clipboard)
+                    }
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::TimePrompt { id, input, target } => {
+                let (id, target) = (*id, *target);
+                let Some(entry_idx) = main_idx(project, id) else {
+                    return OnEvent::without_saving(Self::Entry(0));
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char(c),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::TimePrompt {
+                        id,
+                        input: format!("{input}{c}"),
+                        target,
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Backspace,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let mut input = input.clone();
+                        input.pop();
+                        OnEvent::without_saving(Self::TimePrompt { id, input, target })
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let now = Utc::now();
+                        match (target, parse_when(input, now)) {
+                            (TimeTarget::Snooze, Some(until)) if until > now => {
+                                project.snooze_entry(entry_idx, until);
+                                OnEvent::with_saving(Self::entry(
+                                    project,
+                                    entry_idx.min(project.entries.len().saturating_sub(1)),
+                                ))
+                            }
+                            // an empty due date clears it
+                            (TimeTarget::Due, due) if due.is_some() || input.trim().is_empty() => {
+                                project.set_due(entry_idx, due);
+                                OnEvent::with_saving(Self::entry(project, entry_idx))
+                            }
+                            _ => OnEvent::ignore(),
+                        }
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::entry(project, entry_idx)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Edit { id, fields, focus } => {
+                let (id, focus) = (*id, *focus);
+                let Some(entry_idx) = main_idx(project, id) else {
+                    return OnEvent::without_saving(Self::Entry(0));
+                };
+                let edited = |fields: Vec<String>, focus: usize| {
+                    OnEvent::without_saving(Self::Edit { id, fields, focus })
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char(c),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let mut fields = fields.clone();
+                        fields[focus].push(c);
+                        // there is always an empty line to add metadata on
+                        if fields.last().is_some_and(|field| !field.is_empty()) {
+                            fields.push(String::new());
+                        }
+                        edited(fields, focus)
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Backspace,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let mut fields = fields.clone();
+                        fields[focus].pop();
+                        edited(fields, focus)
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Tab | KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if focus + 1 < fields.len() => edited(fields.clone(), focus + 1),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::BackTab | KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if focus > 0 => edited(fields.clone(), focus - 1),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let name = fields[EDIT_NAME].trim();
+                        let link = fields[EDIT_LINK].trim();
+                        if name.is_empty() || link.is_empty() {
+                            return OnEvent::ignore()
+                                .with_notice("Entries need a name and a link".to_string());
+                        }
+                        let metadata = fields[EDIT_LINK + 1..]
+                            .iter()
+                            .map(|field| field.trim())
+                            .filter(|field| !field.is_empty())
+                            .map(str::to_string)
+                            .collect();
+                        project.edit(entry_idx, name.to_string(), Link::from(link), metadata);
+                        OnEvent::with_saving(Self::entry(project, entry_idx))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::entry(project, entry_idx)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Notes { id, lines, row } => {
+                let (id, row) = (*id, *row);
+                let Some(entry_idx) = main_idx(project, id) else {
+                    return OnEvent::without_saving(Self::Entry(0));
+                };
+                let edited = |lines: Vec<String>, row: usize| {
+                    OnEvent::without_saving(Self::Notes { id, lines, row })
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('s'),
+                        modifiers: KeyModifiers::CONTROL,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let notes = lines.join("\n").trim_end().to_string();
+                        project.set_notes(entry_idx, notes);
+                        OnEvent::with_saving(Self::entry(project, entry_idx))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char(c),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let mut lines = lines.clone();
+                        lines[row].push(c);
+                        edited(lines, row)
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let mut lines = lines.clone();
+                        lines.insert(row + 1, String::new());
+                        edited(lines, row + 1)
+                    }
+                    // backspace on an empty line joins it to the one above
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Backspace,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if lines[row].is_empty() && row > 0 => {
+                        let mut lines = lines.clone();
+                        lines.remove(row);
+                        edited(lines, row - 1)
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Backspace,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let mut lines = lines.clone();
+                        lines[row].pop();
+                        edited(lines, row)
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row + 1 < lines.len() => edited(lines.clone(), row + 1),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row > 0 => edited(lines.clone(), row - 1),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::entry(project, entry_idx)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::FieldPrompt { id, input } => {
+                let id = *id;
+                let Some(entry_idx) = main_idx(project, id) else {
+                    return OnEvent::without_saving(Self::Entry(0));
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char(c),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::FieldPrompt {
+                        id,
+                        input: format!("{input}{c}"),
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Backspace,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let mut input = input.clone();
+                        input.pop();
+                        OnEvent::without_saving(Self::FieldPrompt { id, input })
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match input.split_once('=') {
+                        Some((name, value)) if !name.trim().is_empty() => {
+                            let value =
+                                (!value.trim().is_empty()).then(|| FieldValue::parse(value));
+                            project.set_field(entry_idx, name.trim(), value);
+                            OnEvent::with_saving(Self::entry(project, entry_idx))
+                        }
+                        _ => OnEvent::ignore().with_notice("Type <field>=<value>".to_string()),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::entry(project, entry_idx)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Palette {
+                id,
+                input,
+                confirming,
+            } => {
+                let id = *id;
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char(c),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Palette {
+                        id,
+                        input: format!("{input}{c}"),
+                        confirming: None,
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Backspace,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let mut input = input.clone();
+                        input.pop();
+                        OnEvent::without_saving(Self::Palette {
+                            id,
+                            input,
+                            confirming: None,
+                        })
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Tab,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        // completes the alias only when the typed prefix is unambiguous
+                        let mut completions = project
+                            .config
+                            .aliases
+                            .keys()
+                            .filter(|alias| alias.starts_with(input.as_str()));
+                        match (completions.next(), completions.next()) {
+                            (Some(alias), None) => OnEvent::without_saving(Self::Palette {
+                                id,
+                                input: alias.clone(),
+                                confirming: None,
+                            }),
+                            _ => OnEvent::ignore(),
+                        }
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !input.trim().is_empty() => {
+                        let query = Query::parse(query::expand(&project.config.aliases, input));
+                        let matching = query::matching(project, &query, false);
+                        // a query broader than meant shouldn't open a window for every entry
+                        if matching.len() > CONFIRM_OPEN_ABOVE && confirming.is_none() {
+                            return OnEvent::without_saving(Self::Palette {
+                                id,
+                                input: input.clone(),
+                                confirming: Some(matching.len()),
+                            });
+                        }
+                        let mut unopened = Vec::new();
+                        let matching = matching
+                            .into_iter()
+                            .map(|entry| {
+                                unopened.extend(open_entry(entry, project, false).err());
+                                entry.id
+                            })
+                            .collect::<Vec<_>>();
+                        let notice = match matching.len() {
+                            0 => format!("No entries match {input}"),
+                            count => format!("Opened {count} entries"),
+                        };
+                        for id in matching {
+                            project.opened(id);
+                        }
+                        OnEvent::without_saving(Self::Entry(id))
+                            .with_notice(notice)
+                            .with_unopened(unopened, clipboard)
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Entry(id)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Link {
+                source_id,
+                target_id,
+            } => {
+                let source_id = *source_id;
+                let Some(source_idx) = main_idx(project, source_id) else {
+                    return OnEvent::without_saving(Self::Entry(0));
+                };
+                let target_idx = idx_of(&project.entries, *target_id);
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => tree::step(&project.entries, target_idx, false, project.sort)
+                        .map_or_else(OnEvent::ignore, |target_idx| {
+                            OnEvent::without_saving(Self::Link {
+                                source_id,
+                                target_id: project.entries[target_idx].id,
+                            })
+                        }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => tree::step(&project.entries, target_idx, true, project.sort).map_or_else(
+                        OnEvent::ignore,
+                        |target_idx| {
+                            OnEvent::without_saving(Self::Link {
+                                source_id,
+                                target_id: project.entries[target_idx].id,
+                            })
+                        },
+                    ),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Link {
+                        source_id,
+                        target_id: id_at(
+                            &project.entries,
+                            tree::first(&project.entries, project.sort).unwrap_or(0),
+                        ),
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Link {
+                        source_id,
+                        target_id: id_at(
+                            &project.entries,
+                            tree::last(&project.entries, project.sort).unwrap_or(0),
+                        ),
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if target_idx != source_idx => {
+                        let target_id = project.entries[target_idx].id;
+                        project.toggle_reference(source_idx, target_id);
+                        OnEvent::with_saving(Self::entry(project, source_idx))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::entry(project, source_idx)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::FollowLink {
+                from,
+                targets,
+                choice,
+            } => {
+                let choice = *choice;
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if choice > 0 => OnEvent::without_saving(Self::FollowLink {
+                        from: from.clone(),
+                        targets: targets.clone(),
+                        choice: choice - 1,
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if choice < targets.len() - 1 => OnEvent::without_saving(Self::FollowLink {
+                        from: from.clone(),
+                        targets: targets.clone(),
+                        choice: choice + 1,
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::with_saving(Self::jump_to(project, targets[choice])),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(*from.clone()),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Relink {
+                id,
+                candidates,
+                choice,
+            } => {
+                let (id, choice) = (*id, *choice);
+                let Some(entry_idx) = main_idx(project, id) else {
+                    return OnEvent::without_saving(Self::Entry(0));
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if choice > 0 => OnEvent::without_saving(Self::Relink {
+                        id,
+                        candidates: candidates.clone(),
+                        choice: choice - 1,
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if choice < candidates.len() - 1 => OnEvent::without_saving(Self::Relink {
+                        id,
+                        candidates: candidates.clone(),
+                        choice: choice + 1,
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        project.relink(entry_idx, Link::from(candidates[choice].path.as_str()));
+                        OnEvent::with_saving(Self::entry(project, entry_idx))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::entry(project, entry_idx)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Board { column, row } => {
+                let column = *column;
+                let row = *row;
+                let columns = board_columns(project);
+                let column_len = |column: usize| column_indices(project, &columns[column]).len();
+                let selected = column_indices(project, &columns[column]).get(row).copied();
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row > 0 => OnEvent::without_saving(Self::Board {
+                        column,
+                        row: row - 1,
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row + 1 < column_len(column) => OnEvent::without_saving(Self::Board {
+                        column,
+                        row: row + 1,
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Left,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if column > 0 => OnEvent::without_saving(Self::Board {
+                        column: column - 1,
+                        row: row.min(column_len(column - 1).saturating_sub(1)),
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Right,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if column + 1 < columns.len() => OnEvent::without_saving(Self::Board {
+                        column: column + 1,
+                        row: row.min(column_len(column + 1).saturating_sub(1)),
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let Some(idx) = selected else {
+                            return OnEvent::ignore();
+                        };
+                        let opened = open_entry(&project.entries[idx], project, false);
+                        project.opened(project.entries[idx].id);
+                        OnEvent::ignore().with_unopened(opened.err(), clipboard)
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('d'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => selected.map_or_else(OnEvent::ignore, |entry_idx| {
+                        OnEvent::without_saving(Self::Drag {
+                            dragged_id: project.entries[entry_idx].id,
+                            new_position: entry_idx,
+                            column: Some(column),
+                        })
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('b'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::entry(project, selected.unwrap_or(0))),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Drag {
+                dragged_id,
+                new_position,
+                column: Some(column),
+            } => {
+                let (dragged_id, new_position, column) = (*dragged_id, *new_position, *column);
+                let Some(entry_idx) = main_idx(project, dragged_id) else {
+                    return OnEvent::without_saving(Self::Board { column, row: 0 });
+                };
+                let columns = board_columns(project);
+                let moved = |column: usize| {
+                    OnEvent::without_saving(Self::Drag {
+                        dragged_id,
+                        new_position,
+                        column: Some(column),
+                    })
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Left,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if column > 0 => moved(column - 1),
+                    // the other column gathers the sections missing from the config, entries can't be moved into it
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Right,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if columns
+                        .get(column + 1)
+                        .is_some_and(|next| next.section().is_some()) =>
+                    {
+                        moved(column + 1)
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match columns[column].section() {
+                        Some(section) => match project.set_section(entry_idx, section) {
+                            Ok(new_idx) => OnEvent::with_saving(Self::board_at(project, new_idx)),
+                            Err(err) => OnEvent::ignore().with_notice(err.to_string()),
+                        },
+                        None => OnEvent::without_saving(Self::board_at(project, entry_idx)),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::board_at(project, entry_idx)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Calendar(day) => {
+                let day = *day;
+                let moved = |new_day: Option<NaiveDate>| {
+                    new_day.map_or_else(OnEvent::ignore, |new_day| {
+                        OnEvent::without_saving(Self::Calendar(new_day))
+                    })
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Left,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => moved(day.pred_opt()),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Right,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => moved(day.succ_opt()),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => moved(day.checked_sub_signed(ChronoDuration::weeks(1))),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => moved(day.checked_add_signed(ChronoDuration::weeks(1))),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::PageUp,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => moved(day.checked_sub_months(Months::new(1))),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::PageDown,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => moved(day.checked_add_months(Months::new(1))),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => moved(Some(Local::now().date_naive())),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !entries_on(project, day).is_empty() => {
+                        OnEvent::without_saving(Self::Day { day, row: 0 })
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc | KeyCode::Char('c'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Entry(0)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Day { day, row } => {
+                let day = *day;
+                let row = *row;
+                let entries = entries_on(project, day);
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row > 0 => OnEvent::without_saving(Self::Day { day, row: row - 1 }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row + 1 < entries.len() => {
+                        OnEvent::without_saving(Self::Day { day, row: row + 1 })
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let Some(entry) = entries.get(row) else {
+                            return OnEvent::ignore();
+                        };
+                        let opened = open_entry(entry, project, false);
+                        project.opened(entry.id);
+                        OnEvent::ignore().with_unopened(opened.err(), clipboard)
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Calendar(day)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Provided(selected_idx) => {
+                let selected_idx = *selected_idx;
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if selected_idx > 0 => {
+                        OnEvent::without_saving(Self::Provided(selected_idx - 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if selected_idx + 1 < project.provided.len() => {
+                        OnEvent::without_saving(Self::Provided(selected_idx + 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Provided(0)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Provided(
+                        project.provided.len().saturating_sub(1),
+                    )),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let opened = project
+                            .provided
+                            .get(selected_idx)
+                            .and_then(|entry| open_entry(entry, project, false).err());
+                        OnEvent::ignore().with_unopened(opened, clipboard)
+                    }
+                    // copies the live entry into the main list
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('+'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match project.provided.get(selected_idx).cloned() {
+                        Some(mut entry) => {
+                            entry.provider = None;
+                            match project.insert_entry(entry) {
+                                Ok(()) => OnEvent::with_saving(Self::Provided(selected_idx)),
+                                Err(err) => OnEvent::ignore().with_notice(err.to_string()),
+                            }
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('R'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        refresh_providers(project, background);
+                        OnEvent::ignore()
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Entry(0)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Triage {
+                queue,
+                pos,
+                prompt: Some((prompt, input)),
+            } => {
+                let (pos, prompt) = (*pos, *prompt);
+                let Some(entry_idx) = main_idx(project, queue[pos]) else {
+                    return OnEvent::without_saving(Self::triage(project, queue.clone(), pos));
+                };
+                let with_input = |input: String| Self::Triage {
+                    queue: queue.clone(),
+                    pos,
+                    prompt: Some((prompt, input)),
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char(c),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(with_input(format!("{input}{c}"))),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Backspace,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let mut input = input.clone();
+                        input.pop();
+                        OnEvent::without_saving(with_input(input))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let now = Utc::now();
+                        match prompt {
+                            // tagging is not a decision, the entry stays current
+                            TriagePrompt::Tag if !input.trim().is_empty() => {
+                                project.add_tag(entry_idx, input.trim());
+                                OnEvent::with_saving(Self::Triage {
+                                    queue: queue.clone(),
+                                    pos,
+                                    prompt: None,
+                                })
+                            }
+                            TriagePrompt::Snooze => match parse_when(input, now) {
+                                Some(until) if until > now => {
+                                    project.snooze_entry(entry_idx, until);
+                                    Self::triage_next(project, queue, pos)
+                                }
+                                _ => OnEvent::ignore(),
+                            },
+                            _ => OnEvent::ignore(),
+                        }
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Triage {
+                        queue: queue.clone(),
+                        pos,
+                        prompt: None,
+                    }),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Triage {
+                queue,
+                pos,
+                prompt: None,
+            } => {
+                let pos = *pos;
+                let Some(entry_idx) = main_idx(project, queue[pos]) else {
+                    return OnEvent::without_saving(Self::triage(project, queue.clone(), pos));
+                };
+                let prompting = |prompt| Self::Triage {
+                    queue: queue.clone(),
+                    pos,
+                    prompt: Some((prompt, String::new())),
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let entry = &project.entries[entry_idx];
+                        let id = entry.id;
+                        let opened = open_entry(entry, project, false);
+                        project.opened(id);
+                        OnEvent::ignore().with_unopened(opened.err(), clipboard)
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('k'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        project.keep(entry_idx);
+                        Self::triage_next(project, queue, pos)
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('t'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(prompting(TriagePrompt::Tag)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('z'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(prompting(TriagePrompt::Snooze)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('m'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.config.sections.is_empty() => {
+                        let next = next_section(project, entry_idx);
+                        match project.set_section(entry_idx, next) {
+                            Ok(_) => OnEvent::with_saving(Self::Triage {
+                                queue: queue.clone(),
+                                pos,
+                                prompt: None,
+                            }),
+                            Err(err) => OnEvent::ignore().with_notice(err.to_string()),
+                        }
+                    }
+                    // archived entries leave the inbox, so restoring them doesn't bring them back for triage
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('a'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        project.keep(entry_idx);
+                        project.archive_entry(entry_idx);
+                        Self::triage_next(project, queue, pos)
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Right,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => Self::triage_next(project, queue, pos),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Left,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        // the closest earlier entry that is still in the list
+                        match queue[..pos]
+                            .iter()
+                            .rposition(|id| main_idx(project, *id).is_some())
+                        {
+                            Some(previous) => OnEvent::without_saving(Self::Triage {
+                                queue: queue.clone(),
+                                pos: previous,
+                                prompt: None,
+                            }),
+                            None => OnEvent::ignore(),
+                        }
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::entry(project, entry_idx)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Inbox(selected_idx) => {
+                let selected_idx = *selected_idx;
+                let inbox = project.inbox();
+                let selected = inbox.get(selected_idx).copied();
+                let len = inbox.len();
+                // the inbox after the selected entry left it, back to the list once it is empty
+                let after_triage = |project: &Project| {
+                    let len = project.inbox().len();
+                    if len == 0 {
+                        Self::Entry(0)
+                    } else {
+                        Self::Inbox(selected_idx.min(len - 1))
+                    }
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if selected_idx > 0 => {
+                        OnEvent::without_saving(Self::Inbox(selected_idx - 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if selected_idx + 1 < len => {
+                        OnEvent::without_saving(Self::Inbox(selected_idx + 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Inbox(0)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Inbox(len.saturating_sub(1))),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match selected {
+                        Some(entry_idx) => {
+                            let entry = &project.entries[entry_idx];
+                            let id = entry.id;
+                            let opened = open_entry(entry, project, false);
+                            project.opened(id);
+                            OnEvent::ignore().with_unopened(opened.err(), clipboard)
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('k'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match selected {
+                        Some(entry_idx) => {
+                            project.keep(entry_idx);
+                            OnEvent::with_saving(after_triage(project))
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('x'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match selected {
+                        Some(entry_idx) => {
+                            project.archive_entry(entry_idx);
+                            OnEvent::with_saving(after_triage(project))
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('m'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !project.config.sections.is_empty() => match selected {
+                        Some(entry_idx) => {
+                            let id = project.entries[entry_idx].id;
+                            let next = next_section(project, entry_idx);
+                            match project.set_section(entry_idx, next) {
+                                // making room in the section may have archived entries of the inbox
+                                Ok(_) => {
+                                    let inbox = project.inbox();
+                                    let row = inbox
+                                        .iter()
+                                        .position(|idx| project.entries[*idx].id == id)
+                                        .unwrap_or(0);
+                                    OnEvent::with_saving(Self::Inbox(row))
+                                }
+                                Err(err) => OnEvent::ignore().with_notice(err.to_string()),
+                            }
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('g'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => selected.map_or_else(OnEvent::ignore, |entry_idx| {
+                        let id = project.entries[entry_idx].id;
+                        OnEvent::with_saving(Self::jump_to(project, id))
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc | KeyCode::Char('i'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Entry(0)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Starred(selected_idx) => {
+                let selected_idx = *selected_idx;
+                let selected = project.starred().get(selected_idx).map(|entry| entry.id);
+                let len = project.starred().len();
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if selected_idx > 0 => {
+                        OnEvent::without_saving(Self::Starred(selected_idx - 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if selected_idx + 1 < len => {
+                        OnEvent::without_saving(Self::Starred(selected_idx + 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Starred(0)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Starred(len.saturating_sub(1))),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match selected {
+                        Some(id) => {
+                            let opened = project
+                                .get(id)
+                                .and_then(|entry| open_entry(entry, project, false).err());
+                            project.opened(id);
+                            OnEvent::ignore().with_unopened(opened, clipboard)
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('*'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match selected {
+                        Some(id) => {
+                            project.toggle_star(id);
+                            let len = project.starred().len();
+                            OnEvent::with_saving(if len == 0 {
+                                Self::Entry(0)
+                            } else {
+                                Self::Starred(selected_idx.min(len - 1))
+                            })
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('g'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => selected.map_or_else(OnEvent::ignore, |id| {
+                        OnEvent::with_saving(Self::jump_to(project, id))
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc | KeyCode::Char('f'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Entry(0)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::History(selected_idx) => {
+                let selected_idx = *selected_idx;
+                let selected = project
+                    .recently_opened()
+                    .get(selected_idx)
+                    .map(|(entry, _)| entry.id);
+                let len = project.recently_opened().len();
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if selected_idx > 0 => {
+                        OnEvent::without_saving(Self::History(selected_idx - 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if selected_idx + 1 < len => {
+                        OnEvent::without_saving(Self::History(selected_idx + 1))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::History(0)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::History(len.saturating_sub(1))),
+                    // reopening moves the entry to the top of the history
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match selected {
+                        Some(id) => {
+                            let opened = project
+                                .get(id)
+                                .and_then(|entry| open_entry(entry, project, false).err());
+                            project.opened(id);
+                            OnEvent::without_saving(Self::History(0))
+                                .with_unopened(opened, clipboard)
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('g'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => selected.map_or_else(OnEvent::ignore, |id| {
+                        OnEvent::with_saving(Self::jump_to(project, id))
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc | KeyCode::Char('h'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Entry(0)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Journal { events, row } => {
+                let row = *row;
+                let at = |row| {
+                    OnEvent::without_saving(Self::Journal {
+                        events: events.clone(),
+                        row,
+                    })
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row > 0 => at(row - 1),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row + 1 < events.len() => at(row + 1),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => at(0),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => at(events.len().saturating_sub(1)),
+                    // reverting is saved, and so journaled, as a change of its own
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('r'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match project.revert(&events[row]) {
+                        Ok(()) => OnEvent::with_saving(Self::Journal {
+                            events: events.clone(),
+                            row,
+                        })
+                        .with_notice(format!(
+                            "Reverted #{}: {} {}",
+                            events[row].id,
+                            events[row].action(),
+                            events[row].entry_name()
+                        )),
+                        Err(err) => at(row).with_notice(err.to_string()),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('g'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if project.get(events[row].entry_id).is_some() => {
+                        OnEvent::with_saving(Self::jump_to(project, events[row].entry_id))
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc | KeyCode::Char('H'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Entry(0)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::View { key, row } => {
+                let row = *row;
+                let entries = view_entries(project, key);
+                let selected = entries.get(row).map(|entry| entry.id);
+                let len = entries.len();
+                let moved = |row: usize| {
+                    OnEvent::without_saving(Self::View {
+                        key: key.clone(),
+                        row,
+                    })
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row > 0 => moved(row - 1),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row + 1 < len => moved(row + 1),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => moved(0),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => moved(len.saturating_sub(1)),
+                    Event::Key(KeyEvent {
+                        code: code @ (KeyCode::Enter | KeyCode::Char('o')),
+                        kind: KeyEventKind::Press,
+                        modifiers,
+                        ..
+                    }) => match selected {
+                        Some(id) => {
+                            let opened = project.get(id).and_then(|entry| {
+                                open_entry(entry, project, is_alternate_open(code, modifiers)).err()
+                            });
+                            project.opened(id);
+                            OnEvent::ignore().with_unopened(opened, clipboard)
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('g'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => selected.map_or_else(OnEvent::ignore, |id| {
+                        OnEvent::with_saving(Self::jump_to(project, id))
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Entry(0)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Tag { tag, row } => {
+                let row = *row;
+                let entries = tagged_entries(project, tag);
+                let selected = entries.get(row).map(|entry| entry.id);
+                let len = entries.len();
+                let moved = |row: usize| {
+                    OnEvent::without_saving(Self::Tag {
+                        tag: tag.clone(),
+                        row,
+                    })
+                };
+                let tags = entry_tags(project);
+                let tag_idx = tags.iter().position(|other| other == tag).unwrap_or(0);
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row > 0 => moved(row - 1),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row + 1 < len => moved(row + 1),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => moved(0),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => moved(len.saturating_sub(1)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Left,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if tag_idx > 0 => OnEvent::without_saving(Self::Tag {
+                        tag: tags[tag_idx - 1].clone(),
+                        row: 0,
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Right,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if tag_idx + 1 < tags.len() => OnEvent::without_saving(Self::Tag {
+                        tag: tags[tag_idx + 1].clone(),
+                        row: 0,
+                    }),
+                    Event::Key(KeyEvent {
+                        code: code @ (KeyCode::Enter | KeyCode::Char('o')),
+                        kind: KeyEventKind::Press,
+                        modifiers,
+                        ..
+                    }) => match selected {
+                        Some(id) => {
+                            let opened = project.get(id).and_then(|entry| {
+                                open_entry(entry, project, is_alternate_open(code, modifiers)).err()
+                            });
+                            project.opened(id);
+                            OnEvent::ignore().with_unopened(opened, clipboard)
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('g'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => selected.map_or_else(OnEvent::ignore, |id| {
+                        OnEvent::with_saving(Self::jump_to(project, id))
+                    }),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc | KeyCode::Char('t'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Entry(0)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Search { input, row, typing } => {
+                let (row, typing) = (*row, *typing);
+                let matches = search_matches(project, input);
+                let selected = matches.get(row).copied();
+                let len = matches.len();
+                let searched = |input: String, row: usize, typing: bool| {
+                    OnEvent::without_saving(Self::Search { input, row, typing })
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row > 0 => searched(input.clone(), row - 1, typing),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row + 1 < len => searched(input.clone(), row + 1, typing),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !typing => searched(input.clone(), 0, typing),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !typing => searched(input.clone(), len.saturating_sub(1), typing),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char(c),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if typing => searched(format!("{input}{c}"), 0, true),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Backspace,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if typing => {
+                        let mut input = input.clone();
+                        input.pop();
+                        searched(input, 0, true)
+                    }
+                    // the filter stays, and keys act on the matching entries
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if typing => searched(input.clone(), row, false),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('/'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => searched(input.clone(), row, true),
+                    Event::Key(KeyEvent {
+                        code: code @ (KeyCode::Enter | KeyCode::Char('o')),
+                        kind: KeyEventKind::Press,
+                        modifiers,
+                        ..
+                    }) => match selected {
+                        Some(idx) => {
+                            let entry = &project.entries[idx];
+                            let entry_id = entry.id;
+                            let opened =
+                                open_entry(entry, project, is_alternate_open(code, modifiers));
+                            project.opened(entry_id);
+                            OnEvent::ignore().with_unopened(opened.err(), clipboard)
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Delete,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !typing => match selected {
+                        Some(idx) => {
+                            project.archive_entry(idx);
+                            // keep the selection on the same row
+                            let len = search_matches(project, input).len();
+                            OnEvent::with_saving(Self::Search {
+                                input: input.clone(),
+                                row: row.min(len.saturating_sub(1)),
+                                typing,
+                            })
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('d'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if !typing && selected.is_some() => {
+                        OnEvent::without_saving(Self::SearchDrag {
+                            input: input.clone(),
+                            from: row,
+                            row,
+                        })
+                    }
+                    // clears the filter, keeping the selected entry selected
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match selected {
+                        Some(idx) => {
+                            let id = project.entries[idx].id;
+                            OnEvent::with_saving(Self::jump_to(project, id))
+                        }
+                        None => OnEvent::without_saving(Self::Entry(0)),
+                    },
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::SearchDrag { input, from, row } => {
+                let (from, row) = (*from, *row);
+                let matches = search_matches(project, input);
+                let len = matches.len();
+                let dragged = |row: usize| {
+                    OnEvent::without_saving(Self::SearchDrag {
+                        input: input.clone(),
+                        from,
+                        row,
+                    })
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row > 0 => dragged(row - 1),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row + 1 < len => dragged(row + 1),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Home,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => dragged(0),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::End,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => dragged(len.saturating_sub(1)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        // the entry takes the place of the match it was dropped on, among all the entries
+                        if let (Some(from), Some(to)) = (matches.get(from), matches.get(row)) {
+                            project.move_entry(*from, *to);
+                        }
+                        OnEvent::with_saving(Self::Search {
+                            input: input.clone(),
+                            row,
+                            typing: false,
+                        })
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Search {
+                        input: input.clone(),
+                        row: from,
+                        typing: false,
+                    }),
+                    _ => OnEvent::ignore(),
+                }
+            }
+            Self::Agenda(row) => {
+                let row = *row;
+                let now = Utc::now();
+                let items = agenda::items(project, now, DEFAULT_AGENDA_DAYS);
+                let len = items.len();
+                let selected = items.get(row).map(|item| (item.entry.id, item.kind));
+                // actions can move the item out of the agenda
+                let clamped = |project: &Project| {
+                    let len = agenda::items(project, now, DEFAULT_AGENDA_DAYS).len();
+                    Self::Agenda(row.min(len.saturating_sub(1)))
+                };
+                match event {
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Up,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row > 0 => OnEvent::without_saving(Self::Agenda(row - 1)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Down,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) if row + 1 < len => OnEvent::without_saving(Self::Agenda(row + 1)),
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => {
+                        let Some((id, _)) = selected else {
+                            return OnEvent::ignore();
+                        };
+                        let opened = open_entry(items[row].entry, project, false);
+                        project.opened(id);
+                        OnEvent::ignore().with_unopened(opened.err(), clipboard)
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Delete,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match selected {
+                        Some((id, _)) => {
+                            project.complete(id);
+                            OnEvent::with_saving(clamped(project))
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('p'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => match selected {
+                        Some((id, kind)) => {
+                            match kind {
+                                AgendaKind::Due => {
+                                    project.postpone_due(id, ChronoDuration::days(1), now)
+                                }
+                                AgendaKind::Resurfaces => {
+                                    project.postpone_snooze(id, ChronoDuration::days(1), now)
+                                }
+                            }
+                            OnEvent::with_saving(clamped(project))
+                        }
+                        None => OnEvent::ignore(),
+                    },
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Esc | KeyCode::Char('A'),
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => OnEvent::without_saving(Self::Entry(0)),
+                    _ => OnEvent::ignore(),
+                }
+            }
+        }
+    }
+
+    /// The state with its rows clamped to the lists they index, after those lists shrank. Returns None for states
+    /// with nothing to clamp, including the states that point at entries by id.
+    fn clamp(&self, project: &Project) -> Option<Self> {
+        let clamp = |idx: usize, len: usize| idx.min(len.saturating_sub(1));
+        let reconciled = match self {
+            Self::Drag {
+                dragged_id,
+                new_position,
+                column,
+            } => Self::Drag {
+                dragged_id: *dragged_id,
+                new_position: clamp(*new_position, project.entries.len()),
+                column: column.map(|column| clamp(column, board_columns(project).len())),
+            },
+            Self::Board { column, row } => {
+                let columns = board_columns(project);
+                let column = clamp(*column, columns.len());
+                let row = clamp(*row, column_indices(project, &columns[column]).len());
+                Self::Board { column, row }
+            }
+            Self::FollowLink {
+                from,
+                targets,
+                choice,
+            } => Self::FollowLink {
+                from: Box::new(from.clamp(project).unwrap_or_else(|| (**from).clone())),
+                targets: targets.clone(),
+                choice: *choice,
+            },
+            Self::Provided(idx) => Self::Provided(clamp(*idx, project.provided.len())),
+            Self::Starred(idx) => Self::Starred(clamp(*idx, project.starred().len())),
+            Self::Inbox(idx) => Self::Inbox(clamp(*idx, project.inbox().len())),
+            Self::History(idx) => Self::History(clamp(*idx, project.recently_opened().len())),
+            Self::Agenda(row) => Self::Agenda(clamp(
+                *row,
+                agenda::items(project, Utc::now(), DEFAULT_AGENDA_DAYS).len(),
+            )),
+            Self::Day { day, row } => Self::Day {
+                day: *day,
+                row: clamp(*row, entries_on(project, *day).len()),
+            },
+            Self::View { key, row } => Self::View {
+                key: key.clone(),
+                row: clamp(*row, view_entries(project, key).len()),
+            },
+            Self::Tag { tag, row } => Self::Tag {
+                tag: tag.clone(),
+                row: clamp(*row, tagged_entries(project, tag).len()),
+            },
+            Self::Search { input, row, typing } => Self::Search {
+                input: input.clone(),
+                row: clamp(*row, search_matches(project, input).len()),
+                typing: *typing,
+            },
+            // a dragged match that is gone leaves nothing to drop
+            Self::SearchDrag { input, from, row } => {
+                let matches = search_matches(project, input).len();
+                if *from >= matches {
+                    Self::Search {
+                        input: input.clone(),
+                        row: clamp(*from, matches),
+                        typing: false,
+                    }
+                } else {
+                    Self::SearchDrag {
+                        input: input.clone(),
+                        from: *from,
+                        row: clamp(*row, matches),
+                    }
+                }
+            }
+            Self::Entry(..)
+            | Self::Archive(..)
+            | Self::Snoozed(..)
+            | Self::TimePrompt { .. }
+            | Self::FieldPrompt { .. }
+            | Self::Link { .. }
+            | Self::Relink { .. }
+            | Self::Edit { .. }
+            | Self::Notes { .. }
+            | Self::Palette { .. }
+            | Self::Triage { .. }
+            | Self::Calendar(..)
+            | Self::Journal { .. } => return None,
+        };
+        Some(reconciled)
+    }
+
+    fn describe(&self, project: &Project) -> String {
+        // prompts are about entries that might be gone by the time they are described
+        let name = |id: &u64| {
+            project
+                .get(*id)
+                .map_or("a removed entry", |entry| &entry.name)
+        };
+        let position = |entries: &[Entry], id: &u64| {
+            let idx = idx_of(entries, *id);
+            format!("{} of {}: {}", idx + 1, entries.len(), entries[idx].name)
+        };
+        match self {
+            Self::Entry(_) if project.entries.is_empty() => "No entries".to_string(),
+            Self::Entry(id) => format!("Entry {}", position(&project.entries, id)),
+            Self::Archive(_) if project.archive.is_empty() => "The archive is empty".to_string(),
+            Self::Archive(id) => format!("Archive entry {}", position(&project.archive, id)),
+            Self::Drag {
+                dragged_id,
+                column: Some(column),
+                ..
+            } => format!(
+                "Moving {} to {}",
+                name(dragged_id),
+                column_title(project, &board_columns(project)[*column])
+            ),
+            Self::Drag {
+                dragged_id,
+                new_position,
+                column: None,
+            } => format!(
+                "Dragging {} to position {} of {}",
+                name(dragged_id),
+                new_position + 1,
+                project.entries.len()
+            ),
+            Self::Snoozed(_) if project.snoozed.is_empty() => "No snoozed entries".to_string(),
+            Self::Snoozed(id) => format!("Snoozed entry {}", position(&project.snoozed, id)),
+            Self::TimePrompt { id, input, target } => {
+                format!("{} for {}: {}", target.label(), name(id), input)
+            }
+            Self::FieldPrompt { id, input } => format!("Set field of {}: {}", name(id), input),
+            Self::Palette { input, .. } => format!("Open: {input}"),
+            Self::Notes { id, lines, row } => format!(
+                "Notes of {}, line {} of {}: {}",
+                name(id),
+                row + 1,
+                lines.len(),
+                lines[*row]
+            ),
+            Self::Edit { id, fields, focus } => format!(
+                "Editing {}, {}: {}",
+                name(id),
+                edit_label(*focus),
+                fields[*focus]
+            ),
+            Self::Link {
+                source_id,
+                target_id,
+            } => format!("Linking {} to {}", name(source_id), name(target_id)),
+            Self::Board { column, row } => {
+                let section = &board_columns(project)[*column];
+                let indices = column_indices(project, section);
+                match indices.get(*row) {
+                    Some(idx) => format!(
+                        "{} entry {} of {}: {}",
+                        column_title(project, section),
+                        row + 1,
+                        indices.len(),
+                        project.entries[*idx].name
+                    ),
+                    None => format!("{}: empty", column_title(project, section)),
+                }
+            }
+            Self::Calendar(day) => calendar_summary(project, *day),
+            Self::Provided(selected_idx) => match project.provided.get(*selected_idx) {
+                Some(entry) => format!(
+                    "Provided entry {} of {}: {}",
+                    selected_idx + 1,
+                    project.provided.len(),
+                    entry.name
+                ),
+                None => "No provided entries".to_string(),
+            },
+            Self::Triage { queue, pos, .. } => {
+                let left = queue[*pos..]
+                    .iter()
+                    .filter(|id| main_idx(project, **id).is_some())
+                    .count();
+                match main_idx(project, queue[*pos]) {
+                    Some(idx) => format!("Triage, {left} left: {}", project.entries[idx].name),
+                    None => "Triage done".to_string(),
+                }
+            }
+            Self::Inbox(selected_idx) => {
+                let inbox = project.inbox();
+                match inbox.get(*selected_idx) {
+                    Some(entry_idx) => format!(
+                        "Inbox entry {} of {}: {}",
+                        selected_idx + 1,
+                        inbox.len(),
+                        project.entries[*entry_idx].name
+                    ),
+                    None => "The inbox is empty".to_string(),
+                }
+            }
+            Self::Starred(selected_idx) => {
+                let starred = project.starred();
+                match starred.get(*selected_idx) {
+                    Some(entry) => format!(
+                        "Starred entry {} of {}: {}",
+                        selected_idx + 1,
+                        starred.len(),
+                        entry.name
+                    ),
+                    None => "No starred entries".to_string(),
+                }
+            }
+            Self::History(selected_idx) => {
+                let history = project.recently_opened();
+                match history.get(*selected_idx) {
+                    Some((entry, opened_at)) => format!(
+                        "Recently opened entry {} of {}: {}, opened {}",
+                        selected_idx + 1,
+                        history.len(),
+                        entry.name,
+                        opened_at.with_timezone(&Local).format("%x %I:%M %p")
+                    ),
+                    None => "No recently opened entries".to_string(),
+                }
+            }
+            Self::Journal { events, row } => {
+                let event = &events[*row];
+                format!(
+                    "Change {} of {}: {}",
+                    row + 1,
+                    events.len(),
+                    journal::render([event]).trim_end()
+                )
+            }
+            Self::Agenda(row) => {
+                let items = agenda::items(project, Utc::now(), DEFAULT_AGENDA_DAYS);
+                match items.get(*row) {
+                    Some(item) => format!(
+                        "Agenda item {} of {}: {}, {} {}",
+                        row + 1,
+                        items.len(),
+                        item.entry.name,
+                        item.kind.label(),
+                        item.at.with_timezone(&Local).format("%A %I:%M %p")
+                    ),
+                    None => "Agenda is empty".to_string(),
+                }
+            }
+            Self::Day { day, row } => {
+                let entries = entries_on(project, *day);
+                format!(
+                    "Entry {} of {} on {}: {}",
+                    row + 1,
+                    entries.len(),
+                    day.format("%x"),
+                    entries.get(*row).map_or("", |entry| entry.name.as_str())
+                )
+            }
+            Self::View { key, row } => {
+                let entries = view_entries(project, key);
+                match entries.get(*row) {
+                    Some(entry) => format!(
+                        "{} entry {} of {}: {}",
+                        view_title(project, key),
+                        row + 1,
+                        entries.len(),
+                        entry.name
+                    ),
+                    None => format!("{}: empty", view_title(project, key)),
+                }
+            }
+            Self::Tag { tag, row } => {
+                let entries = tagged_entries(project, tag);
+                match entries.get(*row) {
+                    Some(entry) => format!(
+                        "{} entry {} of {}: {}",
+                        tag_title(project, tag),
+                        row + 1,
+                        entries.len(),
+                        entry.name
+                    ),
+                    None => format!("{}: empty", tag_title(project, tag)),
+                }
+            }
+            Self::Search { input, row, .. } => {
+                let matches = search_matches(project, input);
+                match matches.get(*row) {
+                    Some(idx) => format!(
+                        "{} entry {} of {}: {}",
+                        search_title(input),
+                        row + 1,
+                        matches.len(),
+                        project.entries[*idx].name
+                    ),
+                    None => format!("{}: no matches", search_title(input)),
+                }
+            }
+            Self::SearchDrag { input, from, row } => {
+                let matches = search_matches(project, input);
+                format!(
+                    "Moving {} to match {} of {}",
+                    matches
+                        .get(*from)
+                        .map_or("", |idx| project.entries[*idx].name.as_str()),
+                    row + 1,
+                    matches.len()
+                )
+            }
+            Self::FollowLink {
+                targets, choice, ..
+            } => format!(
+                "Go to linked entry {} of {}: {}",
+                choice + 1,
+                targets.len(),
+                project
+                    .get(targets[*choice])
+                    .map_or("", |entry| entry.name.as_str())
+            ),
+            Self::Relink {
+                id,
+                candidates,
+                choice,
+            } => format!(
+                "Relink {} to candidate {} of {}: {}{}",
+                project.get(*id).map_or("", |entry| entry.name.as_str()),
+                choice + 1,
+                candidates.len(),
+                candidates[*choice].path,
+                if candidates[*choice].same_content {
+                    " (same content)"
+                } else {
+                    ""
+                }
+            ),
+        }
+    }
+
+    fn get_options(&self, app: &App) -> Vec<KeyOption> {
+        let mut ret = Vec::new();
+        match self {
+            SelectState::Entry(selected_id) => {
+                let selected_idx = idx_of(&app.project.entries, *selected_id);
+                if !app.project.entries.is_empty() {
+                    let entry = &app.project.entries[selected_idx];
+                    ret.extend(open_options(entry, &app.project, app.ctrl_enter));
+                    let entries = &app.project.entries;
+                    if tree::step(entries, selected_idx, false, app.project.sort).is_some() {
+                        ret.push(KeyOption::new("<Up>", "select above entry"));
+                    }
+                    if tree::step(entries, selected_idx, true, app.project.sort).is_some() {
+                        ret.push(KeyOption::new("<Down>", "select below entry"));
+                    }
+                    ret.push(KeyOption::new("<Home>", "select first entry"));
+                    ret.push(KeyOption::new("<End>", "select last entry"));
+                    if entries[selected_idx].collapsed {
+                        ret.push(KeyOption::new("<Right>", "expand entry"));
+                    } else if tree::first_child(entries, selected_idx).is_some() {
+                        ret.push(KeyOption::new("<Right>", "select first child"));
+                        ret.push(KeyOption::new("<Left>", "collapse entry"));
+                    }
+                    if tree::parent(entries, selected_idx).is_some() {
+                        if entries[selected_idx].collapsed
+                            || tree::first_child(entries, selected_idx).is_none()
+                        {
+                            ret.push(KeyOption::new("<Left>", "select parent"));
+                        }
+                        ret.push(KeyOption::new("<", "move out of parent"));
+                    }
+                    if tree::sibling(entries, selected_idx, false).is_some() {
+                        ret.push(KeyOption::new(">", "nest under entry above"));
+                    }
+                    ret.push(KeyOption::new("<Delete>", "archive entry"));
+                    if app.project.sort == tree::Sort::Manual {
+                        ret.push(KeyOption::new("d", "drag entry"));
+                        ret.push(KeyOption::new("<Ctrl+Up/Down>", "move entry one place"));
+                    }
+                    ret.push(KeyOption::new(
+                        "s",
+                        format!("sort by {}", app.project.sort.next().label()),
+                    ));
+                    ret.push(KeyOption::new("z", "snooze entry"));
+                    ret.push(KeyOption::new("D", "set due date"));
+                    ret.push(KeyOption::new("F", "set custom field"));
+                    if !app.project.config.sections.is_empty() {
+                        ret.push(KeyOption::new("m", "move to next section"));
+                    }
+                    if app.project.entries.len() > 1 {
+                        ret.push(KeyOption::new("l", "link to another entry"));
+                    }
+                    if let Link::Url(_) = &app.project.entries[selected_idx].link {
+                        ret.push(KeyOption::new("S", "save readable snapshot"));
+                        ret.push(KeyOption::new("L", "download to project"));
+                        if entry.watch.is_some() {
+                            ret.push(KeyOption::new("W", "stop watching page"));
+                        } else {
+                            ret.push(KeyOption::new("W", "watch page for changes"));
+                        }
+                    }
+                    if let Link::Feed(_) = &app.project.entries[selected_idx].link {
+                        ret.push(KeyOption::new("X", "expand unread feed items"));
+                    }
+                    if entry.ephemeral {
+                        ret.push(KeyOption::new("K", "keep temporary entry"));
+                    }
+                    let suggested = app.project.config.suggested_tags(entry);
+                    if !suggested.is_empty() {
+                        ret.push(KeyOption::new(
+                            "+",
+                            format!("add suggested tags: {}", suggested.join(", ")),
+                        ));
+                    }
+                    if app.background.refresh_progress().is_some() {
+                        ret.push(KeyOption::new("<Esc>", "cancel title refresh"));
+                    } else {
+                        ret.push(KeyOption::new("N", "refresh titles of url entries"));
+                    }
+                    ret.push(KeyOption::new(":", "open by alias or query"));
+                    ret.push(KeyOption::new("/", "search entries"));
+                    ret.push(KeyOption::new("'", "type to go to entry"));
+                    ret.push(KeyOption::new("t", "show entries by tag"));
+                    if !app.project.recently_opened().is_empty() {
+                        ret.push(KeyOption::new("h", "show recently opened"));
+                    }
+                    ret.push(KeyOption::new("H", "show journal of changes"));
+                    if entry.starred {
+                        ret.push(KeyOption::new("*", "unstar entry"));
+                    } else {
+                        ret.push(KeyOption::new("*", "star entry"));
+                    }
+                    if entry.pinned {
+                        ret.push(KeyOption::new("p", "unpin entry"));
+                    } else {
+                        ret.push(KeyOption::new("p", "pin entry to top"));
+                    }
+                    if !app.project.starred().is_empty() {
+                        ret.push(KeyOption::new("f", "show starred entries"));
+                    }
+                    let inbox = app.project.inbox().len();
+                    if inbox > 0 {
+                        ret.push(KeyOption::new("i", format!("triage inbox ({inbox})")));
+                    }
+                    ret.push(KeyOption::new(
+                        "T",
+                        "triage inbox or new entries one by one",
+                    ));
+                    ret.push(KeyOption::new("E", "edit entry"));
+                    ret.push(KeyOption::new("n", "edit notes"));
+                    if app.project.config.share_command.is_some() {
+                        ret.push(KeyOption::new("e", "share entry"));
+                    }
+                    if app.recording.is_some() {
+                        ret.push(KeyOption::new("Q", "stop recording macro"));
+                    } else {
+                        ret.push(KeyOption::new("Q", "record macro"));
+                        ret.push(KeyOption::new("@", "replay macro"));
+                    }
+                    let git_status = app.git_statuses.get(entry.link.as_str());
+                    if let Some(Some(GitStatus {
+                        remote_url: Some(_),
+                        ..
+                    })) = git_status
+                    {
+                        ret.push(KeyOption::new("G", "open git remote"));
+                    }
+                    if !app.project.entries[selected_idx].see_also.is_empty() {
+                        ret.push(KeyOption::new("g", "go to linked entry"));
+                    }
+                }
+                if app.last_remote.is_some() {
+                    ret.push(KeyOption::new("J", "jump to entry added remotely"));
+                }
+                if !app.project.archive.is_empty() {
+                    ret.push(KeyOption::new("a", "go to archive"));
+                    ret.push(KeyOption::new("P", "peek at latest archived"));
+                }
+                if !app.project.snoozed.is_empty() {
+                    ret.push(KeyOption::new("Z", "go to snoozed entries"));
+                }
+                ret.push(KeyOption::new("b", "show as board"));
+                ret.push(KeyOption::new("c", "show calendar"));
+                ret.push(KeyOption::new("A", "show agenda"));
+                if !app.project.config.providers.is_empty() {
+                    ret.push(KeyOption::new("R", "show provided entries"));
+                }
+                if let Some(clipboard) = &app.clipboard {
+                    if clipboard.borrow_mut().get_contents().is_ok() {
+                        ret.push(KeyOption::new("^v", "paste clipboard"));
+                        ret.push(KeyOption::new("M-v", "paste as a temporary entry"));
+                    }
+                }
+            }
+            SelectState::Archive(selected_id) => {
+                let selected_idx = idx_of(&app.project.archive, *selected_id);
+                let entry = &app.project.archive[selected_idx];
+                ret.extend(open_options(entry, &app.project, app.ctrl_enter));
+                if selected_idx > 0 {
+                    ret.push(KeyOption::new("<Up>", "select above entry"));
+                }
+                if selected_idx < app.project.archive.len() - 1 {
+                    ret.push(KeyOption::new("<Down>", "select below entry"));
+                }
+                ret.push(KeyOption::new("<Home>", "select first entry"));
+                ret.push(KeyOption::new("<End>", "select last entry"));
+                ret.push(KeyOption::new("<Delete>", "delete entry forever"));
+                ret.push(KeyOption::new("r", "restore entry"));
+                ret.push(KeyOption::new("*", "toggle star"));
+                if !entry.see_also.is_empty() {
+                    ret.push(KeyOption::new("g", "go to linked entry"));
+                }
+                ret.push(KeyOption::new("'", "type to go to entry"));
+                ret.push(KeyOption::new("a", "return to main entries"));
+            }
+            SelectState::Drag {
+                dragged_id,
+                new_position,
+                column: None,
+            } => {
+                let new_position = *new_position;
+                let order = drag_order(&app.project, *dragged_id, new_position);
+                ret.push(KeyOption::new("<Enter>", "select new location"));
+                if tree::sibling(&order, new_position, false).is_some() {
+                    ret.push(KeyOption::new("<Up>", "shift one up"));
+                }
+                if tree::sibling(&order, new_position, true).is_some() {
+                    ret.push(KeyOption::new("<Down>", "shift one down"));
+                }
+                ret.push(KeyOption::new("<Home>", "shift to top"));
+                ret.push(KeyOption::new("<End>", "shift to bottom"));
+                ret.push(KeyOption::new("<Esc>", "cancel drag"));
+            }
+            SelectState::Snoozed(selected_id) => {
+                let selected_idx = idx_of(&app.project.snoozed, *selected_id);
+                ret.push(KeyOption::new("<Enter>", "open entry"));
+                if selected_idx > 0 {
+                    ret.push(KeyOption::new("<Up>", "select above entry"));
+                }
+                if selected_idx < app.project.snoozed.len() - 1 {
+                    ret.push(KeyOption::new("<Down>", "select below entry"));
+                }
+                ret.push(KeyOption::new("<Home>", "select first entry"));
+                ret.push(KeyOption::new("<End>", "select last entry"));
+                ret.push(KeyOption::new("u", "unsnooze entry"));
+                ret.push(KeyOption::new("Z", "return to main entries"));
+            }
+            SelectState::TimePrompt { target, .. } => {
+                ret.push(KeyOption::new(
+                    "<Enter>",
+                    "confirm (e.g. 2h, 3d, 1w, tomorrow, 2023-10-01 18:30)",
+                ));
+                if let TimeTarget::Due = target {
+                    ret.push(KeyOption::new(
+                        "<Enter>",
+                        "with no input to clear the due date",
+                    ));
+                }
+                ret.push(KeyOption::new("<Esc>", "cancel"));
+                // typing q here is part of the input
+                return ret;
+            }
+            SelectState::Link {
+                source_id,
+                target_id,
+            } => {
+                let target_idx = idx_of(&app.project.entries, *target_id);
+                let target_id = id_at(&app.project.entries, target_idx);
+                if target_id != *source_id {
+                    let linked = app
+                        .project
+                        .get(*source_id)
+                        .is_some_and(|source| source.see_also.contains(&target_id));
+                    let desc = if linked {
+                        "remove link to entry"
+                    } else {
+                        "link to entry"
+                    };
+                    ret.push(KeyOption::new("<Enter>", desc));
+                }
+                if target_idx > 0 {
+                    ret.push(KeyOption::new("<Up>", "select above entry"));
+                }
+                if target_idx < app.project.entries.len() - 1 {
+                    ret.push(KeyOption::new("<Down>", "select below entry"));
+                }
+                ret.push(KeyOption::new("<Home>", "select first entry"));
+                ret.push(KeyOption::new("<End>", "select last entry"));
+                ret.push(KeyOption::new("<Esc>", "cancel linking"));
+            }
+            SelectState::FollowLink {
+                targets, choice, ..
+            } => {
+                ret.push(KeyOption::new("<Enter>", "go to entry"));
+                if *choice > 0 {
+                    ret.push(KeyOption::new("<Up>", "previous linked entry"));
+                }
+                if *choice < targets.len() - 1 {
+                    ret.push(KeyOption::new("<Down>", "next linked entry"));
+                }
+                ret.push(KeyOption::new("<Esc>", "cancel"));
+            }
+            SelectState::FieldPrompt { .. } => {
+                ret.push(KeyOption::new(
+                    "<Enter>",
+                    "set field (e.g. isbn=0131103628, pages=272, read=2023-10-01)",
+                ));
+                ret.push(KeyOption::new(
+                    "<Enter>",
+                    "with no value (e.g. isbn=) to remove the field",
+                ));
+                ret.push(KeyOption::new("<Esc>", "cancel"));
+            }
+            SelectState::Edit { fields, focus, .. } => {
+                ret.push(KeyOption::new("<Enter>", "save entry"));
+                if focus + 1 < fields.len() {
+                    ret.push(KeyOption::new("<Tab>", "next field"));
+                }
+                if *focus > 0 {
+                    ret.push(KeyOption::new("<Shift+Tab>", "previous field"));
+                }
+                ret.push(KeyOption::new("<Esc>", "cancel"));
+            }
+            SelectState::Notes { lines, row, .. } => {
+                ret.push(KeyOption::new("<Ctrl+S>", "save notes"));
+                ret.push(KeyOption::new("<Enter>", "new line"));
+                if row + 1 < lines.len() {
+                    ret.push(KeyOption::new("<Down>", "next line"));
+                }
+                if *row > 0 {
+                    ret.push(KeyOption::new("<Up>", "previous line"));
+                }
+                ret.push(KeyOption::new("<Esc>", "cancel"));
+            }
+            SelectState::Palette {
+                confirming: Some(_),
+                ..
+            } => {
+                ret.push(KeyOption::new("<Enter>", "open them all"));
+                ret.push(KeyOption::new("<Esc>", "cancel"));
+            }
+            SelectState::Palette { .. } => {
+                ret.push(KeyOption::new("<Enter>", "open matching entries"));
+                if !app.project.config.aliases.is_empty() {
+                    ret.push(KeyOption::new("<Tab>", "complete alias"));
+                }
+                ret.push(KeyOption::new("<Esc>", "cancel"));
+            }
+            SelectState::Relink {
+                candidates, choice, ..
+            } => {
+                ret.push(KeyOption::new("<Enter>", "relink to candidate"));
+                if *choice > 0 {
+                    ret.push(KeyOption::new("<Up>", "previous candidate"));
+                }
+                if *choice < candidates.len() - 1 {
+                    ret.push(KeyOption::new("<Down>", "next candidate"));
+                }
+                ret.push(KeyOption::new("<Esc>", "cancel"));
+            }
+            SelectState::Board { column, row } => {
+                let columns = board_columns(&app.project);
+                let len = column_indices(&app.project, &columns[*column]).len();
+                if *row < len {
+                    ret.push(KeyOption::new("<Enter>", "open entry"));
+                    ret.push(KeyOption::new("d", "move entry to another column"));
+                }
+                if *row > 0 {
+                    ret.push(KeyOption::new("<Up>", "select above entry"));
+                }
+                if row + 1 < len {
+                    ret.push(KeyOption::new("<Down>", "select below entry"));
+                }
+                if *column > 0 {
+                    ret.push(KeyOption::new("<Left>", "previous column"));
+                }
+                if column + 1 < columns.len() {
+                    ret.push(KeyOption::new("<Right>", "next column"));
+                }
+                ret.push(KeyOption::new("b", "return to list"));
+            }
+            SelectState::Drag {
+                column: Some(column),
+                ..
+            } => {
+                let columns = board_columns(&app.project);
+                if columns[*column].section().is_some() {
+                    ret.push(KeyOption::new("<Enter>", "move to this column"));
+                }
+                if *column > 0 {
+                    ret.push(KeyOption::new("<Left>", "previous column"));
+                }
+                if columns
+                    .get(column + 1)
+                    .is_some_and(|next| next.section().is_some())
+                {
+                    ret.push(KeyOption::new("<Right>", "next column"));
+                }
+                ret.push(KeyOption::new("<Esc>", "cancel move"));
+            }
+            SelectState::Calendar(day) => {
+                if !entries_on(&app.project, *day).is_empty() {
+                    ret.push(KeyOption::new("<Enter>", "list the day's entries"));
+                }
+                ret.push(KeyOption::new("<Arrows>", "select another day"));
+                ret.push(KeyOption::new("<PgUp>/<PgDn>", "previous/next month"));
+                ret.push(KeyOption::new("<Home>", "select today"));
+                ret.push(KeyOption::new("<Esc>", "return to list"));
+            }
+            SelectState::Triage {
+                prompt: Some((prompt, _)),
+                ..
+            } => {
+                ret.push(KeyOption::new(
+                    "<Enter>",
+                    match prompt {
+                        TriagePrompt::Tag => "add the tag",
+                        TriagePrompt::Snooze => "confirm (e.g. 2h, 3d, 1w, tomorrow)",
+                    },
+                ));
+                ret.push(KeyOption::new("<Esc>", "cancel"));
+                // typing q here is part of the input
+                return ret;
+            }
+            SelectState::Triage { pos, .. } => {
+                ret.push(KeyOption::new("<Enter>", "open entry"));
+                ret.push(KeyOption::new("k", "keep and go to next"));
+                ret.push(KeyOption::new("t", "add a tag"));
+                if !app.project.config.sections.is_empty() {
+                    ret.push(KeyOption::new("m", "move to next section"));
+                }
+                ret.push(KeyOption::new("z", "snooze and go to next"));
+                ret.push(KeyOption::new("a", "archive and go to next"));
+                ret.push(KeyOption::new("<Right>", "skip for now"));
+                if *pos > 0 {
+                    ret.push(KeyOption::new("<Left>", "back to previous"));
+                }
+                ret.push(KeyOption::new("<Esc>", "stop triage"));
+            }
+            SelectState::Inbox(selected_idx) => {
+                let len = app.project.inbox().len();
+                if *selected_idx < len {
+                    ret.push(KeyOption::new("<Enter>", "open entry"));
+                    ret.push(KeyOption::new("k", "keep in the list"));
+                    ret.push(KeyOption::new("x", "discard to the archive"));
+                    if !app.project.config.sections.is_empty() {
+                        ret.push(KeyOption::new("m", "move to next section"));
+                    }
+                    ret.push(KeyOption::new("g", "go to entry"));
+                }
+                if *selected_idx > 0 {
+                    ret.push(KeyOption::new("<Up>", "select above entry"));
+                }
+                if selected_idx + 1 < len {
+                    ret.push(KeyOption::new("<Down>", "select below entry"));
+                }
+                ret.push(KeyOption::new("<Esc>", "back to entries"));
+            }
+            SelectState::Starred(selected_idx) => {
+                let len = app.project.starred().len();
+                if *selected_idx < len {
+                    ret.push(KeyOption::new("<Enter>", "open entry"));
+                    ret.push(KeyOption::new("*", "unstar entry"));
+                    ret.push(KeyOption::new("g", "go to entry"));
+                }
+                if *selected_idx > 0 {
+                    ret.push(KeyOption::new("<Up>", "select above entry"));
+                }
+                if selected_idx + 1 < len {
+                    ret.push(KeyOption::new("<Down>", "select below entry"));
+                }
+                ret.push(KeyOption::new("<Esc>", "back to entries"));
+            }
+            SelectState::History(selected_idx) => {
+                let len = app.project.recently_opened().len();
+                if *selected_idx < len {
+                    ret.push(KeyOption::new("<Enter>", "open entry"));
+                    ret.push(KeyOption::new("g", "go to entry"));
+                }
+                if *selected_idx > 0 {
+                    ret.push(KeyOption::new("<Up>", "select above entry"));
+                }
+                if selected_idx + 1 < len {
+                    ret.push(KeyOption::new("<Down>", "select below entry"));
+                }
+                ret.push(KeyOption::new("<Esc>", "back to entries"));
+            }
+            SelectState::Journal { events, row } => {
+                ret.push(KeyOption::new("r", "revert change"));
+                if app.project.get(events[*row].entry_id).is_some() {
+                    ret.push(KeyOption::new("g", "go to entry"));
+                }
+                if *row > 0 {
+                    ret.push(KeyOption::new("<Up>", "select newer change"));
+                }
+                if row + 1 < events.len() {
+                    ret.push(KeyOption::new("<Down>", "select older change"));
+                }
+                ret.push(KeyOption::new("<Esc>", "back to entries"));
+            }
+            SelectState::Provided(selected_idx) => {
+                let len = app.project.provided.len();
+                if *selected_idx < len {
+                    ret.push(KeyOption::new("<Enter>", "open entry"));
+                    ret.push(KeyOption::new("+", "copy to entries"));
+                }
+                if *selected_idx > 0 {
+                    ret.push(KeyOption::new("<Up>", "select above entry"));
+                }
+                if selected_idx + 1 < len {
+                    ret.push(KeyOption::new("<Down>", "select below entry"));
+                }
+                ret.push(KeyOption::new("R", "refresh providers"));
+                ret.push(KeyOption::new("<Esc>", "return to list"));
+            }
+            SelectState::Agenda(row) => {
+                let len = agenda::items(&app.project, Utc::now(), DEFAULT_AGENDA_DAYS).len();
+                if *row < len {
+                    ret.push(KeyOption::new("<Enter>", "open entry"));
+                    ret.push(KeyOption::new("<Delete>", "complete entry"));
+                    ret.push(KeyOption::new("p", "postpone by a day"));
+                }
+                if *row > 0 {
+                    ret.push(KeyOption::new("<Up>", "select above item"));
+                }
+                if row + 1 < len {
+                    ret.push(KeyOption::new("<Down>", "select below item"));
+                }
+                ret.push(KeyOption::new("<Esc>", "return to list"));
+            }
+            SelectState::View { key, row } => {
+                let entries = view_entries(&app.project, key);
+                if let Some(entry) = entries.get(*row) {
+                    ret.extend(open_options(entry, &app.project, app.ctrl_enter));
+                    ret.push(KeyOption::new("g", "go to entry"));
+                }
+                if *row > 0 {
+                    ret.push(KeyOption::new("<Up>", "select above entry"));
+                }
+                if row + 1 < entries.len() {
+                    ret.push(KeyOption::new("<Down>", "select below entry"));
+                }
+                ret.push(KeyOption::new("<Esc>", "back to entries"));
+            }
+            SelectState::Tag { tag, row } => {
+                let entries = tagged_entries(&app.project, tag);
+                if let Some(entry) = entries.get(*row) {
+                    ret.extend(open_options(entry, &app.project, app.ctrl_enter));
+                    ret.push(KeyOption::new("g", "go to entry"));
+                }
+                if *row > 0 {
+                    ret.push(KeyOption::new("<Up>", "select above entry"));
+                }
+                if row + 1 < entries.len() {
+                    ret.push(KeyOption::new("<Down>", "select below entry"));
+                }
+                let tags = entry_tags(&app.project);
+                let tag_idx = tags.iter().position(|other| other == tag).unwrap_or(0);
+                if tag_idx > 0 {
+                    ret.push(KeyOption::new("<Left>", "previous tag"));
+                }
+                if tag_idx + 1 < tags.len() {
+                    ret.push(KeyOption::new("<Right>", "next tag"));
+                }
+                ret.push(KeyOption::new("<Esc>", "back to entries"));
+            }
+            SelectState::Search { input, row, typing } => {
+                let matches = search_matches(&app.project, input);
+                if *typing {
+                    ret.push(KeyOption::new("<Enter>", "act on matching entries"));
+                } else if let Some(idx) = matches.get(*row) {
+                    let entry = &app.project.entries[*idx];
+                    ret.extend(open_options(entry, &app.project, app.ctrl_enter));
+                    ret.push(KeyOption::new("<Delete>", "archive entry"));
+                    ret.push(KeyOption::new("d", "drag entry"));
+                }
+                if *row > 0 {
+                    ret.push(KeyOption::new("<Up>", "select above entry"));
+                }
+                if row + 1 < matches.len() {
+                    ret.push(KeyOption::new("<Down>", "select below entry"));
+                }
+                if !*typing {
+                    ret.push(KeyOption::new("/", "edit search"));
+                }
+                ret.push(KeyOption::new("<Esc>", "clear search"));
+            }
+            SelectState::SearchDrag { input, row, .. } => {
+                let len = search_matches(&app.project, input).len();
+                ret.push(KeyOption::new("<Enter>", "select new location"));
+                if *row > 0 {
+                    ret.push(KeyOption::new("<Up>", "shift one up"));
+                }
+                if row + 1 < len {
+                    ret.push(KeyOption::new("<Down>", "shift one down"));
+                }
+                ret.push(KeyOption::new("<Home>", "shift to top"));
+                ret.push(KeyOption::new("<End>", "shift to bottom"));
+                ret.push(KeyOption::new("<Esc>", "cancel drag"));
+            }
+            SelectState::Day { day, row } => {
+                let len = entries_on(&app.project, *day).len();
+                ret.push(KeyOption::new("<Enter>", "open entry"));
+                if *row > 0 {
+                    ret.push(KeyOption::new("<Up>", "select above entry"));
+                }
+                if row + 1 < len {
+                    ret.push(KeyOption::new("<Down>", "select below entry"));
+                }
+                ret.push(KeyOption::new("<Esc>", "return to calendar"));
+            }
+        }
+        if self.switches_views() && !app.project.config.view_keys.is_empty() {
+            ret.push(KeyOption::new("<View key>", "switch view"));
+        }
+        if app.tabbed && !self.is_text_input() {
+            ret.push(KeyOption::new("<Tab>", "next project"));
+        }
+        match self {
+            _ if self.is_text_input() => {}
+            SelectState::Entry(_) if !app.project.entries.is_empty() => {
+                ret.push(KeyOption::new("V", "send entry to project"));
+            }
+            SelectState::Search { .. } => {
+                ret.push(KeyOption::new("V", "send matches to project"));
+            }
+            _ => {}
+        }
+        if !app.autosave {
+            ret.push(KeyOption::new("<Ctrl+S>", "save changes"));
+        }
+        ret.push(KeyOption::new("q", "quit"));
+        // moved keys are shown as what they were moved to
+        if !self.is_text_input() {
+            for option in &mut ret {
+                if let Some(key) = app.keymap.label(&option.key) {
+                    option.key = key.to_string().into();
+                }
+            }
+        }
+        ret
+    }
+}
+
+struct KeyOption {
+    key: Cow<'static, str>,
+    description: Cow<'static, str>,
+}
+
+impl KeyOption {
+    fn new(key: &'static str, description: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            key: key.into(),
+            description: description.into(),
+        }
+    }
+
+    fn to_line(&self) -> Line<'static> {
+        Line::from(vec![
+            Span::styled(
+                self.key.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("- "),
+            Span::raw(self.description.clone()),
+        ])
+    }
+
+    fn to_accessible_line(&self) -> Line<'static> {
+        Line::from(format!(
+            "Press {} to {}",
+            self.key.trim_start_matches('<').trim_end_matches('>'),
+            self.description
+        ))
+    }
+}
+
+struct OnEvent {
+    next_state: Option<NextState>,
+    save: bool,
+    notice: Option<String>,
+    // paths and urls that couldn't be opened nor copied, to print when the view closes
+    unopened: Vec<String>,
+    // a url copied in a remote session, to show as a QR code
+    qr_code: Option<String>,
+}
+
+enum NextState {
+    Exit,
+    State(SelectState),
+}
+
+impl OnEvent {
+    fn exit() -> Self {
+        Self {
+            next_state: Some(NextState::Exit),
+            save: false,
+            notice: None,
+            unopened: Vec::new(),
+            qr_code: None,
+        }
+    }
+
+    fn without_saving(state: SelectState) -> Self {
+        Self {
+            next_state: Some(NextState::State(state)),
+            save: false,
+            notice: None,
+            unopened: Vec::new(),
+            qr_code: None,
+        }
+    }
+
+    fn with_saving(state: SelectState) -> Self {
+        Self {
+            next_state: Some(NextState::State(state)),
+            save: true,
+            notice: None,
+            unopened: Vec::new(),
+            qr_code: None,
+        }
+    }
+
+    fn ignore() -> Self {
+        Self {
+            next_state: None,
+            save: false,
+            notice: None,
+            unopened: Vec::new(),
+            qr_code: None,
+        }
+    }
+
+    fn with_notice(self, notice: String) -> Self {
+        Self {
+            notice: Some(notice),
+            ..self
+        }
+    }
+
+    /// Copies what couldn't be opened to the clipboard, or keeps it to be printed when the view closes if there is no
+    /// clipboard, and says which in the notice.
+    fn with_unopened(
+        self,
+        unopened: impl IntoIterator<Item = Unopened>,
+        clipboard: &Option<RefCell<ClipboardContext>>,
+    ) -> Self {
+        let unopened = unopened.into_iter().collect::<Vec<_>>();
+        let Some(first) = unopened.first() else {
+            return self;
+        };
+        let targets = unopened
+            .iter()
+            .map(|unopened| unopened.target.clone())
+            .collect::<Vec<_>>();
+        // the clipboard of a remote host isn't the user's, the terminal's is
+        let copied = !first.remote
+            && clipboard.as_ref().is_some_and(|clipboard| {
+                clipboard
+                    .borrow_mut()
+                    .set_contents(targets.join("\n"))
+                    .is_ok()
+            });
+        // without a clipboard here, e.g. over ssh, the terminal may still reach one
+        let copied =
+            copied || (first.reason.is_none() && copy_to_terminal(&targets.join("\n")).is_ok());
+        let qr_code = match unopened.as_slice() {
+            [only] if only.remote && matches!(Link::from(only.target.as_str()), Link::Url(_)) => {
+                Some(only.target.clone())
+            }
+            _ => None,
+        };
+        let because = first
+            .reason
+            .as_ref()
+            .map_or(String::new(), |reason| format!("{reason}, "));
+        if copied {
+            Self { qr_code, ..self }.with_notice(format!(
+                "{because}copied {} to the clipboard{}",
+                targets.join(", "),
+                if first.reason.is_some() {
+                    " instead"
+                } else {
+                    ""
+                }
+            ))
+        } else {
+            let notice = format!(
+                "{because}{} will be printed when flist closes",
+                targets.join(", ")
+            );
+            Self {
+                unopened: targets,
+                qr_code,
+                ..self
+            }
+            .with_notice(notice)
+        }
+    }
+}
+
+enum ListenerMessages {
+    Insert(InsertRequest),
+    InsertMany(Vec<InsertRequest>),
+    // the name of the entry to archive, or its index in the main list
+    Archive(String),
+    // the name of the entry to delete, or its index in the main list
+    Remove(String),
+    Rename(RenameRequest),
+    EditLink(EditLinkRequest),
+    // the entry with this link, that is still named after the link, should be renamed and given the fetched details
+    InferredName {
+        link: String,
+        details: UrlDetails,
+    },
+    // the entry should be renamed to the fetched name, if one was fetched
+    RefreshedTitle {
+        id: u64,
+        details: UrlDetails,
+    },
+    Provided {
+        provider: String,
+        entries: Result<Vec<Entry>, String>,
+    },
+    ReadingTime {
+        id: u64,
+        minutes: u32,
+    },
+    Watched {
+        id: u64,
+        hash: u64,
+    },
+    // the items of a feed, to be added as temporary entries if `expand` is set, or only counted otherwise
+    Feed {
+        id: u64,
+        items: Result<Vec<FeedItem>, String>,
+        expand: bool,
+    },
+    Snapshot {
+        id: u64,
+        path: Result<String, String>,
+    },
+    Downloaded {
+        id: u64,
+        path: Result<String, String>,
+    },
+    TargetStat {
+        link: String,
+        stat: Option<TargetStat>,
+        hash: Option<u64>,
+    },
+    RelinkCandidates {
+        id: u64,
+        candidates: Vec<Candidate>,
+    },
+    GitStatus {
+        dir: String,
+        status: Option<GitStatus>,
+    },
+}
+
+impl ListenerMessages {
+    fn describe(&self) -> String {
+        match self {
+            ListenerMessages::Insert(request) => format!("Added entry {}", request.name),
+            ListenerMessages::InsertMany(requests) => {
+                format!("Adding {} entries", requests.len())
+            }
+            ListenerMessages::Archive(target) => format!("Archiving entry {}", target),
+            ListenerMessages::Remove(target) => format!("Removing entry {}", target),
+            ListenerMessages::Rename(request) => format!("Renaming entry {}", request.rename),
+            ListenerMessages::EditLink(request) => {
+                format!("Changing the link of entry {}", request.edit_link)
+            }
+            ListenerMessages::InferredName {
+                details: UrlDetails {
+                    error: Some(err), ..
+                },
+                ..
+            } => format!("Failed to fetch title: {}", err),
+            ListenerMessages::InferredName { details, .. } => {
+                format!("Fetched title {}", details.name)
+            }
+            ListenerMessages::RefreshedTitle {
+                details: UrlDetails {
+                    error: Some(err), ..
+                },
+                ..
+            } => format!("Failed to refresh title: {}", err),
+            ListenerMessages::RefreshedTitle { details, .. } => {
+                format!("Refreshed title {}", details.name)
+            }
+            ListenerMessages::Provided {
+                provider,
+                entries: Ok(entries),
+            } => format!("{} listed {} entries", provider, entries.len()),
+            ListenerMessages::Provided {
+                entries: Err(err), ..
+            } => err.clone(),
+            ListenerMessages::ReadingTime { minutes, .. } => {
+                format!("Estimated reading time of {} minutes", minutes)
+            }
+            ListenerMessages::Watched { .. } => "Checked a watched page".to_string(),
+            ListenerMessages::Feed {
+                items: Ok(items), ..
+            } => {
+                format!("Fetched {} feed items", items.len())
+            }
+            ListenerMessages::Feed {
+                items: Err(err), ..
+            } => err.clone(),
+            ListenerMessages::Snapshot { path: Ok(path), .. } => format!("Saved snapshot {}", path),
+            ListenerMessages::Snapshot { path: Err(err), .. } => err.clone(),
+            ListenerMessages::Downloaded { path: Ok(path), .. } => format!("Downloaded {}", path),
+            ListenerMessages::Downloaded { path: Err(err), .. } => err.clone(),
+            ListenerMessages::TargetStat { link, .. } => format!("Read size of {}", link),
+            ListenerMessages::GitStatus { dir, .. } => format!("Read git status of {}", dir),
+            ListenerMessages::RelinkCandidates { candidates, .. } => {
+                format!(
+                    "Found {} candidates for the missing target",
+                    candidates.len()
+                )
+            }
+        }
+    }
+
+    fn apply(self, app: &mut App) -> bool {
+        // returns swhether a save is needed
+        match self {
+            ListenerMessages::Insert(request) => app.insert_remote(request),
+            ListenerMessages::InsertMany(requests) => {
+                app.queue_batch(requests);
+                false
+            }
+            ListenerMessages::Archive(target) => app.remove_remote(&target, true),
+            ListenerMessages::Remove(target) => app.remove_remote(&target, false),
+            ListenerMessages::Rename(request) => {
+                let target = request.rename.clone();
+                app.edit_remote(&target, |project, id| {
+                    project.rename(
+                        id,
+                        request.name,
+                        request.expected.as_deref(),
+                        request.revision,
+                    )
+                })
+            }
+            ListenerMessages::EditLink(request) => {
+                let target = request.edit_link.clone();
+                app.edit_remote(&target, |project, id| {
+                    project.change_link(
+                        id,
+                        request.link,
+                        request.expected.as_ref(),
+                        request.revision,
+                    )
+                })
+            }
+            ListenerMessages::InferredName { link, details } => {
+                let renamed = details.name != link;
+                let name = if renamed {
+                    app.project
+                        .unique_name(details.name, &Link::from(link.as_str()))
+                } else {
+                    details.name
+                };
+                // the entry might have been renamed, archived, or deleted in the meantime
+                let entry = app
+                    .project
+                    .entries
+                    .iter_mut()
+                    .chain(app.project.archive.iter_mut())
+                    .find(|entry| entry.link.as_str() == link && entry.name == link);
+                let Some(entry) = entry else {
+                    return false;
+                };
+                // the reason isn't saved
+                entry.name_error = details.error;
+                if renamed {
+                    entry.name = name;
+                    entry.revision += 1;
+                    entry.tags.extend(details.tags);
+                    entry.metadata.extend(details.metadata);
+                }
+                renamed
+            }
+            ListenerMessages::RefreshedTitle { id, details } => {
+                // the entry might have been archived or deleted in the meantime
+                let Some(entry) = app.project.entries.iter().find(|entry| entry.id == id) else {
+                    return false;
+                };
+                let link = entry.link.clone();
+                // a name that falls back on the link means nothing was fetched
+                let renamed = details.name != link.as_str() && details.name != entry.name;
+                let name = renamed.then(|| app.project.unique_name(details.name, &link));
+                let entry = app
+                    .project
+                    .entries
+                    .iter_mut()
+                    .find(|entry| entry.id == id)
+                    .unwrap();
+                entry.name_error = details.error;
+                if let Some(name) = name {
+                    entry.name = name;
+                    entry.revision += 1;
+                }
+                renamed
+            }
+            ListenerMessages::Provided { provider, entries } => {
+                match entries {
+                    Ok(entries) => {
+                        app.project.set_provided(&provider, entries);
+                        if let SelectState::Provided(selected_idx) = app.select_state {
+                            app.select_state = SelectState::Provided(
+                                selected_idx.min(app.project.provided.len().saturating_sub(1)),
+                            );
+                        }
+                    }
+                    Err(err) => app.notice = Some(err),
+                }
+                // provided entries aren't saved
+                false
+            }
+            ListenerMessages::ReadingTime { id, minutes } => {
+                app.project.set_reading_time(id, minutes)
+            }
+            ListenerMessages::Watched { id, hash } => app.project.watched(id, hash),
+            ListenerMessages::Feed { id, items, expand } => match items {
+                Ok(items) if expand => {
+                    let added = app.project.expand_feed(id, items);
+                    app.notice = Some(match added {
+                        0 => "No unread feed items".to_string(),
+                        added => format!("Added {added} feed items"),
+                    });
+                    true
+                }
+                Ok(items) => {
+                    // the count isn't saved
+                    if let Some(entry) = app.project.get_mut(id) {
+                        entry.unread = Some(feed::unread(&items, entry.feed_read));
+                    }
+                    false
+                }
+                // failing to check a feed in the background isn't worth interrupting for
+                Err(err) => {
+                    if expand {
+                        app.notice = Some(err);
                     }
-                    Event::Key(KeyEvent {
-                        code: KeyCode::Esc,
-                        kind: KeyEventKind::Press,
-                        ..
-                    }) => OnEvent::without_saving(Self::Entry(dragged_entry_idx)),
-                    _ => OnEvent::ignore(),
+                    false
+                }
+            },
+            ListenerMessages::Downloaded { id, path } => match path {
+                Ok(path) => {
+                    app.notice = Some(format!("Downloaded {path}"));
+                    let relink = app.project.config.relink_downloads;
+                    app.project.downloaded(id, path, relink)
+                }
+                Err(err) => {
+                    app.notice = Some(err);
+                    false
+                }
+            },
+            ListenerMessages::Snapshot { id, path } => match path {
+                Ok(path) => {
+                    app.notice = Some(format!("Saved snapshot {path}"));
+                    app.project.snapshotted(id, path)
+                }
+                Err(err) => {
+                    app.notice = Some(err);
+                    false
+                }
+            },
+            ListenerMessages::TargetStat { link, stat, hash } => {
+                let hashed = hash.is_some_and(|hash| app.project.hash_target(&link, hash));
+                app.target_stats.insert(link, stat);
+                hashed
+            }
+            ListenerMessages::GitStatus { dir, status } => {
+                app.git_statuses.insert(dir, status);
+                false
+            }
+            ListenerMessages::RelinkCandidates { id, candidates } => {
+                let entry_idx = app.project.entries.iter().position(|entry| entry.id == id);
+                match entry_idx {
+                    // the user moved on to another view in the meantime
+                    Some(_) if !matches!(app.select_state, SelectState::Entry(_)) => {}
+                    Some(_) if candidates.is_empty() => {
+                        app.notice = Some("No candidates found for the missing target".to_string())
+                    }
+                    Some(_) => {
+                        app.select_state = SelectState::Relink {
+                            id,
+                            candidates,
+                            choice: 0,
+                        }
+                    }
+                    None => {}
                 }
+                false
             }
         }
     }
+}
 
-    fn get_options(&self, app: &App) -> Vec<KeyOption> {
-        let mut ret = Vec::new();
-        match self {
-            SelectState::Entry(selected_idx) => {
-                let selected_idx = *selected_idx;
-                if !app.project.entries.is_empty() {
-                    ret.push(KeyOption::new("<Enter>", "open entry"));
-                    let entry = &app.project.entries[selected_idx];
-                    if let Ok(Some(pref)) = entry
-                        .link
-                        .preferred_file(app.project.config.preferred_suffixes.iter())
-                    {
-                        let desc = match &pref.extension {
-                            Some(ext) => format!("open .{} file", ext.to_uppercase()).into(),
-                            None => Cow::Borrowed("open preferred file"),
-                        };
-                        ret.push(KeyOption::new("<Ctrl+Enter>", desc));
-                    }
-                    if selected_idx > 0 {
-                        ret.push(KeyOption::new("<Up>", "select above entry"));
+fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    apps: &mut [App],
+    tick_rate: Duration,
+) -> io::Result<()> {
+    let mut active = 0;
+    loop {
+        for app in apps.iter_mut() {
+            app.tick();
+        }
+        let pending = apps.iter().map(|app| app.pending()).sum::<usize>();
+        if apps[active].quitting && pending == 0 {
+            break Ok(());
+        }
+        let start = Instant::now();
+        terminal.draw(|f| tabs_ui(f, apps, active))?;
+        apps[active].frame_time = start.elapsed();
+
+        let timeout = tick_rate;
+        if crossterm::event::poll(timeout)? {
+            let ev = event::read()?;
+            if apps[active].confirming_quit {
+                let Event::Key(KeyEvent {
+                    code,
+                    kind: KeyEventKind::Press,
+                    ..
+                }) = ev
+                else {
+                    continue;
+                };
+                match code {
+                    // saving from now on also keeps whatever the pending work still changes
+                    KeyCode::Char('y') => {
+                        for app in apps.iter_mut() {
+                            app.autosave = true;
+                            app.save_now();
+                        }
                     }
-                    if selected_idx < app.project.entries.len() - 1 {
-                        ret.push(KeyOption::new("<Down>", "select below entry"));
+                    KeyCode::Char('n') => {}
+                    KeyCode::Esc => {
+                        apps[active].confirming_quit = false;
+                        continue;
                     }
-                    ret.push(KeyOption::new("<Home>", "select first entry"));
-                    ret.push(KeyOption::new("<End>", "select last entry"));
-                    ret.push(KeyOption::new("<Delete>", "archive entry"));
-                    ret.push(KeyOption::new("d", "drag entry"));
-                }
-                if !app.project.archive.is_empty() {
-                    ret.push(KeyOption::new("a", "go to archive"));
+                    _ => continue,
                 }
-                if let Some(clipboard) = &app.clipboard {
-                    if clipboard.borrow_mut().get_contents().is_ok() {
-                        ret.push(KeyOption::new("^v", "paste clipboard"));
-                    }
+                apps[active].confirming_quit = false;
+                if pending == 0 {
+                    break Ok(());
                 }
+                set_quitting(apps, true);
+                continue;
             }
-            SelectState::Archive(selected_idx) => {
-                let selected_idx = *selected_idx;
-                ret.push(KeyOption::new("<Enter>", "open entry"));
-                let entry = &app.project.archive[selected_idx];
-                if let Ok(Some(pref)) = entry
-                    .link
-                    .preferred_file(app.project.config.preferred_suffixes.iter())
+            if apps[active].quitting {
+                if let Event::Key(KeyEvent {
+                    code: KeyCode::Esc,
+                    kind: KeyEventKind::Press,
+                    ..
+                }) = ev
                 {
-                    let desc = match &pref.extension {
-                        Some(ext) => format!("open .{} file", ext.to_uppercase()).into(),
-                        None => Cow::Borrowed("open preferred file"),
-                    };
-                    ret.push(KeyOption::new("<Ctrl+Enter>", desc));
-                }
-                if selected_idx > 0 {
-                    ret.push(KeyOption::new("<Up>", "select above entry"));
+                    set_quitting(apps, false);
+                    continue;
                 }
-                if selected_idx < app.project.archive.len() - 1 {
-                    ret.push(KeyOption::new("<Down>", "select below entry"));
+            }
+            if apps[active].sending.is_some() {
+                send_event(&ev, apps, active);
+                continue;
+            }
+            if let Some(next) = switched_tab(&ev, &apps[active], active, apps.len()) {
+                active = next;
+                let app = &mut apps[active];
+                if app.accessible {
+                    app.announcement = Some(format!("Project {}", project_title(&app.project)));
                 }
-                ret.push(KeyOption::new("<Home>", "select first entry"));
-                ret.push(KeyOption::new("<End>", "select last entry"));
-                ret.push(KeyOption::new("<Delete>", "delete entry forever"));
-                ret.push(KeyOption::new("r", "restore entry"));
-                ret.push(KeyOption::new("a", "return to main entries"));
+                continue;
             }
-            SelectState::Drag { new_position, .. } => {
-                let new_position = *new_position;
-                ret.push(KeyOption::new("<Enter>", "select new location"));
-                if new_position > 0 {
-                    ret.push(KeyOption::new("<Up>", "shift one up"));
+            let app = &mut apps[active];
+            if app.handle_macro_key(&ev) {
+                continue;
+            }
+            if app.handle_event(ev) {
+                if !app.quitting && apps.iter().any(|app| app.dirty) {
+                    apps[active].confirming_quit = true;
+                    continue;
                 }
-                if new_position < app.project.entries.len() - 1 {
-                    ret.push(KeyOption::new("<Down>", "shift one down"));
+                let app = &mut apps[active];
+                // quitting a second time discards whatever is still pending
+                if app.quitting || pending == 0 {
+                    break Ok(());
                 }
-                ret.push(KeyOption::new("<Home>", "shift to top"));
-                ret.push(KeyOption::new("<End>", "shift to bottom"));
-                ret.push(KeyOption::new("<Esc>", "cancel drag"));
+                set_quitting(apps, true);
             }
         }
-        ret.push(KeyOption::new("q", "quit"));
-        ret
     }
 }
 
-struct KeyOption {
-    key: &'static str,
-    description: Cow<'static, str>,
+/// entries of the main list to send to another project
+struct Sending {
+    ids: Vec<u64>,
+    /// the other projects of the registry, as it was when the entries were marked
+    registered: Vec<PathBuf>,
+    /// the picked project, among the targets
+    row: usize,
+}
+
+/// a project entries can be sent to, open in another tab or anywhere else in the registry
+#[derive(Clone)]
+enum SendTarget {
+    Tab(usize),
+    Dir(PathBuf),
+}
+
+/// the projects entries can be sent to from the active tab, the other tabs first
+fn send_targets(apps: &[App], active: usize) -> Vec<SendTarget> {
+    let Some(sending) = &apps[active].sending else {
+        return Vec::new();
+    };
+    let open = apps
+        .iter()
+        .filter_map(|app| app.project.root.canonicalize().ok())
+        .collect::<Vec<_>>();
+    (0..apps.len())
+        .filter(|idx| *idx != active)
+        .map(SendTarget::Tab)
+        .chain(
+            sending
+                .registered
+                .iter()
+                .filter(|root| !open.contains(root))
+                .cloned()
+                .map(SendTarget::Dir),
+        )
+        .collect()
+}
+
+fn target_title(apps: &[App], target: &SendTarget) -> String {
+    match target {
+        SendTarget::Tab(idx) => project_title(&apps[*idx].project),
+        SendTarget::Dir(root) => root.file_name().map_or_else(
+            || root.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        ),
+    }
+}
+
+/// Picks the tab to send the entries to: Enter moves them there, `c` copies them, and Esc keeps them.
+fn send_event(ev: &Event, apps: &mut [App], active: usize) {
+    let Event::Key(KeyEvent {
+        code,
+        kind: KeyEventKind::Press,
+        ..
+    }) = ev
+    else {
+        return;
+    };
+    let targets = send_targets(apps, active);
+    let Some(sending) = &mut apps[active].sending else {
+        return;
+    };
+    match code {
+        KeyCode::Up => sending.row = sending.row.saturating_sub(1),
+        KeyCode::Down => sending.row = (sending.row + 1).min(targets.len() - 1),
+        KeyCode::Enter | KeyCode::Char('c') => {
+            let target = targets[sending.row].clone();
+            let ids = std::mem::take(&mut sending.ids);
+            apps[active].sending = None;
+            send_entries(apps, active, &target, &ids, *code == KeyCode::Char('c'));
+            return;
+        }
+        KeyCode::Esc => {
+            apps[active].sending = None;
+            return;
+        }
+        _ => return,
+    }
+    let row = sending.row;
+    if apps[active].accessible {
+        apps[active].announcement = Some(format!("Send to {}", target_title(apps, &targets[row])));
+    }
+}
+
+/// Adds the entries with `ids` from the tab `from` to the `to` project, where the config of its project puts entries sent
+/// by another flist, and unless `copy` removes them from `from`. Entries that the target refuses stay where they are,
+/// as do entries with nested entries, whose nesting wouldn't carry over, and entries moved to a project open in another
+/// flist.
+fn send_entries(apps: &mut [App], from: usize, to: &SendTarget, ids: &[u64], copy: bool) {
+    let title = target_title(apps, to);
+    let mut refused = None;
+    let mut entries = Vec::new();
+    for id in ids {
+        let Some(mut entry) = apps[from].project.get(*id).cloned() else {
+            continue;
+        };
+        if apps[from]
+            .project
+            .entries
+            .iter()
+            .any(|child| child.parent == Some(*id))
+        {
+            refused.get_or_insert(format!(
+                "{} has nested entries, move them out of it first",
+                entry.name
+            ));
+            continue;
+        }
+        // ids, and the references made of them, don't carry over to another project, and the entry leaves its parent
+        entry.id = 0;
+        entry.parent = None;
+        entry.see_also.clear();
+        entries.push((*id, entry));
+    }
+    let sent = match to {
+        _ if entries.is_empty() => Vec::new(),
+        SendTarget::Tab(to) => {
+            let app = &mut apps[*to];
+            let mut sent = Vec::new();
+            for (id, entry) in entries {
+                match app.project.capture(entry, InsertSource::Remote, None) {
+                    Ok(_) => sent.push(id),
+                    Err(err) => {
+                        refused.get_or_insert(err.to_string());
+                    }
+                }
+            }
+            if !sent.is_empty() {
+                app.save();
+            }
+            sent
+        }
+        SendTarget::Dir(root) => match send_to_dir(root, entries, copy, &apps[from].tracer) {
+            Ok((sent, err)) => {
+                refused = refused.or(err);
+                sent
+            }
+            Err(err) => {
+                refused.get_or_insert(err);
+                Vec::new()
+            }
+        },
+    };
+    if !copy && !sent.is_empty() {
+        let app = &mut apps[from];
+        // the selection stays on the row of an entry moved from under it
+        let row = match app.select_state {
+            SelectState::Entry(selected) if sent.contains(&selected) => {
+                main_idx(&app.project, selected)
+            }
+            _ => None,
+        };
+        for id in &sent {
+            app.project.remove(*id);
+        }
+        if let Some(row) = row {
+            let last = app.project.entries.len().saturating_sub(1);
+            app.select_state = SelectState::entry(&app.project, row.min(last));
+        }
+        app.clamp_selection();
+        app.save();
+    }
+    let verb = if copy { "Copied" } else { "Moved" };
+    apps[from].notice = Some(match refused {
+        Some(err) => format!(
+            "{verb} {} of {} entries to {title}, {err}",
+            sent.len(),
+            ids.len()
+        ),
+        None if sent.len() == 1 => format!("{verb} entry to {title}"),
+        None => format!("{verb} {} entries to {title}", sent.len()),
+    });
+}
+
+/// Adds the entries to the project at `root` that isn't open in a tab: over the remote protocol if another flist has it
+/// open, otherwise by taking its lock and writing them into it. Returns the ids of the entries that were sent, and why
+/// the others were refused.
+///
+/// The running flist doesn't answer inserts, so a lost request goes unnoticed, and a request carries only part of an
+/// entry. Entries are only copied to it, unless `copy` none are sent.
+fn send_to_dir(
+    root: &Path,
+    entries: Vec<(u64, Entry)>,
+    copy: bool,
+    tracer: &Tracer,
+) -> Result<(Vec<u64>, Option<String>), String> {
+    let config = match args::read_config(root, false) {
+        Ok(config) => config,
+        Err(FlistError::Locked(LockedProject::WithListener(_))) if !copy => {
+            return Err("it is open in another flist, copy the entries with c instead".to_string());
+        }
+        Err(FlistError::Locked(LockedProject::WithListener(peer))) => {
+            let (ids, requests): (Vec<_>, Vec<_>) = entries
+                .into_iter()
+                .map(|(id, entry)| (id, InsertRequest::from(entry)))
+                .unzip();
+            args::send_request(peer, "insert-many", 0, &requests, tracer)
+                .map_err(|err| err.to_string())?;
+            return Ok((ids, None));
+        }
+        Err(err) => return Err(err.to_string()),
+    };
+    let _lockfile = LockFile::new(root).map_err(|err| err.to_string())?;
+    let mut project = Project::from_dir(root, config).map_err(|err| err.to_string())?;
+    let mut sent = Vec::new();
+    let mut refused = None;
+    for (id, entry) in entries {
+        match project.capture(entry, InsertSource::Remote, None) {
+            Ok(_) => sent.push(id),
+            Err(err) => {
+                refused.get_or_insert(err.to_string());
+            }
+        }
+    }
+    project.save().map_err(|err| err.to_string())?;
+    Ok((sent, refused))
+}
+
+/// the projects to send the entries to, drawn over the middle of the view
+fn send_ui<B: Backend>(f: &mut Frame<B>, apps: &[App], active: usize, area: Rect) {
+    let Some(sending) = &apps[active].sending else {
+        return;
+    };
+    let lines = send_targets(apps, active)
+        .iter()
+        .enumerate()
+        .map(|(row, target)| {
+            let style = if row == sending.row {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            Line::from(Span::styled(target_title(apps, target), style))
+        })
+        .collect::<Vec<_>>();
+    let title = format!(
+        "Send {} to: <Enter> move, c copy, <Esc> cancel",
+        if sending.ids.len() == 1 {
+            "entry".to_string()
+        } else {
+            format!("{} entries", sending.ids.len())
+        }
+    );
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let area = Rect {
+        y: area.y + (area.height - height) / 2,
+        height,
+        ..area
+    };
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
+
+/// quitting closes all the tabs, so they all wait for their pending work
+fn set_quitting(apps: &mut [App], quitting: bool) {
+    for app in apps {
+        app.quitting = quitting;
+    }
+}
+
+/// the tab Tab or Shift+Tab switches to, if the event is one of them
+fn switched_tab(ev: &Event, app: &App, active: usize, count: usize) -> Option<usize> {
+    if count < 2 || app.select_state.is_text_input() {
+        return None;
+    }
+    match ev {
+        Event::Key(KeyEvent {
+            code: KeyCode::Tab,
+            kind: KeyEventKind::Press,
+            ..
+        }) => Some((active + 1) % count),
+        Event::Key(KeyEvent {
+            code: KeyCode::BackTab,
+            kind: KeyEventKind::Press,
+            ..
+        }) => Some((active + count - 1) % count),
+        _ => None,
+    }
+}
+
+/// the name of the project's directory
+fn project_title(project: &Project) -> String {
+    project
+        .root
+        .canonicalize()
+        .ok()
+        .and_then(|root| Some(root.file_name()?.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| project.root.display().to_string())
+}
+
+/// the current tab, under a bar of the open projects if there are several
+fn tabs_ui<B: Backend>(f: &mut Frame<B>, apps: &mut [App], active: usize) {
+    let mut area = f.size();
+    if apps.len() > 1 {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+            .split(area);
+        area = chunks[1];
+        let titles = apps
+            .iter()
+            .map(|app| Line::from(project_title(&app.project)))
+            .collect::<Vec<_>>();
+        f.render_widget(
+            Tabs::new(titles)
+                .select(active)
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+            chunks[0],
+        );
+    }
+    ui(f, &mut apps[active], area);
+    send_ui(f, apps, active, area);
+    if apps[active].debug {
+        debug_ui(f, &apps[active]);
+    }
+}
+
+/// Timings, queue depths, and the approximate memory of the entry lists, drawn over the top right corner.
+fn debug_ui<B: Backend>(f: &mut Frame<B>, app: &App) {
+    let project = &app.project;
+    let lines = [
+        format!("frame: {:.1?}", app.frame_time),
+        format!("last save: {:.1?}", app.save_time),
+        format!(
+            "messages: {} queued, {} pending",
+            app.background.messages.lock().unwrap().len(),
+            app.background.pending()
+        ),
+        format!(
+            "entries: {} ({} KB)",
+            project.entries.len(),
+            list_bytes(&project.entries) / 1024
+        ),
+        format!(
+            "archive: {} ({} KB)",
+            project.archive.len(),
+            list_bytes(&project.archive) / 1024
+        ),
+        format!(
+            "snoozed: {} ({} KB)",
+            project.snoozed.len(),
+            list_bytes(&project.snoozed) / 1024
+        ),
+        format!(
+            "provided: {} ({} KB)",
+            project.provided.len(),
+            list_bytes(&project.provided) / 1024
+        ),
+    ];
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0) as u16 + 2;
+    let size = f.size();
+    let area = Rect {
+        x: size.width.saturating_sub(width),
+        y: 0,
+        width: width.min(size.width),
+        height: (lines.len() as u16 + 2).min(size.height),
+    };
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>())
+            .block(Block::default().borders(Borders::ALL).title("Debug")),
+        area,
+    );
+}
+
+/// the memory the entries take, roughly, counting the strings they own but not spare capacity
+fn list_bytes(entries: &[Entry]) -> usize {
+    std::mem::size_of_val(entries)
+        + entries
+            .iter()
+            .map(|entry| {
+                entry.name.len()
+                    + entry.link.as_str().len()
+                    + entry.metadata.iter().map(String::len).sum::<usize>()
+                    + entry.tags.iter().map(String::len).sum::<usize>()
+            })
+            .sum::<usize>()
+}
+
+fn status_line(app: &App) -> Option<String> {
+    if app.confirming_quit {
+        Some("Save changes before quitting? y/n, <Esc> to cancel".to_string())
+    } else if app.quitting {
+        Some(format!(
+            "Finishing {} pending task(s)... press q again to discard them, <Esc> to cancel",
+            app.pending()
+        ))
+    } else if let Some(batch) = &app.batch {
+        Some(format!(
+            "Adding entries... {}/{}",
+            batch.total - batch.requests.len(),
+            batch.total
+        ))
+    } else if let SelectState::TimePrompt { input, target, .. } = &app.select_state {
+        Some(format!("{}: {input}", target.label()))
+    } else if let SelectState::Triage {
+        prompt: Some((prompt, input)),
+        ..
+    } = &app.select_state
+    {
+        Some(format!("{}: {input}", prompt.label()))
+    } else if let Some((prefix, _)) = &app.typed {
+        Some(format!("Go to: {prefix}"))
+    } else if let SelectState::Edit { fields, focus, .. } = &app.select_state {
+        Some(format!("{}: {}", edit_label(*focus), fields[*focus]))
+    } else if let SelectState::Notes { lines, row, .. } = &app.select_state {
+        Some(format!("Notes: {}", lines[*row]))
+    } else if let SelectState::FieldPrompt { input, .. } = &app.select_state {
+        Some(format!("Field: {input}"))
+    } else if let SelectState::Palette {
+        input,
+        confirming: Some(count),
+        ..
+    } = &app.select_state
+    {
+        Some(format!(":{input} matches {count} entries, open them all?"))
+    } else if let SelectState::Palette { input, .. } = &app.select_state {
+        Some(format!(":{input}"))
+    } else if let SelectState::Search {
+        input,
+        typing: true,
+        ..
+    } = &app.select_state
+    {
+        Some(format!("/{input}"))
+    } else if let SelectState::FollowLink { .. } | SelectState::Relink { .. } = &app.select_state {
+        Some(app.select_state.describe(&app.project))
+    } else if let Some(progress) = app.background.refresh_progress() {
+        Some(progress)
+    } else if let Some(progress) = app.background.download_progress() {
+        Some(progress)
+    } else if app.dirty {
+        Some(match &app.notice {
+            Some(notice) => format!("[modified] {notice}"),
+            None => "[modified] <Ctrl+S> to save".to_string(),
+        })
+    } else if app.notice.is_none() && !app.size_warnings.is_empty() {
+        Some(format!("Warning: {}", app.size_warnings.join("; ")))
+    } else {
+        app.notice.clone()
+    }
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App, mut area: Rect) {
+    if app.accessible {
+        return accessible_ui(f, app, area);
+    }
+    if let Some(status) = status_line(app) {
+        let status_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
+            .split(area);
+        area = status_chunks[0];
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                status,
+                Style::default().add_modifier(Modifier::REVERSED),
+            )),
+            status_chunks[1],
+        );
+    }
+    if !app.project.config.view_keys.is_empty() {
+        let tab_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+            .split(area);
+        area = tab_chunks[1];
+        view_tabs_ui(f, app, tab_chunks[0]);
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+        .split(area);
+
+    let bottom_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+        .split(chunks[1]);
+
+    let (entrylist, selected_idx, block_title) = app.select_state.list_view(&app.project);
+    let rows = app.select_state.rows(&entrylist, app.project.sort);
+    let selected_row = rows.iter().position(|row| row.idx == selected_idx);
+    let mut list_state = ListState::default().with_selected(selected_row);
+    let markers = rows.iter().any(|row| row.has_children);
+
+    let highlight_modifier = match app.select_state {
+        SelectState::Drag { .. } | SelectState::SearchDrag { .. } | SelectState::Link { .. } => {
+            Modifier::REVERSED
+        }
+        _ => Modifier::BOLD,
+    };
+
+    let list = List::new(
+        column_labels(&rows, &entrylist, markers, &app.project.config.columns)
+            .into_iter()
+            .map(ListItem::new)
+            .collect::<Vec<_>>(),
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(block_title.into_owned()),
+    )
+    .highlight_style(Style::default().add_modifier(highlight_modifier))
+    .highlight_symbol(">>");
+
+    if let SelectState::Board { .. }
+    | SelectState::Drag {
+        column: Some(_), ..
+    } = app.select_state
+    {
+        board_ui(f, app, chunks[0]);
+    } else if let SelectState::Calendar(day) = app.select_state {
+        calendar_ui(f, app, chunks[0], day);
+    } else if let SelectState::Agenda(row) = app.select_state {
+        agenda_ui(f, app, chunks[0], row);
+    } else if let SelectState::Journal { events, row } = &app.select_state {
+        journal_ui(f, chunks[0], events, *row);
+    } else {
+        f.render_stateful_widget(list, chunks[0], &mut list_state);
+    }
+    app.drawn_list = match app.select_state {
+        SelectState::Entry(_)
+        | SelectState::Archive(_)
+        | SelectState::Drag { column: None, .. } => {
+            let inner = Block::default().borders(Borders::ALL).inner(chunks[0]);
+            let drawn = rows
+                .iter()
+                .skip(list_state.offset())
+                .take(inner.height as usize)
+                .map(|row| row.idx)
+                .collect();
+            Some((inner, drawn))
+        }
+        _ => None,
+    };
+
+    if let Some(selected_entry) = app.select_state.selected_entry(&app.project) {
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(
+                    &selected_entry.name,
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" ["),
+                Span::styled(
+                    format!("{}", selected_entry.time_added.format("%x %I:%M %p")),
+                    Style::default().add_modifier(Modifier::ITALIC),
+                ),
+                Span::raw("]"),
+            ]),
+            Line::from(Span::raw("")),
+            Line::from(Span::raw(selected_entry.link.as_str())),
+        ];
+        lines.extend(
+            detail_fields(selected_entry, app)
+                .into_iter()
+                .map(|(label, value)| {
+                    Line::from(Span::styled(
+                        format!("{label}: {value}"),
+                        Style::default().add_modifier(Modifier::ITALIC),
+                    ))
+                }),
+        );
+        if !selected_entry.notes.is_empty() {
+            lines.push(Line::from(Span::raw("")));
+            lines.extend(selected_entry.notes.lines().map(Line::from));
+        }
+        f.render_widget(Paragraph::new(lines), bottom_chunks[0]);
+    }
+
+    let key_options = app
+        .select_state
+        .get_options(app)
+        .into_iter()
+        .map(|opt| opt.to_line())
+        .collect::<Vec<_>>();
+
+    let key_par = Paragraph::new(key_options);
+
+    f.render_widget(key_par, bottom_chunks[1]);
+
+    if let SelectState::Edit { fields, focus, .. } = &app.select_state {
+        edit_ui(f, chunks[0], fields, *focus);
+    }
+    if app.peek_archive {
+        archive_peek_ui(f, chunks[0], &app.project);
+    }
+    if let Some(url) = &app.qr_code {
+        qr_ui(f, area, url);
+    }
+    if let SelectState::Notes { id, lines, row } = &app.select_state {
+        if let Some(entry) = app.project.get(*id) {
+            notes_ui(f, chunks[0], &entry.name, lines, *row);
+        }
+    }
+}
+
+/// the title of the main list, with the order it is sorted in and the size of the archive
+fn entries_title(project: &Project) -> Cow<'static, str> {
+    let mut title = String::from("Entries");
+    if project.sort != tree::Sort::Manual {
+        title.push_str(&format!(" [sorted by {}]", project.sort.label()));
+    }
+    if !project.archive.is_empty() {
+        title.push_str(&format!(" [{} archived]", project.archive.len()));
+    }
+    title.into()
+}
+
+/// the latest archived entries, drawn over the bottom of the list
+fn archive_peek_ui<B: Backend>(f: &mut Frame<B>, area: Rect, project: &Project) {
+    let lines = project
+        .archive
+        .iter()
+        .take(ARCHIVE_PEEK)
+        .map(|entry| {
+            Line::from(vec![
+                Span::raw(entry.name.as_str()),
+                Span::styled(
+                    format!(" ({})", entry.link.as_str()),
+                    Style::default().add_modifier(Modifier::ITALIC),
+                ),
+            ])
+        })
+        .collect::<Vec<_>>();
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let area = Rect {
+        y: area.y + area.height - height,
+        height,
+        ..area
+    };
+    let title = format!(
+        "Latest archived, {} of {}",
+        lines.len(),
+        project.archive.len()
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
+
+/// the url as a QR code, drawn in the middle of the view in light modules on dark so that it scans on dark terminals
+fn qr_ui<B: Backend>(f: &mut Frame<B>, area: Rect, url: &str) {
+    let lines = match QrCode::new(url.as_bytes()) {
+        Ok(code) => code
+            .render::<Dense1x2>()
+            .dark_color(Dense1x2::Light)
+            .light_color(Dense1x2::Dark)
+            .build()
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>(),
+        Err(err) => vec![format!("Failed to make a QR code of {url}: {err}")],
+    };
+    let size = |lines: &[String]| {
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0) as u16;
+        (width + 2, lines.len() as u16 + 2)
+    };
+    let (width, height) = size(&lines);
+    let lines = if width > area.width || height > area.height {
+        vec!["The QR code doesn't fit in the window, enlarge it and copy again".to_string()]
+    } else {
+        lines
+    };
+    let (width, height) = size(&lines);
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    let area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>())
+            .block(Block::default().borders(Borders::ALL).title("Scan to open")),
+        area,
+    );
+}
+
+/// the notes being edited, drawn over the list with the line being typed on highlighted
+fn notes_ui<B: Backend>(f: &mut Frame<B>, area: Rect, name: &str, lines: &[String], row: usize) {
+    // the lines around the one being typed on, if there are more than fit
+    let visible = area.height.saturating_sub(2) as usize;
+    let first = (row + 1).saturating_sub(visible);
+    let text = lines
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(visible)
+        .map(|(idx, line)| {
+            if idx == row {
+                Line::from(Span::styled(
+                    line.as_str(),
+                    Style::default().add_modifier(Modifier::REVERSED),
+                ))
+            } else {
+                Line::from(line.as_str())
+            }
+        })
+        .collect::<Vec<_>>();
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Notes of {name}")),
+        ),
+        area,
+    );
+}
+
+/// the fields of the entry being edited, drawn over the list with the edited one highlighted
+fn edit_ui<B: Backend>(f: &mut Frame<B>, area: Rect, fields: &[String], focus: usize) {
+    let lines = fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            let style = if idx == focus {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            Line::from(vec![
+                Span::raw(format!("{}: ", edit_label(idx))),
+                Span::styled(field.as_str(), style),
+            ])
+        })
+        .collect::<Vec<_>>();
+    let area = Rect {
+        height: (lines.len() as u16 + 2).min(area.height),
+        ..area
+    };
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Edit entry")),
+        area,
+    );
 }
 
-impl KeyOption {
-    fn new(key: &'static str, description: impl Into<Cow<'static, str>>) -> Self {
-        Self {
-            key,
-            description: description.into(),
-        }
+const EDIT_NAME: usize = 0;
+const EDIT_LINK: usize = 1;
+
+fn edit_label(field: usize) -> &'static str {
+    match field {
+        EDIT_NAME => "Name",
+        EDIT_LINK => "Link",
+        _ => "Metadata",
     }
+}
 
-    fn to_line(&self) -> Line<'static> {
-        Line::from(vec![
-            Span::styled(self.key, Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw("- "),
-            Span::raw(self.description.clone()),
-        ])
+/// the name of the view key that `code` is, if it can be bound
+fn view_key_name(code: KeyCode) -> Option<String> {
+    match code {
+        KeyCode::F(number @ 1..=12) => Some(format!("F{number}")),
+        KeyCode::Char(c @ '1'..='9') => Some(c.to_string()),
+        _ => None,
     }
 }
 
-struct OnEvent {
-    next_state: Option<NextState>,
-    save: bool,
+/// the main entries matching the alias or query bound to `key`
+fn view_entries<'a>(project: &'a Project, key: &str) -> Vec<&'a Entry> {
+    let Some(text) = project.config.view_keys.get(key) else {
+        return Vec::new();
+    };
+    let query = Query::parse(query::expand(&project.config.aliases, text));
+    project
+        .entries
+        .iter()
+        .filter(|entry| query.matches(entry))
+        .collect()
 }
 
-enum NextState {
-    Exit,
-    State(SelectState),
+/// the tags of the main entries, sorted
+fn entry_tags(project: &Project) -> Vec<String> {
+    project
+        .entries
+        .iter()
+        .flat_map(|entry| entry.tags.iter().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
 }
 
-impl OnEvent {
-    fn exit() -> Self {
-        Self {
-            next_state: Some(NextState::Exit),
-            save: false,
-        }
+/// the main entries tagged `tag`
+fn tagged_entries<'a>(project: &'a Project, tag: &str) -> Vec<&'a Entry> {
+    project
+        .entries
+        .iter()
+        .filter(|entry| entry.tags.iter().any(|other| other == tag))
+        .collect()
+}
+
+fn tag_title(project: &Project, tag: &str) -> String {
+    let tags = entry_tags(project);
+    match tags.iter().position(|other| other == tag) {
+        Some(idx) => format!("Tag: {tag} ({} of {})", idx + 1, tags.len()),
+        None => format!("Tag: {tag}"),
     }
+}
 
-    fn without_saving(state: SelectState) -> Self {
-        Self {
-            next_state: Some(NextState::State(state)),
-            save: false,
+/// the indices of the main entries whose name, link, or metadata contain `input`, ignoring case
+fn search_matches(project: &Project, input: &str) -> Vec<usize> {
+    let input = input.to_lowercase();
+    project
+        .entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| {
+            [entry.name.as_str(), entry.link.as_str()]
+                .into_iter()
+                .chain(entry.metadata.iter().map(String::as_str))
+                .any(|text| text.to_lowercase().contains(&input))
+        })
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+fn search_title(input: &str) -> String {
+    format!("Search: {input}")
+}
+
+fn view_title(project: &Project, key: &str) -> String {
+    let text = project.config.view_keys.get(key).map_or("", String::as_str);
+    format!("{key}: {text}")
+}
+
+/// a line of the bound views, with the current one highlighted
+fn view_tabs_ui<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let keys = app.project.config.view_keys();
+    let current = match &app.select_state {
+        SelectState::View { key, .. } => keys
+            .iter()
+            .position(|(bound, _)| *bound == key.as_str())
+            .map_or(0, |idx| idx + 1),
+        _ => 0,
+    };
+    let titles = std::iter::once("All".to_string())
+        .chain(keys.iter().map(|(key, text)| format!("{key} {text}")))
+        .map(Line::from)
+        .collect::<Vec<_>>();
+    f.render_widget(
+        Tabs::new(titles)
+            .select(current)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+        area,
+    );
+}
+
+/// the index of the entry with `id` in the main list, None once it was archived, snoozed, or deleted
+fn main_idx(project: &Project, id: u64) -> Option<usize> {
+    match project.locate(id) {
+        Some(EntryLocation::Entries(idx)) => Some(idx),
+        _ => None,
+    }
+}
+
+/// the index of the entry with `id` in `entries`, the first entry if it isn't there
+fn idx_of(entries: &[Entry], id: u64) -> usize {
+    entries.iter().position(|entry| entry.id == id).unwrap_or(0)
+}
+
+/// the id of the entry at `idx` of `entries`, 0, which no entry has, if there is none
+fn id_at(entries: &[Entry], idx: usize) -> u64 {
+    entries.get(idx).map_or(0, |entry| entry.id)
+}
+
+/// the main entries added or due on `day`
+fn entries_on(project: &Project, day: NaiveDate) -> Vec<Entry> {
+    project
+        .entries
+        .iter()
+        .filter(|entry| {
+            entry.time_added.with_timezone(&Local).date_naive() == day
+                || entry
+                    .due
+                    .is_some_and(|due| due.with_timezone(&Local).date_naive() == day)
+        })
+        .cloned()
+        .collect()
+}
+
+/// how many main entries were added and are due on each day
+fn day_counts(project: &Project) -> HashMap<NaiveDate, (usize, usize)> {
+    let mut ret: HashMap<NaiveDate, (usize, usize)> = HashMap::new();
+    for entry in &project.entries {
+        ret.entry(entry.time_added.with_timezone(&Local).date_naive())
+            .or_default()
+            .0 += 1;
+        if let Some(due) = entry.due {
+            ret.entry(due.with_timezone(&Local).date_naive())
+                .or_default()
+                .1 += 1;
         }
     }
+    ret
+}
 
-    fn with_saving(state: SelectState) -> Self {
-        Self {
-            next_state: Some(NextState::State(state)),
-            save: true,
+fn calendar_summary(project: &Project, day: NaiveDate) -> String {
+    let (added, due) = day_counts(project).get(&day).copied().unwrap_or_default();
+    format!("{}: {added} added, {due} due", day.format("%A %x"))
+}
+
+fn calendar_ui<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect, selected: NaiveDate) {
+    const CELL_WIDTH: usize = 11;
+    let counts = day_counts(&app.project);
+    let today = Local::now().date_naive();
+    let first = selected.with_day(1).unwrap();
+    let mut day = first - ChronoDuration::days(first.weekday().num_days_from_monday().into());
+    let mut lines = vec![Line::from(
+        ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+            .iter()
+            .map(|name| {
+                Span::styled(
+                    format!("{name:<CELL_WIDTH$}"),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )
+            })
+            .collect::<Vec<_>>(),
+    )];
+    while day.month() == first.month() || day < first {
+        let mut cells = Vec::new();
+        for _ in 0..7 {
+            let (added, due) = counts.get(&day).copied().unwrap_or_default();
+            let mut summary = Vec::new();
+            if added > 0 {
+                summary.push(format!("+{added}"));
+            }
+            if due > 0 {
+                summary.push(format!("!{due}"));
+            }
+            let text = format!("{:>2} {}", day.day(), summary.join(" "));
+            let mut style = Style::default();
+            if day.month() != first.month() {
+                style = style.add_modifier(Modifier::DIM);
+            }
+            if day == today {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            if day == selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            cells.push(Span::styled(format!("{text:<CELL_WIDTH$}"), style));
+            day = day.succ_opt().unwrap();
         }
+        lines.push(Line::from(cells));
+        lines.push(Line::from(""));
     }
+    let title = format!("{} (+ added, ! due)", first.format("%B %Y"));
+    f.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
 
-    fn ignore() -> Self {
-        Self {
-            next_state: None,
-            save: false,
+fn agenda_ui<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect, selected_row: usize) {
+    let items = agenda::items(&app.project, Utc::now(), DEFAULT_AGENDA_DAYS);
+    let mut list_items = Vec::new();
+    let mut selected = None;
+    let mut current_day = None;
+    for (row, item) in items.iter().enumerate() {
+        if current_day != Some(item.day) {
+            list_items.push(ListItem::new(Span::styled(
+                item.day.format("%A %x").to_string(),
+                Style::default().add_modifier(Modifier::UNDERLINED),
+            )));
+            current_day = Some(item.day);
+        }
+        if row == selected_row {
+            selected = Some(list_items.len());
         }
+        list_items.push(ListItem::new(format!(
+            "  {} [{} {}]",
+            item.entry.name,
+            item.kind.label(),
+            item.at.with_timezone(&Local).format("%I:%M %p")
+        )));
     }
+    if list_items.is_empty() {
+        list_items.push(ListItem::new(format!(
+            "Nothing due or resurfacing in the next {DEFAULT_AGENDA_DAYS} days"
+        )));
+    }
+    let mut list_state = ListState::default().with_selected(selected);
+    let list = List::new(list_items)
+        .block(Block::default().borders(Borders::ALL).title("Agenda"))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">>");
+    f.render_stateful_widget(list, area, &mut list_state);
 }
 
-enum ListenerMessages {
-    Insert(InsertRequest),
+fn journal_ui<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    events: &[JournalEvent],
+    selected_row: usize,
+) {
+    let list_items = events
+        .iter()
+        .map(|event| ListItem::new(journal::render([event]).trim_end().to_string()))
+        .collect::<Vec<_>>();
+    let mut list_state = ListState::default().with_selected(Some(selected_row));
+    let list = List::new(list_items)
+        .block(Block::default().borders(Borders::ALL).title("Journal"))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">>");
+    f.render_stateful_widget(list, area, &mut list_state);
 }
 
-impl ListenerMessages {
-    fn apply(self, app: &mut App) -> bool {
-        // returns swhether a save is needed
+/// a column of the board
+#[derive(Debug, Clone, PartialEq)]
+enum BoardColumn {
+    // the entries without a section
+    NoSection,
+    Section(String),
+    // the entries whose section is missing from the config
+    Other,
+}
+
+impl BoardColumn {
+    fn holds(&self, project: &Project, entry: &Entry) -> bool {
+        match (self, &entry.section) {
+            (Self::NoSection, section) => section.is_none(),
+            (Self::Section(name), Some(section)) => name == section,
+            (Self::Other, Some(section)) => project.config.section(section).is_none(),
+            (_, None) => false,
+        }
+    }
+
+    /// the section of entries moved into the column, None for the other column, which entries can't be moved into
+    fn section(&self) -> Option<Option<String>> {
         match self {
-            ListenerMessages::Insert(request) => {
-                app.project.insert_entry(request.into());
-                true
-            }
+            Self::NoSection => Some(None),
+            Self::Section(name) => Some(Some(name.clone())),
+            Self::Other => None,
         }
     }
 }
 
-impl From<RemoteRequest> for ListenerMessages {
-    fn from(request: RemoteRequest) -> Self {
-        match request {
-            RemoteRequest::Insert(request) => Self::Insert(request),
-        }
+/// the columns of the board: no section, the configured sections, and the other sections if any entry is in one
+fn board_columns(project: &Project) -> Vec<BoardColumn> {
+    let mut ret = std::iter::once(BoardColumn::NoSection)
+        .chain(
+            project
+                .config
+                .sections
+                .iter()
+                .map(|section| BoardColumn::Section(section.name.clone())),
+        )
+        .collect::<Vec<_>>();
+    if project
+        .entries
+        .iter()
+        .any(|entry| BoardColumn::Other.holds(project, entry))
+    {
+        ret.push(BoardColumn::Other);
     }
+    ret
 }
 
-fn run_app<B: Backend>(
-    terminal: &mut Terminal<B>,
-    mut app: App,
-    tick_rate: Duration,
-) -> io::Result<()> {
-    loop {
-        app.apply_messages();
-        terminal.draw(|f| ui(f, &mut app))?;
+/// the entries a triage walks through: the inbox, or the entries added in the last `NEW_ENTRY_DAYS` if it is empty
+fn triage_queue(project: &Project) -> Vec<u64> {
+    let inbox = project.inbox();
+    if !inbox.is_empty() {
+        return inbox
+            .into_iter()
+            .map(|idx| project.entries[idx].id)
+            .collect();
+    }
+    let since = Utc::now() - ChronoDuration::days(NEW_ENTRY_DAYS);
+    project
+        .entries
+        .iter()
+        .filter(|entry| entry.time_added >= since)
+        .map(|entry| entry.id)
+        .collect()
+}
 
-        let timeout = tick_rate;
-        if crossterm::event::poll(timeout)? {
-            let ev = event::read()?;
-            let on_event = app
-                .select_state
-                .on_event(ev, &mut app.project, &app.clipboard);
-            if on_event.save {
-                app.project.save();
-            }
+/// the section after the one of the entry at `entry_idx`, cycling through the configured sections and then back to
+/// no section
+fn next_section(project: &Project, entry_idx: usize) -> Option<String> {
+    let sections = &project.config.sections;
+    let next = match &project.entries[entry_idx].section {
+        None => 0,
+        Some(current) => sections
+            .iter()
+            .position(|section| &section.name == current)
+            .map_or(0, |idx| idx + 1),
+    };
+    sections.get(next).map(|section| section.name.clone())
+}
 
-            match on_event.next_state {
-                None => {}
-                Some(NextState::Exit) => {
-                    break Ok(());
-                }
-                Some(NextState::State(new_state)) => {
-                    app.select_state = new_state;
-                }
+/// the indices of the main entries in `column`, in list order
+fn column_indices(project: &Project, column: &BoardColumn) -> Vec<usize> {
+    project
+        .entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| column.holds(project, entry))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+fn column_title(project: &Project, column: &BoardColumn) -> String {
+    let count = column_indices(project, column).len();
+    match column {
+        BoardColumn::NoSection => format!("No section ({count})"),
+        BoardColumn::Section(name) => {
+            match project.config.section(name).and_then(|config| config.max) {
+                Some(max) => format!("{name} ({count}/{max})"),
+                None => format!("{name} ({count})"),
             }
         }
+        BoardColumn::Other => format!("Other sections ({count})"),
     }
 }
 
-fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
-        .split(f.size());
-
-    let bottom_chunks = Layout::default()
+fn board_ui<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let columns = board_columns(&app.project);
+    let layout = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
-        .split(chunks[1]);
-
-    let (entrylist, mut list_state, block_title) = match app.select_state {
-        SelectState::Entry(selected_idx) => (
-            Cow::Borrowed(&app.project.entries),
-            ListState::default().with_selected(Some(selected_idx)),
-            "Entries",
-        ),
-        SelectState::Archive(selected_idx) => (
-            Cow::Borrowed(&app.project.archive),
-            ListState::default().with_selected(Some(selected_idx)),
-            "Archive",
-        ),
+        .constraints(
+            columns
+                .iter()
+                .map(|_| Constraint::Ratio(1, columns.len() as u32))
+                .collect::<Vec<_>>(),
+        )
+        .split(area);
+    let (dragged, selected_column, selected_row) = match app.select_state {
+        SelectState::Board { column, row } => (None, column, row),
         SelectState::Drag {
-            dragged_entry_idx,
-            new_position,
-        } => {
-            let mut entries = app.project.entries.clone();
-            let dragged_entry = entries.remove(dragged_entry_idx);
-            entries.insert(new_position, dragged_entry);
-            (
-                Cow::Owned(entries),
-                ListState::default().with_selected(Some(new_position)),
-                "Entries",
-            )
-        }
+            dragged_id,
+            column: Some(column),
+            ..
+        } => (main_idx(&app.project, dragged_id), column, usize::MAX),
+        _ => unreachable!(),
     };
+    for (column, (section, chunk)) in columns.iter().zip(layout.iter()).enumerate() {
+        let mut indices = column_indices(&app.project, section);
+        // the dragged entry is shown at the bottom of the column it would be moved to
+        if let Some(dragged) = dragged {
+            indices.retain(|idx| *idx != dragged);
+            if column == selected_column {
+                indices.push(dragged);
+            }
+        }
+        let items = indices
+            .iter()
+            .map(|idx| ListItem::new(app.project.entries[*idx].name.clone()))
+            .collect::<Vec<_>>();
+        let mut list_state = ListState::default();
+        if column == selected_column {
+            list_state.select(Some(selected_row.min(indices.len().saturating_sub(1))));
+        }
+        let highlight_modifier = if dragged.is_some() {
+            Modifier::REVERSED
+        } else {
+            Modifier::BOLD
+        };
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(column_title(&app.project, section)),
+            )
+            .highlight_style(Style::default().add_modifier(highlight_modifier))
+            .highlight_symbol(">>");
+        f.render_stateful_widget(list, *chunk, &mut list_state);
+    }
+}
+
+/// the main entries as they would be ordered if the dragged entry were dropped at `new_position`
+fn drag_order(project: &Project, dragged_id: u64, new_position: usize) -> Vec<Entry> {
+    let mut entries = project.entries.clone();
+    if let Some(dragged_entry_idx) = main_idx(project, dragged_id) {
+        let dragged_entry = entries.remove(dragged_entry_idx);
+        entries.insert(new_position.min(entries.len()), dragged_entry);
+    }
+    entries
+}
 
-    let highlight_modifier = if let SelectState::Drag { .. } = app.select_state {
-        Modifier::REVERSED
+/// the entry's name, preceded by indent guides and, if any entry in the list has children, an expansion marker
+fn row_label(row: &tree::Row, entry: &Entry, markers: bool) -> String {
+    let marker = match (row.has_children, entry.collapsed) {
+        (true, true) => "▸ ",
+        (true, false) => "▾ ",
+        (false, _) if markers => "  ",
+        (false, _) => "",
+    };
+    let star = if entry.starred { "★ " } else { "" };
+    let pin = if entry.pinned { "[pinned] " } else { "" };
+    let inbox = if entry.inbox { "[inbox] " } else { "" };
+    let temp = if entry.ephemeral { "[temp] " } else { "" };
+    let changed = if entry.watch.as_ref().is_some_and(Watch::changed) {
+        "[changed] "
     } else {
-        Modifier::BOLD
+        ""
     };
+    let mut ret = format!(
+        "{}{marker}{pin}{inbox}{temp}{changed}{star}{}",
+        "│ ".repeat(row.depth),
+        entry.name
+    );
+    if let Some(duration) = media::duration(entry) {
+        ret.push_str(&format!(" [{duration}]"));
+    }
+    if let Some(unread @ 1..) = entry.unread {
+        ret.push_str(&format!(" ({unread} unread)"));
+    }
+    ret
+}
 
-    let list = List::new(
-        entrylist
-            .iter()
-            .map(|entry| ListItem::new(entry.name.clone()))
-            .collect::<Vec<_>>(),
-    )
-    .block(Block::default().borders(Borders::ALL).title(block_title))
-    .highlight_style(Style::default().add_modifier(highlight_modifier))
-    .highlight_symbol(">>");
+/// the labels of the rows, followed by the configured custom fields in aligned columns
+fn column_labels(
+    rows: &[tree::Row],
+    entries: &[Entry],
+    markers: bool,
+    columns: &[String],
+) -> Vec<String> {
+    let mut ret = rows
+        .iter()
+        .map(|row| row_label(row, &entries[row.idx], markers))
+        .collect::<Vec<_>>();
+    for column in columns {
+        let width = ret.iter().map(|label| label.chars().count()).max();
+        for (label, row) in ret.iter_mut().zip(rows) {
+            let padding = width.unwrap_or(0) - label.chars().count();
+            let value = entries[row.idx]
+                .field(column)
+                .map(ToString::to_string)
+                .unwrap_or_default();
+            label.push_str(&format!("{}  {value}", " ".repeat(padding)));
+        }
+    }
+    ret
+}
 
-    f.render_stateful_widget(list, chunks[0], &mut list_state);
+/// whether Enter (rather than Ctrl+Enter) opens the entry's preferred file
+fn quick_launch_on_enter(entry: &Entry, config: &FlistConfig) -> bool {
+    matches!(entry.link, Link::Directory(_))
+        && config.enter_on_directory == DirectoryEnter::PreferredFile
+}
 
-    let selected_entry = match app.select_state {
-        SelectState::Entry(0) if app.project.entries.is_empty() => None,
-        SelectState::Entry(selected_idx) => Some(&app.project.entries[selected_idx]),
-        SelectState::Archive(selected_idx) => Some(&app.project.archive[selected_idx]),
-        SelectState::Drag {
-            dragged_entry_idx, ..
-        } => Some(&app.project.entries[dragged_entry_idx]),
+/// whether the view runs away from the user's screen, as `remote_session` says or else as detected
+fn remote_session(config: &FlistConfig) -> bool {
+    config
+        .remote_session
+        .unwrap_or_else(opener::is_remote_session)
+}
+
+/// a path or url that no program could open, or that was to be copied instead of opened
+struct Unopened {
+    target: String,
+    /// why it couldn't be opened, None if copying it was asked for
+    reason: Option<String>,
+    /// copied in a remote session, to the terminal's clipboard, and shown as a QR code if it is a url
+    remote: bool,
+}
+
+/// Opens the entry's link, or its preferred file if there is one and `ctrl` asks for the non-default open action. Urls
+/// that `enter_on_url` says to copy, and every entry on Enter in a remote session, are returned to be copied.
+fn open_entry(entry: &Entry, project: &Project, ctrl: bool) -> Result<(), Unopened> {
+    let config = &project.config;
+    let remote = remote_session(config);
+    let copy = match entry.link {
+        Link::Url(_) => ctrl != (remote || config.enter_on_url == UrlEnter::Copy),
+        _ => remote && !ctrl,
     };
+    if copy {
+        return Err(Unopened {
+            target: entry
+                .link
+                .feed_url()
+                .unwrap_or(entry.link.as_str())
+                .to_string(),
+            reason: None,
+            remote,
+        });
+    }
+    // in a remote session the alternate key opens what Enter would have
+    let ctrl = ctrl && !remote;
+    if ctrl != quick_launch_on_enter(entry, config) {
+        if let Ok(Some(pref)) =
+            entry.preferred_file(&project.root, config.preferred_suffixes.iter())
+        {
+            return pref.open().map_err(|reason| Unopened {
+                target: pref.file.as_str().to_string(),
+                reason: Some(reason),
+                remote: false,
+            });
+        }
+    }
+    entry.link.explore().map_err(|reason| Unopened {
+        target: entry
+            .link
+            .feed_url()
+            .unwrap_or(entry.link.as_str())
+            .to_string(),
+        reason: Some(reason),
+        remote: false,
+    })
+}
 
-    if let Some(selected_entry) = selected_entry {
-        let entry_data = Paragraph::new(vec![
-            Line::from(vec![
-                Span::styled(
-                    &selected_entry.name,
-                    Style::default().add_modifier(Modifier::BOLD),
-                ),
-                Span::raw(" ["),
-                Span::styled(
-                    format!("{}", selected_entry.time_added.format("%x %I:%M %p")),
-                    Style::default().add_modifier(Modifier::ITALIC),
-                ),
-                Span::raw("]"),
-            ]),
-            Line::from(Span::raw("")),
-            Line::from(Span::raw(selected_entry.link.as_str())),
-        ]);
-        f.render_widget(entry_data, bottom_chunks[0]);
+/// Asks the terminal to put `text` on its clipboard with an OSC 52 sequence, which reaches the local clipboard over
+/// ssh. Terminals that don't support it ignore it.
+fn copy_to_terminal(text: &str) -> io::Result<()> {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in text.as_bytes().chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (idx, byte)| {
+            bits | (*byte as u32) << (16 - 8 * idx)
+        });
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                encoded.push(ALPHABET[(bits >> (18 - 6 * idx) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
     }
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{encoded}\x07")?;
+    stdout.flush()
+}
 
-    let key_options = app
-        .select_state
-        .get_options(app)
-        .into_iter()
-        .map(|opt| opt.to_line())
+/// whether the key asks for the non-default open action, `o` stands in for Ctrl+Enter in terminals that can't send it
+fn is_alternate_open(code: KeyCode, modifiers: KeyModifiers) -> bool {
+    code == KeyCode::Char('o') || modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// the key options for Enter and Ctrl+Enter (or `o` if the terminal can't send it) on the entry
+fn open_options(entry: &Entry, project: &Project, ctrl_enter: bool) -> Vec<KeyOption> {
+    let config = &project.config;
+    let alternate = if ctrl_enter { "<Ctrl+Enter>" } else { "o" };
+    if remote_session(config) {
+        let copy = match entry.link {
+            Link::Url(_) => "copy url, show QR code",
+            _ => "copy path (remote session)",
+        };
+        return vec![
+            KeyOption::new("<Enter>", copy),
+            KeyOption::new(alternate, "open entry on this host"),
+        ];
+    }
+    if let Link::Url(_) = entry.link {
+        return match config.enter_on_url {
+            UrlEnter::Open => vec![
+                KeyOption::new("<Enter>", "open entry"),
+                KeyOption::new(alternate, "copy url"),
+            ],
+            UrlEnter::Copy => vec![
+                KeyOption::new("<Enter>", "copy url"),
+                KeyOption::new(alternate, "open entry"),
+            ],
+        };
+    }
+    let Ok(Some(pref)) = entry.preferred_file(&project.root, config.preferred_suffixes.iter())
+    else {
+        return vec![KeyOption::new("<Enter>", "open entry")];
+    };
+    let pref_desc = match &pref.extension {
+        Some(ext) => format!("open .{} file", ext.to_uppercase()).into(),
+        None => Cow::Borrowed("open preferred file"),
+    };
+    if quick_launch_on_enter(entry, config) {
+        vec![
+            KeyOption::new("<Enter>", pref_desc),
+            KeyOption::new(alternate, "open directory"),
+        ]
+    } else {
+        vec![
+            KeyOption::new("<Enter>", "open entry"),
+            KeyOption::new(alternate, pref_desc),
+        ]
+    }
+}
+
+/// what is fetched about a pasted url
+struct UrlDetails {
+    name: String,
+    tags: Vec<String>,
+    metadata: Vec<String>,
+    // why the page couldn't be read, even if the name was found otherwise
+    error: Option<String>,
+}
+
+/// the details of a pasted url or feed, issues and videos are described through their site's api
+fn infer_url_details(link: &Link, steps: &[NameStep], net: &Net) -> UrlDetails {
+    let url = link.as_str();
+    if let Some(feed_url) = link.feed_url() {
+        let feed = feed::fetch(feed_url, net);
+        return UrlDetails {
+            name: feed
+                .as_ref()
+                .ok()
+                .and_then(|feed| feed.title.clone())
+                .unwrap_or_else(|| url.to_string()),
+            tags: Vec::new(),
+            metadata: Vec::new(),
+            error: feed.err(),
+        };
+    }
+    if let Some(Ok(info)) = issues::parse(url).map(|issue| issues::fetch(&issue, net)) {
+        return UrlDetails {
+            name: info.name,
+            tags: info.tags,
+            metadata: Vec::new(),
+            error: None,
+        };
+    }
+    if let Some(Ok(info)) = media::site(url).map(|site| media::fetch(site, url, net)) {
+        return UrlDetails {
+            name: info.title,
+            tags: Vec::new(),
+            metadata: info.metadata,
+            error: None,
+        };
+    }
+    let inferred = naming::infer(url, steps, net);
+    UrlDetails {
+        name: inferred.name.unwrap_or_else(|| url.to_string()),
+        tags: Vec::new(),
+        metadata: Vec::new(),
+        error: inferred.error,
+    }
+}
+
+/// details of an entry beyond its name, date, and link, as label-value pairs
+fn detail_fields(entry: &Entry, app: &App) -> Vec<(&'static str, String)> {
+    let project = &app.project;
+    let mut ret = Vec::new();
+    if app.debug {
+        ret.push(("Id", entry.id.to_string()));
+        ret.push(("Revision", entry.revision.to_string()));
+    }
+    if let Some(due) = entry.due {
+        ret.push((
+            "Due",
+            due.with_timezone(&Local).format("%x %I:%M %p").to_string(),
+        ));
+    }
+    if let Some(until) = entry.snoozed_until {
+        ret.push((
+            "Snoozed until",
+            until
+                .with_timezone(&Local)
+                .format("%x %I:%M %p")
+                .to_string(),
+        ));
+    }
+    if let Some(section) = &entry.section {
+        ret.push(("Section", section.clone()));
+    }
+    if let Some(provider) = &entry.provider {
+        ret.push(("Provider", provider.clone()));
+    }
+    if !entry.tags.is_empty() {
+        ret.push(("Tags", entry.tags.join(", ")));
+    }
+    if !entry.metadata.is_empty() {
+        ret.push(("Metadata", entry.metadata.join(", ")));
+    }
+    if !entry.fields.is_empty() {
+        let fields = entry
+            .fields
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>();
+        ret.push(("Fields", fields.join(", ")));
+    }
+    if let Some(err) = &entry.name_error {
+        ret.push(("Title not fetched", err.clone()));
+    }
+    if let Some(minutes) = entry.reading_minutes {
+        ret.push(("Reading time", format!("{minutes} min")));
+    }
+    if let Some(snapshot) = &entry.snapshot {
+        ret.push(("Snapshot", snapshot.clone()));
+    }
+    if let Some(download) = &entry.download {
+        ret.push(("Download", download.clone()));
+    }
+    if let Some(Some(stat)) = app.target_stats.get(entry.link.as_str()) {
+        ret.push(("Size", target::format_size(stat.size)));
+        if let Some(modified) = stat.modified {
+            ret.push((
+                "Modified",
+                modified
+                    .with_timezone(&Local)
+                    .format("%x %I:%M %p")
+                    .to_string(),
+            ));
+        }
+    }
+    let suffixes = &project.config.preferred_suffixes;
+    if let Ok(counts) = entry.link.suffix_matches(suffixes.iter()) {
+        // the first layer with matches decides, like it does for the preferred file
+        let mut decided = false;
+        let layers = suffixes
+            .iter()
+            .zip(counts)
+            .map(|(layer, count)| {
+                let note = match count {
+                    0 => "",
+                    _ if decided => "",
+                    1 => " (picked)",
+                    _ => " (ambiguous)",
+                };
+                decided |= count > 0;
+                format!("{}: {count}{note}", layer.join("|"))
+            })
+            .collect::<Vec<_>>();
+        if !layers.is_empty() {
+            ret.push(("Quick launch", layers.join(", ")));
+        }
+    }
+    if let Some(Some(status)) = app.git_statuses.get(entry.link.as_str()) {
+        let dirty = if status.dirty {
+            ", uncommitted changes"
+        } else {
+            ""
+        };
+        ret.push(("Git", format!("{}{dirty}", status.branch)));
+    }
+    if let Some(item) = project.history.iter().find(|item| item.id == entry.id) {
+        ret.push((
+            "Last opened",
+            item.opened_at
+                .with_timezone(&Local)
+                .format("%x %I:%M %p")
+                .to_string(),
+        ));
+    }
+    if let Some(added_by) = &entry.added_by {
+        ret.push(("Added by", added_by.clone()));
+    }
+    if let Some(modified_by) = &entry.modified_by {
+        ret.push(("Modified by", modified_by.clone()));
+    }
+    if let Some(parent) = entry.parent.and_then(|id| project.get(id)) {
+        ret.push(("Inside", parent.name.clone()));
+    }
+    let see_also = entry
+        .see_also
+        .iter()
+        .filter_map(|id| project.get(*id))
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+    if !see_also.is_empty() {
+        ret.push(("See also", see_also.join(", ")));
+    }
+    let referenced_by = project
+        .referenced_by(entry.id)
+        .map(|entry| entry.name.as_str())
         .collect::<Vec<_>>();
+    if !referenced_by.is_empty() {
+        ret.push(("Referenced by", referenced_by.join(", ")));
+    }
+    ret
+}
 
-    let key_par = Paragraph::new(key_options);
+fn accessible_ui<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
+    let (entries, selected_idx, title) = app.select_state.list_view(&app.project);
+    let rows = app.select_state.rows(&entries, app.project.sort);
+    let selected_row = rows.iter().position(|row| row.idx == selected_idx);
+    let key_options = app.select_state.get_options(app);
+    let mut details = Vec::new();
+    if let Some(entry) = app.select_state.selected_entry(&app.project) {
+        details.push(Line::from(format!("Name: {}", entry.name)));
+        details.push(Line::from(format!(
+            "Added: {}",
+            entry.time_added.format("%x %I:%M %p")
+        )));
+        details.push(Line::from(format!("Link: {}", entry.link.as_str())));
+        details.extend(
+            detail_fields(entry, app)
+                .into_iter()
+                .map(|(label, value)| Line::from(format!("{label}: {value}"))),
+        );
+    }
+    if let Some(status) = status_line(app) {
+        details.push(Line::from(format!("Status: {status}")));
+    } else if let Some(announcement) = &app.announcement {
+        details.push(Line::from(format!("Status: {announcement}")));
+    }
 
-    f.render_widget(key_par, bottom_chunks[1]);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Min(3),
+                Constraint::Length(details.len() as u16),
+                Constraint::Length(key_options.len() as u16 + 1),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    let header = match selected_row {
+        None if rows.is_empty() => format!("{title}: empty"),
+        None => format!("{title}: {} shown", rows.len()),
+        Some(selected_row) => format!("{title}: {} of {} selected", selected_row + 1, rows.len()),
+    };
+    f.render_widget(Paragraph::new(header), chunks[0]);
+
+    let items = rows
+        .iter()
+        .enumerate()
+        .map(|(pos, row)| {
+            let entry = &entries[row.idx];
+            let mut label = format!("{}. {}", pos + 1, entry.name);
+            if row.depth > 0 {
+                label.push_str(&format!(", level {}", row.depth + 1));
+            }
+            if row.has_children {
+                label.push_str(if entry.collapsed {
+                    ", collapsed"
+                } else {
+                    ", expanded"
+                });
+            }
+            if entry.inbox {
+                label.push_str(", in inbox");
+            }
+            if entry.ephemeral {
+                label.push_str(", temporary");
+            }
+            if entry.watch.as_ref().is_some_and(Watch::changed) {
+                label.push_str(", changed since last opened");
+            }
+            if let Some(unread @ 1..) = entry.unread {
+                label.push_str(&format!(", {unread} unread items"));
+            }
+            if row.idx == selected_idx {
+                label.push_str(" (selected)");
+            }
+            ListItem::new(label)
+        })
+        .collect::<Vec<_>>();
+    let mut list_state = ListState::default().with_selected(selected_row);
+    f.render_stateful_widget(List::new(items), chunks[1], &mut list_state);
+
+    f.render_widget(Paragraph::new(details), chunks[2]);
+
+    let mut key_lines = vec![Line::from("Keys:")];
+    key_lines.extend(key_options.iter().map(KeyOption::to_accessible_line));
+    f.render_widget(Paragraph::new(key_lines), chunks[3]);
 }
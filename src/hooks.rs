@@ -0,0 +1,41 @@
+use std::path::Path;
+use std::process::Stdio;
+
+use crate::config::HooksConfig;
+use crate::events::ProjectEvent;
+use crate::shell;
+
+/// Runs the configured hook of every event and waits for it to finish. A failing hook doesn't stop the rest.
+pub fn run(hooks: &HooksConfig, project_root: &Path, events: &[ProjectEvent]) {
+    for event in events {
+        let Some(command) = hooks.command(event.kind) else {
+            continue;
+        };
+        let entry = &event.entry;
+        let mut command = shell::command(command);
+        // hooks run while the view is drawn, their output would garble it
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .current_dir(project_root)
+            .env("FLIST_PROJECT", project_root)
+            .env("FLIST_ENTRY_ID", entry.id.to_string())
+            .env("FLIST_ENTRY_NAME", &entry.name)
+            .env("FLIST_ENTRY_LINK", entry.link.as_str())
+            .env("FLIST_ENTRY_ADDED", entry.time_added.to_rfc3339())
+            .env("FLIST_ENTRY_METADATA", entry.metadata.join("\n"));
+        if let Some(due) = entry.due {
+            command.env("FLIST_ENTRY_DUE", due.to_rfc3339());
+        }
+        if let Some(section) = &entry.section {
+            command.env("FLIST_ENTRY_SECTION", section);
+        }
+        if !entry.tags.is_empty() {
+            command.env("FLIST_ENTRY_TAGS", entry.tags.join("\n"));
+        }
+        if let Ok(mut child) = command.spawn() {
+            let _ = child.wait();
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::net::Net;
+
+const API_TIMEOUT: Duration = Duration::from_millis(3000);
+// github's api rejects requests without a user agent
+const API_UA: &str = concat!("flist/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Clone, PartialEq)]
+enum Forge {
+    GitHub,
+    /// a gitlab instance, by host
+    GitLab(String),
+}
+
+/// an issue or a pull/merge request on a recognized forge
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssueRef {
+    forge: Forge,
+    /// e.g. "owner/repo", gitlab projects can be nested in groups
+    repo: String,
+    is_pull: bool,
+    number: u64,
+}
+
+#[derive(Debug)]
+pub struct IssueInfo {
+    pub name: String,
+    /// the repo and the state, e.g. ["owner/repo", "open"]
+    pub tags: Vec<String>,
+}
+
+/// Recognizes github issue/PR urls, and gitlab issue/MR urls on gitlab.com or any host with a gitlab-style path.
+pub fn parse(url: &str) -> Option<IssueRef> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let rest = rest.split(['?', '#']).next()?.trim_end_matches('/');
+    let (host, path) = rest.split_once('/')?;
+    let segments = path.split('/').collect::<Vec<_>>();
+    if host == "github.com" || host == "www.github.com" {
+        let [owner, repo, kind, number] = segments.as_slice() else {
+            return None;
+        };
+        let is_pull = match *kind {
+            "issues" => false,
+            "pull" => true,
+            _ => return None,
+        };
+        return Some(IssueRef {
+            forge: Forge::GitHub,
+            repo: format!("{owner}/{repo}"),
+            is_pull,
+            number: number.parse().ok()?,
+        });
+    }
+    // gitlab paths look like group/subgroup/project/-/issues/12
+    let marker = segments.iter().position(|segment| *segment == "-")?;
+    let [kind, number] = &segments[marker + 1..] else {
+        return None;
+    };
+    let is_pull = match *kind {
+        "issues" => false,
+        "merge_requests" => true,
+        _ => return None,
+    };
+    if marker < 2 {
+        return None;
+    }
+    Some(IssueRef {
+        forge: Forge::GitLab(host.to_string()),
+        repo: segments[..marker].join("/"),
+        is_pull,
+        number: number.parse().ok()?,
+    })
+}
+
+/// Fetches the title and state of the issue from the forge's public api.
+pub fn fetch(issue: &IssueRef, net: &Net) -> Result<IssueInfo, String> {
+    let api_url = match &issue.forge {
+        // the issues endpoint also serves pull requests
+        Forge::GitHub => format!(
+            "https://api.github.com/repos/{}/issues/{}",
+            issue.repo, issue.number
+        ),
+        Forge::GitLab(host) => format!(
+            "https://{host}/api/v4/projects/{}/{}/{}",
+            issue.repo.replace('/', "%2F"),
+            if issue.is_pull {
+                "merge_requests"
+            } else {
+                "issues"
+            },
+            issue.number
+        ),
+    };
+    let client = net.client(API_TIMEOUT, API_UA)?;
+    let body: Value = net
+        .get(&client, &api_url)?
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.text())
+        .map_err(|err| format!("Failed to fetch {api_url}: {err}"))
+        .and_then(|text| {
+            serde_json::from_str(&text)
+                .map_err(|err| format!("Invalid response from {api_url}: {err}"))
+        })?;
+    let title = body["title"]
+        .as_str()
+        .ok_or_else(|| format!("No title in response from {api_url}"))?;
+    let state = match (&issue.forge, body["state"].as_str()) {
+        (Forge::GitHub, Some("closed")) if !body["pull_request"]["merged_at"].is_null() => "merged",
+        // gitlab says "opened" where github says "open"
+        (_, Some("opened")) => "open",
+        (_, Some(state)) => state,
+        (_, None) => "unknown",
+    };
+    let sigil = match (&issue.forge, issue.is_pull) {
+        (Forge::GitLab(_), true) => '!',
+        _ => '#',
+    };
+    Ok(IssueInfo {
+        name: format!("{}{sigil}{}: {title}", issue.repo, issue.number),
+        tags: vec![issue.repo.clone(), state.to_string()],
+    })
+}
@@ -0,0 +1,344 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::config::Entry;
+use crate::errors::RevertError;
+
+pub const JOURNAL_FILE: &str = "journal.jsonl";
+// changes about to be saved, left behind if flist stops before it is done saving them
+pub const UNSAVED_FILE: &str = "unsaved.jsonl";
+
+/// the saved list an entry is in
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum List {
+    Entries,
+    Archive,
+    Snoozed,
+}
+
+impl List {
+    /// the file of the project the list is saved to
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::Entries => "entries.json",
+            Self::Archive => "archive.json",
+            Self::Snoozed => "snoozed.json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Snapshot {
+    pub list: List,
+    pub entry: Entry,
+}
+
+/// a change to a single entry, as recorded in the project's journal
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JournalEvent {
+    /// increases with every event of the project
+    pub id: u64,
+    pub time: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    pub entry_id: u64,
+    /// the entry before the change, None if it was added
+    pub before: Option<Snapshot>,
+    /// the entry after the change, None if it was deleted
+    pub after: Option<Snapshot>,
+}
+
+impl JournalEvent {
+    pub fn action(&self) -> &'static str {
+        match (&self.before, &self.after) {
+            (None, _) => "added",
+            (Some(_), None) => "deleted",
+            (Some(before), Some(after)) if before.list != after.list => match after.list {
+                List::Archive => "archived",
+                List::Snoozed => "snoozed",
+                List::Entries => "restored",
+            },
+            (Some(_), Some(_)) => "changed",
+        }
+    }
+
+    /// the name of the entry, as it was after the change if it still existed
+    pub fn entry_name(&self) -> &str {
+        self.after
+            .as_ref()
+            .or(self.before.as_ref())
+            .map_or("", |snapshot| snapshot.entry.name.as_str())
+    }
+
+    /// the names of the entry's fields that changed
+    pub fn changed_fields(&self) -> Vec<String> {
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => changed_fields(&before.entry, &after.entry),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// the names of the fields that differ between the entries, as they are saved
+pub fn changed_fields(before: &Entry, after: &Entry) -> Vec<String> {
+    let (before, after) = (fields(before), fields(after));
+    before
+        .keys()
+        .chain(after.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        // every change raises the revision, it isn't a change of its own
+        .filter(|key| *key != "revision" && before.get(*key) != after.get(*key))
+        .cloned()
+        .collect()
+}
+
+// the entry's fields as they are saved
+fn fields(entry: &Entry) -> Map<String, Value> {
+    match serde_json::to_value(entry) {
+        Ok(Value::Object(fields)) => fields,
+        _ => unreachable!("entries serialize to objects"),
+    }
+}
+
+/// The state of an entry with this event undone, from its `current` state. Only the fields the event changed are
+/// reverted, so later changes to other fields are kept.
+pub fn reverted(
+    event: &JournalEvent,
+    current: Option<&Snapshot>,
+) -> Result<Option<Snapshot>, RevertError> {
+    match (&event.before, &event.after, current) {
+        (None, _, None) => Err(RevertError::Gone(event.entry_id)),
+        (None, _, Some(_)) => Ok(None),
+        (Some(_), None, Some(_)) => Err(RevertError::Exists(event.entry_id)),
+        (Some(before), None, None) => Ok(Some(before.clone())),
+        (Some(_), Some(_), None) => Err(RevertError::Gone(event.entry_id)),
+        (Some(before), Some(after), Some(current)) => {
+            let list = if before.list != after.list && current.list == after.list {
+                before.list
+            } else {
+                current.list
+            };
+            let (old, mut entry) = (fields(&before.entry), fields(&current.entry));
+            for field in event.changed_fields() {
+                match old.get(&field) {
+                    Some(value) => entry.insert(field, value.clone()),
+                    None => entry.remove(&field),
+                };
+            }
+            let entry = serde_json::from_value(Value::Object(entry))
+                .expect("Failed to deserialize reverted entry");
+            Ok(Some(Snapshot { list, entry }))
+        }
+    }
+}
+
+/// The entries of the saved lists by id, to compare against after changes. Temporary entries are never saved, so they
+/// are left out.
+pub fn snapshots<'a>(
+    lists: impl IntoIterator<Item = (List, &'a [Entry])>,
+) -> HashMap<u64, Snapshot> {
+    lists
+        .into_iter()
+        .flat_map(|(list, entries)| {
+            entries
+                .iter()
+                .filter(|entry| !entry.ephemeral)
+                .map(move |entry| {
+                    (
+                        entry.id,
+                        Snapshot {
+                            list,
+                            entry: entry.clone(),
+                        },
+                    )
+                })
+        })
+        .collect()
+}
+
+/// The changes from `before` to `after`, as events numbered from `first_id`.
+pub fn diff(
+    before: &HashMap<u64, Snapshot>,
+    after: &HashMap<u64, Snapshot>,
+    first_id: u64,
+    user: Option<&str>,
+    time: DateTime<Utc>,
+) -> Vec<JournalEvent> {
+    let same = |a: &Snapshot, b: &Snapshot| {
+        a.list == b.list
+            && serde_json::to_value(&a.entry).ok() == serde_json::to_value(&b.entry).ok()
+    };
+    before
+        .keys()
+        .chain(after.keys())
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|id| match (before.get(id), after.get(id)) {
+            (Some(a), Some(b)) => !same(a, b),
+            _ => true,
+        })
+        .zip(first_id..)
+        .map(|(entry_id, id)| JournalEvent {
+            id,
+            time,
+            user: user.map(str::to_string),
+            entry_id,
+            before: before.get(&entry_id).cloned(),
+            after: after.get(&entry_id).cloned(),
+        })
+        .collect()
+}
+
+fn to_lines(events: &[JournalEvent]) -> io::Result<String> {
+    let mut lines = String::new();
+    for event in events {
+        lines.push_str(&serde_json::to_string(event)?);
+        lines.push('\n');
+    }
+    Ok(lines)
+}
+
+// lines that can't be read, e.g. from an interrupted write, are skipped
+fn from_lines(path: &Path) -> Vec<JournalEvent> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+pub fn append(root: &Path, events: &[JournalEvent]) -> io::Result<()> {
+    if events.is_empty() {
+        return Ok(());
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(root.join(JOURNAL_FILE))?;
+    file.write_all(to_lines(events)?.as_bytes())
+}
+
+/// the events of the journal, oldest first
+pub fn read(root: &Path) -> Vec<JournalEvent> {
+    from_lines(&root.join(JOURNAL_FILE))
+}
+
+/// Records changes before they are saved, so they can be replayed if flist stops before it is done.
+pub fn write_unsaved(root: &Path, events: &[JournalEvent]) -> io::Result<()> {
+    fs::write(root.join(UNSAVED_FILE), to_lines(events)?)
+}
+
+/// the changes a previous run of flist did not get to save, empty if it was closed cleanly
+pub fn read_unsaved(root: &Path) -> Vec<JournalEvent> {
+    from_lines(&root.join(UNSAVED_FILE))
+}
+
+pub fn clear_unsaved(root: &Path) -> io::Result<()> {
+    match fs::remove_file(root.join(UNSAVED_FILE)) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Drops all but the latest `keep` events of the journal, returns how many were dropped.
+pub fn compact(root: &Path, keep: usize) -> io::Result<usize> {
+    let events = read(root);
+    let dropped = events.len().saturating_sub(keep);
+    if dropped > 0 {
+        // written aside first, so that an interrupted compaction leaves the journal whole
+        let path = root.join(JOURNAL_FILE);
+        let compacted = path.with_extension("jsonl.tmp");
+        fs::write(&compacted, to_lines(&events[dropped..])?)?;
+        fs::rename(compacted, path)?;
+    }
+    Ok(dropped)
+}
+
+/// the id the next event of the journal should get
+pub fn next_id(root: &Path) -> u64 {
+    read(root).last().map_or(1, |event| event.id + 1)
+}
+
+/// A line per event, in the given order.
+pub fn render<'a>(events: impl IntoIterator<Item = &'a JournalEvent>) -> String {
+    let mut ret = String::new();
+    for event in events {
+        write!(
+            ret,
+            "#{} {} ",
+            event.id,
+            event.time.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        )
+        .unwrap();
+        if let Some(user) = &event.user {
+            write!(ret, "{user} ").unwrap();
+        }
+        write!(
+            ret,
+            "{} {} (id {})",
+            event.action(),
+            event.entry_name(),
+            event.entry_id
+        )
+        .unwrap();
+        if let Some(after) = event
+            .after
+            .as_ref()
+            .filter(|after| after.entry.revision > 0)
+        {
+            write!(ret, " at revision {}", after.entry.revision).unwrap();
+        }
+        let fields = event.changed_fields();
+        if !fields.is_empty() {
+            write!(ret, ": {}", fields.join(", ")).unwrap();
+        }
+        ret.push('\n');
+    }
+    ret
+}
+
+/// Like `render`, with what the events remove and add under each: the changed fields of entries that stay in their
+/// file, or the whole entry for each file it leaves or enters.
+pub fn render_diff(events: &[JournalEvent]) -> String {
+    let mut ret = String::new();
+    for event in events {
+        ret.push_str(&render([event]));
+        match (&event.before, &event.after) {
+            (Some(before), Some(after)) if before.list == after.list => {
+                let (before, after) = (fields(&before.entry), fields(&after.entry));
+                for field in event.changed_fields() {
+                    if let Some(value) = before.get(&field) {
+                        writeln!(ret, "  - {field}: {value}").unwrap();
+                    }
+                    if let Some(value) = after.get(&field) {
+                        writeln!(ret, "  + {field}: {value}").unwrap();
+                    }
+                }
+            }
+            (before, after) => {
+                for (sign, snapshot) in [("-", before), ("+", after)] {
+                    if let Some(snapshot) = snapshot {
+                        writeln!(
+                            ret,
+                            "  {sign} {}: {}",
+                            snapshot.list.file_name(),
+                            Value::Object(fields(&snapshot.entry))
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+        }
+    }
+    ret
+}
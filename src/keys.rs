@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+/// The actions whose keys can be changed in `[keys]`, named after what their key does in the main list, and that key.
+/// The key acts the same in the other views.
+const ACTIONS: &[(&str, &str)] = &[
+    ("open", "Enter"),
+    ("alternate-open", "o"),
+    ("up", "Up"),
+    ("down", "Down"),
+    ("archive", "Delete"),
+    ("drag", "d"),
+    ("sort", "s"),
+    ("paste", "Ctrl+v"),
+    ("save", "Ctrl+s"),
+    ("quit", "q"),
+    ("edit", "E"),
+    ("notes", "n"),
+    ("snooze", "z"),
+    ("due", "D"),
+    ("field", "F"),
+    ("star", "*"),
+    ("pin", "p"),
+    ("link", "l"),
+    ("follow-link", "g"),
+    ("move-section", "m"),
+    ("share", "e"),
+    ("search", "/"),
+    ("palette", ":"),
+    ("type-ahead", "'"),
+    ("tags", "t"),
+    ("go-to-archive", "a"),
+    ("peek-archive", "P"),
+    ("go-to-snoozed", "Z"),
+    ("starred", "f"),
+    ("history", "h"),
+    ("inbox", "i"),
+    ("triage", "T"),
+    ("board", "b"),
+    ("calendar", "c"),
+    ("agenda", "A"),
+    ("journal", "H"),
+    ("jump-to-remote", "J"),
+    ("send-to-project", "V"),
+    ("refresh-titles", "N"),
+];
+
+type Key = (KeyCode, KeyModifiers);
+
+/// The keys moved in `[keys]`. A moved key acts as the key it replaces, which does nothing unless another action was
+/// moved onto it.
+#[derive(Debug, Default)]
+pub struct KeyMap {
+    moved: HashMap<Key, Key>,
+    freed: HashSet<Key>,
+    // the label of each replaced key in the key options, and the label of its replacement
+    labels: HashMap<String, String>,
+}
+
+impl KeyMap {
+    /// the keys of `[keys]`, which maps action names to key names like `x`, `Ctrl+x`, `Delete`, or `F2`
+    pub fn new(keys: &BTreeMap<String, String>) -> Result<Self, String> {
+        let mut ret = Self::default();
+        for (action, name) in keys {
+            let Some((_, default)) = ACTIONS.iter().find(|(known, _)| *known == action.as_str())
+            else {
+                return Err(format!(
+                    "Unknown action {action} in [keys], the actions are {}",
+                    ACTIONS
+                        .iter()
+                        .map(|(action, _)| *action)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            };
+            let key =
+                parse(name).ok_or_else(|| format!("Unknown key {name} for {action} in [keys]"))?;
+            ret.moved.insert(key, parse(default).unwrap());
+            ret.freed.insert(parse(default).unwrap());
+            ret.labels.insert(label(default), label(name));
+        }
+        for key in ret.moved.keys() {
+            ret.freed.remove(key);
+        }
+        Ok(ret)
+    }
+
+    /// the event as the keys it replaces see it, None if its key was moved away
+    pub fn translate(&self, ev: Event) -> Option<Event> {
+        let Event::Key(key) = ev else {
+            return Some(ev);
+        };
+        let pressed = normalize(key.code, key.modifiers);
+        if let Some((code, modifiers)) = self.moved.get(&pressed) {
+            return Some(Event::Key(KeyEvent {
+                code: *code,
+                modifiers: *modifiers,
+                ..key
+            }));
+        }
+        (!self.freed.contains(&pressed)).then_some(ev)
+    }
+
+    /// the label of the key that replaced the key labelled `default` in the key options, if it was moved
+    pub fn label(&self, default: &str) -> Option<&str> {
+        self.labels.get(default).map(String::as_str)
+    }
+}
+
+// letters are told apart by their case, whether the terminal also reports shift varies
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> Key {
+    match code {
+        KeyCode::Char(_) => (code, modifiers.difference(KeyModifiers::SHIFT)),
+        _ => (code, modifiers),
+    }
+}
+
+fn parse(name: &str) -> Option<Key> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = name.trim();
+    while let Some((modifier, key)) = rest.split_once('+').filter(|(_, key)| !key.is_empty()) {
+        modifiers |= match modifier.trim().to_lowercase().as_str() {
+            "ctrl" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+        rest = key;
+    }
+    let code = match rest.to_lowercase().as_str() {
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "space" => KeyCode::Char(' '),
+        lower if lower.len() > 1 && lower.starts_with('f') => KeyCode::F(lower[1..].parse().ok()?),
+        _ => {
+            let mut chars = rest.chars();
+            let c = chars.next()?;
+            if chars.next().is_some() {
+                return None;
+            }
+            KeyCode::Char(c)
+        }
+    };
+    Some(normalize(code, modifiers))
+}
+
+// as keys are shown in the key options, a character as it is and other keys in angle brackets
+fn label(name: &str) -> String {
+    let name = name.trim();
+    match name.rsplit_once('+') {
+        _ if name.chars().count() == 1 => name.to_string(),
+        Some((modifiers, key)) if key.chars().count() == 1 => {
+            format!("<{modifiers}+{}>", key.to_uppercase())
+        }
+        _ => format!("<{name}>"),
+    }
+}
@@ -0,0 +1,35 @@
+use std::fs;
+
+use crate::journal;
+use crate::project::Project;
+
+/// What makes the project large enough to slow flist down, with what to do about it.
+pub fn warnings(project: &Project) -> Vec<String> {
+    let limits = &project.config.limits;
+    let mut ret = Vec::new();
+    let count = project.entries.len() + project.snoozed.len();
+    if count > limits.entries {
+        ret.push(format!(
+            "{count} entries, over limits.entries of {}, archive finished entries",
+            limits.entries
+        ));
+    }
+    for (file_name, advice) in [
+        ("entries.json", "archive finished entries"),
+        ("snoozed.json", "archive finished entries"),
+        ("archive.json", "lower max_archive and run `flist compact`"),
+        (
+            journal::JOURNAL_FILE,
+            "run `flist compact` to drop old changes",
+        ),
+    ] {
+        let Ok(metadata) = fs::metadata(project.root.join(file_name)) else {
+            continue;
+        };
+        let kb = metadata.len() / 1024;
+        if kb > limits.file_kb {
+            ret.push(format!("{file_name} is {kb} KB, {advice}"));
+        }
+    }
+    ret
+}
@@ -1,21 +1,28 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io;
-use std::{path::Path, time::Duration};
+use std::path::{Path, PathBuf};
 
 use itertools::Itertools;
-use std::process::Command;
+
+use crate::feed::FEED_PREFIX;
+use crate::opener;
 
 #[derive(Debug, Clone)]
 pub enum Link {
     File(String),
     Directory(String),
     Url(String),
+    /// an rss or atom feed, saved as its url after the `feed:` prefix
+    Feed(String),
 }
 
 impl From<&str> for Link {
     fn from(s: &str) -> Self {
         let pth = Path::new(s);
-        if pth.is_absolute() {
+        if s.starts_with(FEED_PREFIX) {
+            Self::Feed(s.to_string())
+        } else if pth.is_absolute() {
             if pth.is_dir() {
                 Self::Directory(s.to_string())
             } else {
@@ -28,6 +35,7 @@ impl From<&str> for Link {
 }
 
 impl Link {
+    /// the name of the target, urls and feeds are named after themselves as they are named in the background
     pub fn infer_name(&self) -> String {
         match self {
             Self::File(s) => Path::new(s)
@@ -40,18 +48,18 @@ impl Link {
                 .unwrap()
                 .to_string_lossy()
                 .to_string(),
-            Self::Url(s) => {
-                let Ok(Some(title)) = get_url_title(s) else { return s.to_string() };
-                title
-            }
+            Self::Url(s) => s.to_string(),
+            Self::Feed(s) => s.to_string(),
         }
     }
 
-    pub fn explore(&self) {
+    /// opens the target with the program of the system, which fails if the system has none
+    pub fn explore(&self) -> Result<(), String> {
         match self {
-            Self::File(s) => Provider::new().explore_at_file(s),
-            Self::Directory(s) => Provider::new().open_dir(s),
-            Self::Url(s) => Provider::new().open_url(s),
+            Self::File(s) => opener::provider().explore_at_file(s),
+            Self::Directory(s) => opener::provider().open_dir(s),
+            Self::Url(s) => opener::provider().open_url(s),
+            Self::Feed(_) => opener::provider().open_url(self.feed_url().unwrap()),
         }
     }
 
@@ -60,26 +68,40 @@ impl Link {
             Self::File(s) => s.as_str(),
             Self::Directory(s) => s.as_str(),
             Self::Url(s) => s.as_str(),
+            Self::Feed(s) => s.as_str(),
+        }
+    }
+
+    /// the name of the target's parent directory, or the host of a url, to tell apart targets with the same name
+    pub fn context(&self) -> Option<String> {
+        match self {
+            Self::File(s) | Self::Directory(s) => Path::new(s)
+                .parent()?
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string()),
+            Self::Url(_) | Self::Feed(_) => {
+                let url = reqwest::Url::parse(self.feed_url().unwrap_or(self.as_str())).ok()?;
+                let host = url.host_str()?;
+                Some(host.strip_prefix("www.").unwrap_or(host).to_string())
+            }
         }
     }
 
+    /// the url of the feed, for feed links
+    pub fn feed_url(&self) -> Option<&str> {
+        let Self::Feed(s) = self else {
+            return None;
+        };
+        s.strip_prefix(FEED_PREFIX)
+    }
+
     pub fn preferred_file<'a>(
         &self,
         preffered_suffixes: impl IntoIterator<Item = &'a Vec<String>>,
     ) -> io::Result<Option<PreferredFile>> {
         match self {
             Self::Directory(dir) => {
-                let suffixes = Path::new(dir)
-                    .read_dir()?
-                    .map(|f| f.map(|f| f.path()))
-                    .collect::<io::Result<Vec<_>>>()?
-                    .into_iter()
-                    .filter_map(|pth| {
-                        pth.extension()
-                            .and_then(|e| e.to_owned().into_string().ok())
-                            .map(|e| (e, pth))
-                    })
-                    .into_group_map();
+                let suffixes = files_by_suffix(dir)?;
                 for layer in preffered_suffixes {
                     match layer
                         .iter()
@@ -117,6 +139,41 @@ impl Link {
             _ => Ok(None),
         }
     }
+
+    /// how many files in the directory match each layer of the preferred suffixes, empty for other links
+    pub fn suffix_matches<'a>(
+        &self,
+        preffered_suffixes: impl IntoIterator<Item = &'a Vec<String>>,
+    ) -> io::Result<Vec<usize>> {
+        let Self::Directory(dir) = self else {
+            return Ok(Vec::new());
+        };
+        let suffixes = files_by_suffix(dir)?;
+        Ok(preffered_suffixes
+            .into_iter()
+            .map(|layer| {
+                layer
+                    .iter()
+                    .filter_map(|suffix| suffixes.get(suffix))
+                    .map(Vec::len)
+                    .sum()
+            })
+            .collect())
+    }
+}
+
+fn files_by_suffix(dir: &str) -> io::Result<HashMap<String, Vec<PathBuf>>> {
+    Ok(Path::new(dir)
+        .read_dir()?
+        .map(|f| f.map(|f| f.path()))
+        .collect::<io::Result<Vec<_>>>()?
+        .into_iter()
+        .filter_map(|pth| {
+            pth.extension()
+                .and_then(|e| e.to_owned().into_string().ok())
+                .map(|e| (e, pth))
+        })
+        .into_group_map())
 }
 
 impl<'de> Deserialize<'de> for Link {
@@ -138,22 +195,11 @@ impl Serialize for Link {
             Self::File(s) => s.serialize(serializer),
             Self::Directory(s) => s.serialize(serializer),
             Self::Url(s) => s.serialize(serializer),
+            Self::Feed(s) => s.serialize(serializer),
         }
     }
 }
 
-trait OsProvider {
-    fn new() -> Self;
-    fn open_file(&self, link: &str) {
-        open::that_detached(link).expect("Failed to open file");
-    }
-    fn explore_at_file(&self, link: &str);
-    fn open_dir(&self, link: &str);
-    fn open_url(&self, link: &str) {
-        open::that_detached(link).expect("Failed to open browser");
-    }
-}
-
 #[derive(Debug)]
 pub struct PreferredFile {
     pub file: Link,
@@ -161,115 +207,11 @@ pub struct PreferredFile {
 }
 
 impl PreferredFile {
-    fn new(file: Link, extension: Option<String>) -> Self {
+    pub fn new(file: Link, extension: Option<String>) -> Self {
         Self { file, extension }
     }
 
-    pub fn open(&self) {
-        Provider::new().open_file(self.file.as_str());
-    }
-}
-
-struct WindowsProvider;
-
-impl OsProvider for WindowsProvider {
-    fn new() -> Self {
-        Self
-    }
-
-    fn explore_at_file(&self, link: &str) {
-        Command::new("explorer")
-            .arg("/select,")
-            .arg(link)
-            .spawn()
-            .expect("Failed to open explorer");
-    }
-
-    fn open_dir(&self, link: &str) {
-        Command::new("explorer")
-            .arg(link)
-            .spawn()
-            .expect("Failed to open explorer");
+    pub fn open(&self) -> Result<(), String> {
+        opener::provider().open_file(self.file.as_str())
     }
 }
-
-struct LinuxProvider;
-
-impl OsProvider for LinuxProvider {
-    fn new() -> Self {
-        Self
-    }
-
-    fn explore_at_file(&self, link: &str) {
-        Command::new("xdg-open")
-            .arg("--select")
-            .arg(link)
-            .spawn()
-            .expect("Failed to open explorer");
-    }
-
-    fn open_dir(&self, link: &str) {
-        Command::new("xdg-open")
-            .arg(link)
-            .spawn()
-            .expect("Failed to open explorer");
-    }
-}
-
-struct MacProvider;
-
-impl OsProvider for MacProvider {
-    fn new() -> Self {
-        Self
-    }
-
-    fn explore_at_file(&self, link: &str) {
-        Command::new("open")
-            .arg("-R")
-            .arg(link)
-            .spawn()
-            .expect("Failed to open explorer");
-    }
-
-    fn open_dir(&self, link: &str) {
-        Command::new("open")
-            .arg(link)
-            .spawn()
-            .expect("Failed to open explorer");
-    }
-}
-
-#[cfg(target_os = "windows")]
-type Provider = WindowsProvider;
-
-#[cfg(target_os = "linux")]
-type Provider = LinuxProvider;
-
-#[cfg(target_os = "macos")]
-type Provider = MacProvider;
-
-use reqwest::blocking::Client;
-use scraper::{Html, Selector};
-
-const INFER_TIMEOUT: Duration = Duration::from_millis(1000);
-const INFER_UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/117.0.0.0 Safari/537.36";
-
-fn get_url_title(url: &str) -> reqwest::Result<Option<String>> {
-    let title_selectors = vec![
-        Selector::parse("title").unwrap(),
-        Selector::parse("head > title").unwrap(),
-    ];
-
-    let client = Client::builder()
-        .user_agent(INFER_UA)
-        .timeout(INFER_TIMEOUT)
-        .build()
-        .unwrap();
-
-    let resp = client.get(url).send()?;
-    let body = resp.text()?;
-
-    let fragment = Html::parse_document(&body);
-
-    Ok(title_selectors.iter().map(|s| fragment.select(s).map(|e| e.inner_html())).flatten().next())
-}
@@ -0,0 +1,73 @@
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::config::Entry;
+use crate::project::Project;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ListFormat {
+    /// the name and link of each entry
+    Plain,
+    /// an array of the entries, as they are saved
+    Json,
+    /// the id, name, link, section, tags, due date, and whether it is archived of each entry, separated by tabs
+    Tsv,
+}
+
+#[derive(Serialize)]
+struct Listed<'a> {
+    #[serde(flatten)]
+    entry: &'a Entry,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    archived: bool,
+}
+
+/// A line per main entry, or a json array of them, followed by the archived entries if `archive` is set. Temporary
+/// entries are left out.
+pub fn render(project: &Project, archive: bool, format: ListFormat) -> String {
+    let archived = if archive { &project.archive[..] } else { &[] };
+    let listed = project
+        .entries
+        .iter()
+        .map(|entry| (entry, false))
+        .chain(archived.iter().map(|entry| (entry, true)))
+        .filter(|(entry, _)| !entry.ephemeral)
+        .map(|(entry, archived)| Listed { entry, archived })
+        .collect::<Vec<_>>();
+    match format {
+        ListFormat::Json => {
+            serde_json::to_string_pretty(&listed).expect("Failed to serialize entries") + "\n"
+        }
+        ListFormat::Plain => listed
+            .iter()
+            .map(|listed| {
+                format!(
+                    "{} ({}){}\n",
+                    listed.entry.name,
+                    listed.entry.link.as_str(),
+                    if listed.archived { " [archived]" } else { "" }
+                )
+            })
+            .collect(),
+        ListFormat::Tsv => listed
+            .iter()
+            .map(|listed| {
+                let entry = listed.entry;
+                let due = entry.due.map(|due| due.to_rfc3339()).unwrap_or_default();
+                let fields = [
+                    entry.id.to_string(),
+                    entry.name.clone(),
+                    entry.link.as_str().to_string(),
+                    entry.section.clone().unwrap_or_default(),
+                    entry.tags.join(","),
+                    due,
+                    if listed.archived { "archived" } else { "" }.to_string(),
+                ];
+                fields
+                    .map(|field| field.replace(['\t', '\n'], " "))
+                    .join("\t")
+                    + "\n"
+            })
+            .collect(),
+    }
+}
@@ -1,38 +1,42 @@
 use std::fs;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 
 use crate::config::Lock;
+use crate::errors::FlistError;
 
 #[derive(Debug, Clone)]
 pub struct LockFile {
-    pub path: Option<PathBuf>,
+    pub path: PathBuf,
 }
 
 impl LockFile {
-    pub fn new(root: &Path) -> Self {
+    pub fn new(root: &Path) -> Result<Self, FlistError> {
         let path = root.join("flist.lock");
         let lock = Lock::without_listener();
-        let ret = Self { path: Some(path) };
-        ret.write(lock);
-        ret
+        // the lock is only removed on drop once it was written
+        write(&path, lock)?;
+        Ok(Self { path })
     }
 
-    pub fn set_listener(&self, hostname: String, listener_port: u16) {
-        let lock = Lock::with_listener(hostname, listener_port);
-        self.write(lock);
+    pub fn set_listener(
+        &self,
+        addresses: &[SocketAddr],
+        fingerprint: &str,
+    ) -> Result<(), FlistError> {
+        let lock = Lock::with_listener(addresses, fingerprint);
+        write(&self.path, lock)
     }
+}
 
-    fn write(&self, lock: Lock) {
-        let lock = serde_json::to_string(&lock).expect("Failed to serialize lock");
-        fs::write(self.path.as_ref().unwrap(), lock).expect("Failed to write lock file");
-    }
+fn write(path: &Path, lock: Lock) -> Result<(), FlistError> {
+    let lock = serde_json::to_string(&lock).expect("Failed to serialize lock");
+    fs::write(path, lock).map_err(FlistError::io("write", path))
 }
 
 impl Drop for LockFile {
     fn drop(&mut self) {
-        if let Some(path) = &self.path {
-            // we want to continue even if the file doesn't exist
-            let _ = fs::remove_file(path);
-        }
+        // we want to continue even if the file doesn't exist
+        let _ = fs::remove_file(&self.path);
     }
 }
@@ -0,0 +1,103 @@
+use std::fs;
+use std::path::Path;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+const MACRO_FILE: &str = "macro.json";
+
+/// a readable name for the key, like "Ctrl+v" or "Enter", None for keys that can't be recorded
+fn encode(key: &KeyEvent) -> Option<String> {
+    let code = match key.code {
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{n}"),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        KeyCode::Delete => "Delete".to_string(),
+        _ => return None,
+    };
+    let mut ret = String::new();
+    for (modifier, name) in [
+        (KeyModifiers::CONTROL, "Ctrl+"),
+        (KeyModifiers::ALT, "Alt+"),
+        (KeyModifiers::SHIFT, "Shift+"),
+    ] {
+        if key.modifiers.contains(modifier) {
+            ret.push_str(name);
+        }
+    }
+    ret.push_str(&code);
+    Some(ret)
+}
+
+fn decode(text: &str) -> Option<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = text;
+    // a lone "+" is the plus key, not a modifier separator
+    while let Some((modifier, after)) = rest.split_once('+').filter(|(_, after)| !after.is_empty())
+    {
+        modifiers |= match modifier {
+            "Ctrl" => KeyModifiers::CONTROL,
+            "Alt" => KeyModifiers::ALT,
+            "Shift" => KeyModifiers::SHIFT,
+            _ => break,
+        };
+        rest = after;
+    }
+    let code = match rest {
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Backspace" => KeyCode::Backspace,
+        "Tab" => KeyCode::Tab,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Delete" => KeyCode::Delete,
+        _ => {
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                (Some('F'), Some(_)) => KeyCode::F(rest[1..].parse().ok()?),
+                _ => return None,
+            }
+        }
+    };
+    Some(KeyEvent::new_with_kind(
+        code,
+        modifiers,
+        KeyEventKind::Press,
+    ))
+}
+
+/// The project's recorded macro, empty if none was recorded.
+pub fn load(project_root: &Path) -> Result<Vec<KeyEvent>, String> {
+    let Ok(contents) = fs::read_to_string(project_root.join(MACRO_FILE)) else {
+        return Ok(Vec::new());
+    };
+    let keys = serde_json::from_str::<Vec<String>>(&contents)
+        .map_err(|err| format!("Failed to parse {MACRO_FILE}: {err}"))?;
+    Ok(keys.iter().filter_map(|key| decode(key)).collect())
+}
+
+pub fn save(project_root: &Path, keys: &[KeyEvent]) -> Result<(), String> {
+    let keys = keys.iter().filter_map(encode).collect::<Vec<_>>();
+    fs::write(
+        project_root.join(MACRO_FILE),
+        serde_json::to_string_pretty(&keys).expect("Failed to serialize macro"),
+    )
+    .map_err(|err| format!("Failed to write {MACRO_FILE}: {err}"))
+}
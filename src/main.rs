@@ -1,45 +1,215 @@
+mod agenda;
 mod args;
+mod bookmarks;
+mod bundle;
+mod cd;
 mod config;
+mod diff;
+mod digest;
+mod download;
 mod errors;
+mod events;
+mod export;
+mod feed;
+mod fields;
+mod fsck;
+mod git;
 mod gui;
+mod hooks;
+mod issues;
+mod journal;
+mod keys;
+mod limits;
 mod link;
+mod list;
 mod lock;
+mod macros;
+mod media;
+mod naming;
+mod net;
+mod opener;
 mod project;
+mod providers;
+mod query;
+mod reading;
+mod refresh;
+mod registry;
+mod relink;
+mod reminders;
+mod report;
 mod requests;
+mod secrets;
+mod share;
+mod shell;
+mod snapshot;
+mod target;
+mod tls;
+mod trace;
+mod tree;
+mod watch;
+mod webhooks;
+mod when;
 
-use std::net::TcpListener;
+use std::io::{self, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, TcpListener};
+use std::path::Path;
+use std::process;
+use std::sync::Arc;
 
 use args::MainArgs;
-use chrono::{DateTime, Local};
 use clap::Parser;
-use errors::LockedProject;
+use config::ListenerPort;
+use errors::{FlistError, LockedProject};
 use lock::LockFile;
 use project::Project;
+use rustls::ServerConfig;
+use tls::Identity;
 
 fn main() {
+    if let Err(err) = run() {
+        eprintln!("{err}");
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), FlistError> {
     let args = MainArgs::parse();
     let config = args.get_config();
 
     match config {
+        // checked before loading the project, as loading fails on some of the problems it finds
+        Ok(config) if args.is_fsck() => {
+            let _lockfile = if args.dry_run {
+                None
+            } else {
+                Some(LockFile::new(&args.project_root)?)
+            };
+            fsck::run(&args.project_root, &config, args.dry_run)
+        }
+        Ok(config) if args.is_read_only() => {
+            let mut project = Project::from_dir(&args.project_root, config)?;
+            args.apply(&mut project)?;
+            Ok(())
+        }
         Ok(config) => {
-            let lockfile = LockFile::new(&args.project_root);
-            let mut project = Project::from_dir(&args.project_root, config);
-            let apply_results = args.apply(&mut project);
+            let lockfile = LockFile::new(&args.project_root)?;
+            let mut project = Project::from_dir(&args.project_root, config)?;
+            recover(&mut project)?;
+            for warning in limits::warnings(&project) {
+                eprintln!("Warning: {warning}");
+            }
+            let accessible = args.accessible;
+            let trace_protocol = args.trace_protocol;
+            let also = args.also.clone();
+            let apply_results = args.apply(&mut project)?;
             if apply_results.should_exit {
-                return;
+                return Ok(());
+            }
+            let (listeners, tls) = listen(&lockfile, project.config.listener_port.as_ref())?;
+            register(&project.root);
+            let mut projects = vec![(project, listeners, tls, lockfile)];
+            for root in also {
+                // the errors about the lock say "project", not which of the projects it is
+                let config = args::read_config(&root, false).map_err(|err| match err {
+                    FlistError::Locked(LockedProject::WithListener(_)) => {
+                        FlistError::Command(format!("Project {} is already open", root.display()))
+                    }
+                    err @ FlistError::Locked(_) => {
+                        FlistError::Command(format!("{}: {err}", root.display()))
+                    }
+                    err => err,
+                })?;
+                let lockfile = LockFile::new(&root)?;
+                let mut project = Project::from_dir(&root, config)?;
+                recover(&mut project)?;
+                let (listeners, tls) = listen(&lockfile, project.config.listener_port.as_ref())?;
+                register(&project.root);
+                projects.push((project, listeners, tls, lockfile));
             }
-            let listener = TcpListener::bind(("127.0.0.1", 0)).expect("Failed to bind to port");
-            let addr = listener.local_addr().expect("Failed to get local addr");
-            lockfile.set_listener(addr.ip().to_string(), addr.port());
-            gui::main(project, listener, lockfile)
+            gui::main(projects, accessible, trace_protocol)
+        }
+        Err(FlistError::Locked(LockedProject::WithListener(peer))) => args.on_locked(peer),
+        Err(err) => Err(err),
+    }
+}
+
+/// offers to replay the changes that the last run did not get to save before it stopped
+fn recover(project: &mut Project) -> Result<(), FlistError> {
+    let unsaved = journal::read_unsaved(&project.root);
+    if unsaved.is_empty() {
+        return Ok(());
+    }
+    print!(
+        "{} was not closed cleanly, these changes were not saved:\n{}Replay them? [y/N] ",
+        project.root.display(),
+        journal::render(&unsaved)
+    );
+    let mut answer = String::new();
+    io::stdout()
+        .flush()
+        .and_then(|_| io::stdin().read_line(&mut answer))
+        .map_err(|err| FlistError::Command(format!("Failed to read answer: {err}")))?;
+    if answer.trim().eq_ignore_ascii_case("y") {
+        for event in &unsaved {
+            project.replay(event);
         }
-        Err(LockedProject::WithListener(stream)) => args.on_locked(stream),
-        Err(LockedProject::WithoutListener(time)) => {
-            let time: DateTime<Local> = time.into();
-            panic!(
-                "Project is locked, last lock was at {}",
-                time.format("%Y-%m-%d %H:%M:%S")
-            );
+        project.save()
+    } else {
+        let path = project.root.join(journal::UNSAVED_FILE);
+        journal::clear_unsaved(&project.root).map_err(FlistError::io("delete", &path))
+    }
+}
+
+/// lists the project among those entries can be sent to from other projects, which is not worth failing to open over
+fn register(root: &Path) {
+    if let Err(err) = registry::register(root) {
+        eprintln!("Warning: {err}");
+    }
+}
+
+/// Binds listeners for remote requests and records them in the lock, on the configured port if it is free.
+///
+/// Requests are over TLS with a certificate made for this run, the lock records its fingerprint so that clients only
+/// send to this flist.
+fn listen(
+    lockfile: &LockFile,
+    port: Option<&ListenerPort>,
+) -> Result<(Vec<TcpListener>, Arc<ServerConfig>), FlistError> {
+    let identity = Identity::generate().map_err(FlistError::Certificate)?;
+    let listeners = port
+        .into_iter()
+        .flat_map(ListenerPort::ports)
+        .find_map(bind_loopbacks)
+        .or_else(|| bind_loopbacks(0))
+        .ok_or_else(|| {
+            FlistError::Command("Failed to listen for remote requests on the loopback".to_string())
+        })?;
+    let addresses = listeners
+        .iter()
+        .map(TcpListener::local_addr)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| {
+            FlistError::Remote(format!("Failed to listen for remote requests: {err}"))
+        })?;
+    lockfile.set_listener(&addresses, &identity.fingerprint)?;
+    Ok((listeners, identity.config))
+}
+
+/// Binds the IPv4 and IPv6 loopbacks, whichever the system has, on the same port. None if neither is free.
+fn bind_loopbacks(port: u16) -> Option<Vec<TcpListener>> {
+    let mut listeners: Vec<TcpListener> = Vec::new();
+    for host in [
+        IpAddr::from(Ipv4Addr::LOCALHOST),
+        IpAddr::from(Ipv6Addr::LOCALHOST),
+    ] {
+        // once a port is picked, the other loopback takes the same one
+        let port = listeners
+            .first()
+            .and_then(|listener| listener.local_addr().ok())
+            .map_or(port, |addr| addr.port());
+        if let Ok(listener) = TcpListener::bind((host, port)) {
+            listeners.push(listener);
         }
     }
+    (!listeners.is_empty()).then_some(listeners)
 }
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+use crate::config::Entry;
+use crate::net::Net;
+
+const MEDIA_TIMEOUT: Duration = Duration::from_millis(3000);
+const MEDIA_UA: &str = concat!("flist/", env!("CARGO_PKG_VERSION"));
+const DURATION_PREFIX: &str = "duration: ";
+const CHANNEL_PREFIX: &str = "channel: ";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MediaSite {
+    YouTube,
+    Vimeo,
+}
+
+#[derive(Debug)]
+pub struct MediaInfo {
+    pub title: String,
+    /// metadata lines for the channel and duration, when known
+    pub metadata: Vec<String>,
+}
+
+pub fn site(url: &str) -> Option<MediaSite> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let host = rest.split(['/', '?', '#']).next()?;
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    match host {
+        "youtube.com" | "m.youtube.com" | "music.youtube.com" | "youtu.be" => {
+            Some(MediaSite::YouTube)
+        }
+        "vimeo.com" | "player.vimeo.com" => Some(MediaSite::Vimeo),
+        _ => None,
+    }
+}
+
+/// Fetches the title, channel, and duration of a video through the site's oembed endpoint, no api key needed.
+pub fn fetch(site: MediaSite, url: &str, net: &Net) -> Result<MediaInfo, String> {
+    let client = net.client(MEDIA_TIMEOUT, MEDIA_UA)?;
+    let endpoint = match site {
+        MediaSite::YouTube => "https://www.youtube.com/oembed",
+        MediaSite::Vimeo => "https://vimeo.com/api/oembed.json",
+    };
+    let body: Value = net
+        .get(&client, endpoint)?
+        .query(&[("url", url), ("format", "json")])
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.text())
+        .map_err(|err| format!("Failed to fetch {endpoint}: {err}"))
+        .and_then(|text| {
+            serde_json::from_str(&text)
+                .map_err(|err| format!("Invalid response from {endpoint}: {err}"))
+        })?;
+    let title = body["title"]
+        .as_str()
+        .ok_or_else(|| format!("No title in response from {endpoint}"))?
+        .to_string();
+    let mut metadata = Vec::new();
+    if let Some(channel) = body["author_name"].as_str() {
+        metadata.push(format!("{CHANNEL_PREFIX}{channel}"));
+    }
+    let seconds = match site {
+        MediaSite::Vimeo => body["duration"].as_u64(),
+        // youtube's oembed has no duration, but the watch page declares it
+        MediaSite::YouTube => youtube_duration(&client, url),
+    };
+    if let Some(seconds) = seconds {
+        metadata.push(format!("{DURATION_PREFIX}{}", format_duration(seconds)));
+    }
+    Ok(MediaInfo { title, metadata })
+}
+
+fn youtube_duration(client: &Client, url: &str) -> Option<u64> {
+    let body = client.get(url).send().ok()?.text().ok()?;
+    let selector = Selector::parse(r#"meta[itemprop="duration"]"#).unwrap();
+    let fragment = Html::parse_document(&body);
+    let content = fragment.select(&selector).next()?.value().attr("content")?;
+    parse_iso_duration(content)
+}
+
+/// parses durations like "PT1H2M3S"
+fn parse_iso_duration(text: &str) -> Option<u64> {
+    let mut rest = text.strip_prefix("PT")?;
+    let mut ret = 0;
+    while !rest.is_empty() {
+        let unit_idx = rest.find(|c: char| !c.is_ascii_digit())?;
+        let amount = rest[..unit_idx].parse::<u64>().ok()?;
+        let multiplier = match rest[unit_idx..].chars().next()? {
+            'H' => 3600,
+            'M' => 60,
+            'S' => 1,
+            _ => return None,
+        };
+        ret += amount * multiplier;
+        rest = &rest[unit_idx + 1..];
+    }
+    Some(ret)
+}
+
+fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// the video duration stored in the entry's metadata, if any
+pub fn duration(entry: &Entry) -> Option<&str> {
+    entry
+        .metadata
+        .iter()
+        .find_map(|line| line.strip_prefix(DURATION_PREFIX))
+}
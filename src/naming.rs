@@ -0,0 +1,252 @@
+use std::io::Read;
+use std::thread;
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+use reqwest::{StatusCode, Url};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+
+use crate::net::{Net, BROWSER_UA};
+
+pub const DEFAULT_STEP_TIMEOUT_MS: u64 = 1000;
+// titles are in the head of the page, the rest of a large page isn't needed
+const MAX_PAGE_BYTES: u64 = 2 * 1024 * 1024;
+// transient failures are retried after this, doubled on every retry
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_RETRIES: u32 = 2;
+
+/// where a step of name inference takes the name of a url from
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NameSource {
+    /// the title of the page
+    Title,
+    /// the page's open graph title, which usually leaves out the site's name
+    OgTitle,
+    /// the last segment of the url's path
+    PathSegment,
+    /// the host of the url
+    Host,
+}
+
+impl NameSource {
+    fn fetches(&self) -> bool {
+        matches!(self, Self::Title | Self::OgTitle)
+    }
+}
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_STEP_TIMEOUT_MS
+}
+
+fn is_default_timeout_ms(timeout_ms: &u64) -> bool {
+    *timeout_ms == DEFAULT_STEP_TIMEOUT_MS
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct NameStep {
+    pub source: NameSource,
+    /// how long to wait for the page, for steps that read it
+    #[serde(
+        default = "default_timeout_ms",
+        skip_serializing_if = "is_default_timeout_ms"
+    )]
+    pub timeout_ms: u64,
+}
+
+/// the title of the page, then its open graph title, then the url's last path segment, then its host
+pub fn default_steps() -> Vec<NameStep> {
+    [
+        NameSource::Title,
+        NameSource::OgTitle,
+        NameSource::PathSegment,
+        NameSource::Host,
+    ]
+    .into_iter()
+    .map(|source| NameStep {
+        source,
+        timeout_ms: DEFAULT_STEP_TIMEOUT_MS,
+    })
+    .collect()
+}
+
+// a fetched page, after redirects
+struct Page {
+    url: Url,
+    // None if the response isn't html, e.g. a pdf, which isn't read
+    html: Option<String>,
+}
+
+/// The outcome of name inference, a name might still be found by a later step if the page couldn't be read.
+pub struct Inferred {
+    pub name: Option<String>,
+    /// why the page couldn't be read, if a step needed it
+    pub error: Option<String>,
+}
+
+/// The name of the url from the first step that gives one. The page is fetched once, by the first step that reads it
+/// successfully, and the url's steps use the url it redirected to. A later step only fetches the page again if an
+/// earlier one timed out, as it might wait longer.
+pub fn infer(url: &str, steps: &[NameStep], net: &Net) -> Inferred {
+    let client = net.client(Duration::from_millis(DEFAULT_STEP_TIMEOUT_MS), BROWSER_UA);
+    let mut page: Option<Page> = None;
+    let mut failure: Option<Failure> = None;
+    for step in steps {
+        let retry = failure.as_ref().is_none_or(|failure| failure.timed_out);
+        if step.source.fetches() && page.is_none() && retry {
+            let timeout = net.timeout(Duration::from_millis(step.timeout_ms));
+            let fetched = match &client {
+                Ok(client) => fetch(client, net, url, timeout),
+                Err(err) => Err(Failure::from(err.clone())),
+            };
+            match fetched {
+                Ok(fetched) => {
+                    page = Some(fetched);
+                    failure = None;
+                }
+                Err(err) => failure = Some(err),
+            }
+        }
+        let final_url = match &page {
+            Some(page) => Some(page.url.clone()),
+            None => Url::parse(url).ok(),
+        };
+        let name = match step.source {
+            NameSource::Title => page.as_ref().and_then(|page| select(page, "title", None)),
+            NameSource::OgTitle => page
+                .as_ref()
+                .and_then(|page| select(page, "meta[property=\"og:title\"]", Some("content"))),
+            NameSource::PathSegment => final_url.as_ref().and_then(path_segment),
+            NameSource::Host => final_url
+                .as_ref()
+                .and_then(|url| url.host_str())
+                .map(|host| host.strip_prefix("www.").unwrap_or(host).to_string()),
+        };
+        let name = name.map(|name| name.split_whitespace().collect::<Vec<_>>().join(" "));
+        if let Some(name) = name.filter(|name| !name.is_empty()) {
+            return Inferred {
+                name: Some(name),
+                error: failure.map(|failure| failure.message),
+            };
+        }
+    }
+    Inferred {
+        name: None,
+        error: failure.map(|failure| failure.message),
+    }
+}
+
+// why a page couldn't be fetched
+struct Failure {
+    message: String,
+    timed_out: bool,
+    // server errors, rate limits, timeouts, and dropped connections might pass
+    transient: bool,
+}
+
+impl From<reqwest::Error> for Failure {
+    fn from(err: reqwest::Error) -> Self {
+        let transient = err.is_timeout()
+            || err.is_connect()
+            || err.status().is_some_and(|status| {
+                status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+            });
+        Self {
+            message: err.to_string(),
+            timed_out: err.is_timeout(),
+            transient,
+        }
+    }
+}
+
+// problems of the config, which retrying doesn't help
+impl From<String> for Failure {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            timed_out: false,
+            transient: false,
+        }
+    }
+}
+
+// runs on the background fetcher, so waiting between retries doesn't hold up the view
+fn fetch(client: &Client, net: &Net, url: &str, timeout: Duration) -> Result<Page, Failure> {
+    let mut backoff = RETRY_BACKOFF;
+    let mut retries = 0;
+    loop {
+        match fetch_once(client, net, url, timeout) {
+            Err(failure) if retries < MAX_RETRIES && failure.transient => {
+                thread::sleep(backoff);
+                backoff *= 2;
+                retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn fetch_once(client: &Client, net: &Net, url: &str, timeout: Duration) -> Result<Page, Failure> {
+    let resp = net
+        .get(client, url)?
+        .timeout(timeout)
+        .send()?
+        .error_for_status()?;
+    let url = resp.url().clone();
+    let is_html = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        // servers that don't say are assumed to send html
+        .is_none_or(|content_type| content_type.contains("html"));
+    let html = if is_html {
+        let mut body = Vec::new();
+        // a failure partway keeps what was read, which likely has the head
+        let _ = resp.take(MAX_PAGE_BYTES).read_to_end(&mut body);
+        Some(String::from_utf8_lossy(&body).into_owned())
+    } else {
+        None
+    };
+    Ok(Page { url, html })
+}
+
+// the text of the first element that matches, or its `attr` if given
+fn select(page: &Page, selector: &str, attr: Option<&str>) -> Option<String> {
+    let document = Html::parse_document(page.html.as_ref()?);
+    let element = document
+        .select(&Selector::parse(selector).unwrap())
+        .next()?;
+    Some(match attr {
+        Some(attr) => element.value().attr(attr)?.to_string(),
+        None => element.text().collect(),
+    })
+}
+
+fn path_segment(url: &Url) -> Option<String> {
+    let segment = url.path_segments()?.rfind(|segment| !segment.is_empty())?;
+    Some(percent_decode(segment))
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut ret = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let escaped = (bytes[idx] == b'%')
+            .then(|| text.get(idx + 1..idx + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                ret.push(byte);
+                idx += 3;
+            }
+            None => {
+                ret.push(bytes[idx]);
+                idx += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&ret).into_owned()
+}
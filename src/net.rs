@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::blocking::{Client, ClientBuilder, RequestBuilder};
+use reqwest::cookie::Jar;
+use reqwest::redirect::Policy;
+use reqwest::{Proxy, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::secrets::Secret;
+
+pub const BROWSER_UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/117.0.0.0 Safari/537.36";
+const MAX_REDIRECTS: usize = 5;
+
+fn default_true() -> bool {
+    true
+}
+
+fn is_true(b: &bool) -> bool {
+    *b
+}
+
+/// What requests flist may make outside the computer, for restricted environments or to keep it offline.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct NetworkConfig {
+    /// without it, no feature makes requests, and urls are named after themselves
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub enabled: bool,
+    /// the timeout of every request, instead of the one of each feature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// the user agent of every request, instead of the one of each feature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// the only hosts requests may go to, with their subdomains, any host if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_hosts: Vec<String>,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_ms: None,
+            user_agent: None,
+            allowed_hosts: Vec::new(),
+        }
+    }
+}
+
+impl NetworkConfig {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// How pages are fetched, for links behind a proxy or a login. Without `proxy`, the `HTTP_PROXY`, `HTTPS_PROXY`, and
+/// `NO_PROXY` environment variables are honored.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct HttpConfig {
+    /// the proxy of all requests, e.g. "http://proxy.corp:3128"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// extra headers by host, sent to the host and its subdomains, e.g. an Authorization header for an intranet. A
+    /// value can be kept in the keyring as `{ secret = "<name>" }`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, BTreeMap<String, Secret>>,
+    /// a cookies.txt file, as exported from a browser, whose cookies are sent to their sites
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cookie_file: Option<String>,
+}
+
+impl HttpConfig {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+// whether `host` is one of `hosts` or their subdomains, any host is if there are none
+fn host_matches(hosts: &[String], host: &str) -> bool {
+    let host = host.to_lowercase();
+    hosts.is_empty()
+        || hosts.iter().any(|allowed| {
+            let allowed = allowed.to_lowercase();
+            host == allowed || host.ends_with(&format!(".{allowed}"))
+        })
+}
+
+/// The network settings of a project, which every feature that makes requests goes through. Cheap enough to clone
+/// into background work.
+#[derive(Debug, Clone, Default)]
+pub struct Net {
+    network: NetworkConfig,
+    http: HttpConfig,
+}
+
+impl Net {
+    pub fn new(network: &NetworkConfig, http: &HttpConfig) -> Self {
+        Self {
+            network: network.clone(),
+            http: http.clone(),
+        }
+    }
+
+    /// the configured timeout if there is one, otherwise the feature's own
+    pub fn timeout(&self, timeout: Duration) -> Duration {
+        self.network
+            .timeout_ms
+            .map_or(timeout, Duration::from_millis)
+    }
+
+    /// A client builder with the proxy, cookies, timeout, and user agent of the config, falling back on the given
+    /// ones. Redirects are only followed to allowed hosts. Fails if requests are disabled.
+    pub fn client_builder(
+        &self,
+        timeout: Duration,
+        user_agent: &str,
+    ) -> Result<ClientBuilder, String> {
+        if !self.network.enabled {
+            return Err("Network requests are disabled in flist.toml".to_string());
+        }
+        let allowed_hosts = self.network.allowed_hosts.clone();
+        let mut builder = Client::builder()
+            .user_agent(self.network.user_agent.as_deref().unwrap_or(user_agent))
+            .timeout(self.timeout(timeout))
+            .redirect(Policy::custom(move |attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS {
+                    attempt.error("too many redirects")
+                } else if !host_matches(
+                    &allowed_hosts,
+                    attempt.url().host_str().unwrap_or_default(),
+                ) {
+                    attempt.error("redirected to a host that is not in allowed_hosts")
+                } else {
+                    attempt.follow()
+                }
+            }));
+        if let Some(proxy) = &self.http.proxy {
+            let proxy = Proxy::all(proxy).map_err(|err| format!("Invalid proxy {proxy}: {err}"))?;
+            builder = builder.proxy(proxy);
+        }
+        if let Some(cookie_file) = &self.http.cookie_file {
+            builder = builder.cookie_provider(Arc::new(read_cookies(cookie_file)?));
+        }
+        Ok(builder)
+    }
+
+    /// like `client_builder`, built
+    pub fn client(&self, timeout: Duration, user_agent: &str) -> Result<Client, String> {
+        self.client_builder(timeout, user_agent)?
+            .build()
+            .map_err(|err| format!("Failed to set up requests: {err}"))
+    }
+
+    /// a GET request of `url`, with the extra headers of its host, if requests to it are allowed
+    pub fn get(&self, client: &Client, url: &str) -> Result<RequestBuilder, String> {
+        self.request(client.get(url), url)
+    }
+
+    /// a POST request to `url`, with the extra headers of its host, if requests to it are allowed
+    pub fn post(&self, client: &Client, url: &str) -> Result<RequestBuilder, String> {
+        self.request(client.post(url), url)
+    }
+
+    fn request(&self, mut request: RequestBuilder, url: &str) -> Result<RequestBuilder, String> {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+            .unwrap_or_default();
+        if !host_matches(&self.network.allowed_hosts, &host) {
+            return Err(format!("{host} is not in the allowed_hosts of flist.toml"));
+        }
+        for (site, headers) in &self.http.headers {
+            if host_matches(std::slice::from_ref(site), &host) {
+                for (name, value) in headers {
+                    request = request.header(name, value.resolve()?);
+                }
+            }
+        }
+        Ok(request)
+    }
+}
+
+// lines of the netscape format are: domain, subdomains flag, path, secure flag, expiry, name, value
+fn read_cookies(path: &str) -> Result<Jar, String> {
+    let text =
+        fs::read_to_string(path).map_err(|err| format!("Failed to read cookies {path}: {err}"))?;
+    let jar = Jar::default();
+    for line in text.lines() {
+        // curl marks http-only cookies with a prefix on what would otherwise be a comment
+        let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+        if line.starts_with('#') {
+            continue;
+        }
+        let [domain, _, path, secure, _, name, value] = line.split('\t').collect::<Vec<_>>()[..]
+        else {
+            continue;
+        };
+        let scheme = if secure.eq_ignore_ascii_case("true") {
+            "https"
+        } else {
+            "http"
+        };
+        let Ok(url) = Url::parse(&format!(
+            "{scheme}://{}{path}",
+            domain.trim_start_matches('.')
+        )) else {
+            continue;
+        };
+        jar.add_cookie_str(
+            &format!("{name}={value}; Domain={domain}; Path={path}"),
+            &url,
+        );
+    }
+    Ok(jar)
+}
@@ -0,0 +1,534 @@
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+#[cfg(target_os = "linux")]
+use serde::Serialize;
+
+/// Runs the programs that open targets. The providers only decide what to run, so that they can be given a runner
+/// that records the commands instead of running them.
+pub trait Runner {
+    /// starts the command without waiting for it
+    fn spawn(&self, command: &mut Command) -> Result<(), String>;
+    /// opens the target with its default app, whichever program of the system that takes
+    fn open_default(&self, target: &str) -> Result<(), String>;
+    /// whether `program` is in one of the directories of the path
+    fn on_path(&self, program: &str) -> bool;
+}
+
+// how long an opener is given to fail, openers still running after it are taken to have opened the target
+const OPENER_GRACE: Duration = Duration::from_millis(250);
+
+/// the runner of the actual system
+pub struct SystemRunner;
+
+impl Runner for SystemRunner {
+    fn spawn(&self, command: &mut Command) -> Result<(), String> {
+        spawn_checked(command).map_err(|err| format!("Failed to open explorer: {err}"))?
+    }
+
+    fn open_default(&self, target: &str) -> Result<(), String> {
+        // the commands are the ones `open` would try, in order, until one of them exists
+        for mut command in open::commands(target) {
+            match spawn_checked(&mut command) {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(format!("Failed to open {target}: {err}")),
+                Ok(result) => return result,
+            }
+        }
+        Err(format!(
+            "Failed to open {target}: no program to open it with"
+        ))
+    }
+
+    fn on_path(&self, program: &str) -> bool {
+        on_path(program)
+    }
+}
+
+/// How the system opens targets, chosen when opening as the same binary may run on different systems.
+pub trait OsProvider {
+    fn runner(&self) -> &dyn Runner;
+    /// the program that opens directories and shows files, which minimal systems might lack
+    fn opener(&self) -> &'static str;
+    fn has_opener(&self) -> bool {
+        self.runner().on_path(self.opener())
+    }
+    fn open_file(&self, link: &str) -> Result<(), String> {
+        self.runner().open_default(link)
+    }
+    fn explore_at_file(&self, link: &str) -> Result<(), String>;
+    fn open_dir(&self, link: &str) -> Result<(), String> {
+        self.runner().spawn(Command::new(self.opener()).arg(link))
+    }
+    fn open_url(&self, link: &str) -> Result<(), String> {
+        self.runner().open_default(link)
+    }
+}
+
+/// Starts the command, and waits a moment for it to fail, as openers that have no app for the target exit right away.
+/// Fails with the last line the opener wrote to stderr, or its exit status if it wrote none.
+fn spawn_checked(command: &mut Command) -> io::Result<Result<(), String>> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // explorer exits with 1 even when it opened the target
+    if matches!(program.as_str(), "explorer" | "explorer.exe") {
+        return Ok(Ok(()));
+    }
+    let (sender, receiver) = mpsc::channel();
+    // the child keeps running past the grace, its stderr is drained and it is waited for so that it isn't left a zombie
+    thread::spawn(move || {
+        let mut stderr = String::new();
+        if let Some(mut pipe) = child.stderr.take() {
+            let _ = pipe.read_to_string(&mut stderr);
+        }
+        let _ = sender.send((child.wait(), stderr));
+    });
+    Ok(match receiver.recv_timeout(OPENER_GRACE) {
+        Ok((Ok(status), stderr)) if !status.success() => {
+            let reason = stderr
+                .lines()
+                .map(str::trim)
+                .rfind(|line| !line.is_empty())
+                .map_or_else(|| format!("{program} exited with {status}"), str::to_string);
+            Err(format!("Failed to open: {reason}"))
+        }
+        _ => Ok(()),
+    })
+}
+
+fn on_path(program: &str) -> bool {
+    let names = if cfg!(windows) {
+        vec![format!("{program}.exe"), program.to_string()]
+    } else {
+        vec![program.to_string()]
+    };
+    std::env::var_os("PATH").is_some_and(|path| {
+        std::env::split_paths(&path).any(|dir| names.iter().any(|name| dir.join(name).is_file()))
+    })
+}
+
+/// The opener of the system, if it can't be found on the path, so that the view can warn about it before anything
+/// fails to open.
+pub fn missing_opener() -> Option<&'static str> {
+    let provider = provider();
+    (!provider.has_opener()).then(|| provider.opener())
+}
+
+/// Whether the view seems to run on another machine than the one the user sits at, over ssh or without a display,
+/// where whatever it opens would open out of the user's reach.
+pub fn is_remote_session() -> bool {
+    let set = |name: &str| std::env::var_os(name).is_some_and(|value| !value.is_empty());
+    if set("SSH_CONNECTION") || set("SSH_CLIENT") || set("SSH_TTY") {
+        return true;
+    }
+    // windows and macos always have a display to open apps on
+    cfg!(all(unix, not(target_os = "macos"))) && !set("DISPLAY") && !set("WAYLAND_DISPLAY")
+}
+
+#[cfg(any(target_os = "windows", test))]
+struct WindowsProvider(Box<dyn Runner>);
+
+#[cfg(any(target_os = "windows", test))]
+impl OsProvider for WindowsProvider {
+    fn runner(&self) -> &dyn Runner {
+        self.0.as_ref()
+    }
+
+    fn opener(&self) -> &'static str {
+        "explorer"
+    }
+
+    fn explore_at_file(&self, link: &str) -> Result<(), String> {
+        self.runner()
+            .spawn(Command::new(self.opener()).arg("/select,").arg(link))
+    }
+}
+
+struct LinuxProvider(Box<dyn Runner>);
+
+impl OsProvider for LinuxProvider {
+    fn runner(&self) -> &dyn Runner {
+        self.0.as_ref()
+    }
+
+    fn opener(&self) -> &'static str {
+        "xdg-open"
+    }
+
+    // xdg-open can't select a file, the directory it is in is opened instead
+    fn explore_at_file(&self, link: &str) -> Result<(), String> {
+        let dir = Path::new(link).parent().unwrap_or(Path::new(link));
+        self.runner().spawn(Command::new(self.opener()).arg(dir))
+    }
+}
+
+/// Linux under WSL, where targets are opened by the apps of Windows, through `wslview` if it is installed and Windows'
+/// explorer otherwise.
+struct WslProvider(Box<dyn Runner>);
+
+impl WslProvider {
+    // explorer opens files and urls with their default apps too, but only knows windows paths
+    fn open(&self, link: &str) -> Result<(), String> {
+        if self.runner().on_path("wslview") {
+            self.runner().spawn(Command::new("wslview").arg(link))
+        } else {
+            self.runner()
+                .spawn(Command::new(self.opener()).arg(windows_path(link)))
+        }
+    }
+}
+
+impl OsProvider for WslProvider {
+    fn runner(&self) -> &dyn Runner {
+        self.0.as_ref()
+    }
+
+    fn opener(&self) -> &'static str {
+        "explorer.exe"
+    }
+
+    fn open_file(&self, link: &str) -> Result<(), String> {
+        self.open(link)
+    }
+
+    fn explore_at_file(&self, link: &str) -> Result<(), String> {
+        self.runner()
+            .spawn(Command::new(self.opener()).arg(format!("/select,{}", windows_path(link))))
+    }
+
+    fn open_dir(&self, link: &str) -> Result<(), String> {
+        self.runner()
+            .spawn(Command::new(self.opener()).arg(windows_path(link)))
+    }
+
+    fn open_url(&self, link: &str) -> Result<(), String> {
+        if self.runner().on_path("wslview") {
+            self.runner().spawn(Command::new("wslview").arg(link))
+        } else {
+            self.runner().spawn(Command::new(self.opener()).arg(link))
+        }
+    }
+}
+
+/// Linux inside a Flatpak or Snap sandbox, where the programs that open targets can't be spawned outside of it, so
+/// targets are handed to the desktop portal of the session instead.
+#[cfg(target_os = "linux")]
+struct PortalProvider(Box<dyn Runner>);
+
+#[cfg(target_os = "linux")]
+impl PortalProvider {
+    fn call<B>(&self, method: &str, body: &B) -> Result<(), String>
+    where
+        B: Serialize + zbus::zvariant::DynamicType,
+    {
+        let connection = zbus::blocking::Connection::session()
+            .map_err(|err| format!("Failed to reach the desktop portal: {err}"))?;
+        connection
+            .call_method(
+                Some("org.freedesktop.portal.Desktop"),
+                "/org/freedesktop/portal/desktop",
+                Some("org.freedesktop.portal.OpenURI"),
+                method,
+                body,
+            )
+            .map(drop)
+            .map_err(|err| format!("Failed to open through the desktop portal: {err}"))
+    }
+
+    // files are handed to the portal already open, as their paths inside the sandbox may mean nothing outside of it
+    fn call_with_file(&self, method: &str, link: &str) -> Result<(), String> {
+        use std::os::fd::AsRawFd;
+
+        let file =
+            std::fs::File::open(link).map_err(|err| format!("Failed to open {link}: {err}"))?;
+        let options = HashMap::<&str, zbus::zvariant::Value>::new();
+        self.call(
+            method,
+            &("", zbus::zvariant::Fd::from(file.as_raw_fd()), options),
+        )
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl OsProvider for PortalProvider {
+    fn runner(&self) -> &dyn Runner {
+        self.0.as_ref()
+    }
+
+    fn opener(&self) -> &'static str {
+        "xdg-desktop-portal"
+    }
+
+    // the portal is a service of the session rather than a program on the path
+    fn has_opener(&self) -> bool {
+        true
+    }
+
+    fn open_file(&self, link: &str) -> Result<(), String> {
+        self.call_with_file("OpenFile", link)
+    }
+
+    fn explore_at_file(&self, link: &str) -> Result<(), String> {
+        self.call_with_file("OpenDirectory", link)
+    }
+
+    fn open_dir(&self, link: &str) -> Result<(), String> {
+        self.call_with_file("OpenFile", link)
+    }
+
+    fn open_url(&self, link: &str) -> Result<(), String> {
+        let options = HashMap::<&str, zbus::zvariant::Value>::new();
+        self.call("OpenURI", &("", link, options))
+    }
+}
+
+/// whether this runs in a Flatpak or Snap sandbox, which mark themselves in the environment or the file system's root
+#[cfg(target_os = "linux")]
+fn is_sandboxed() -> bool {
+    Path::new("/.flatpak-info").exists()
+        || std::env::var_os("FLATPAK_ID").is_some()
+        || std::env::var_os("SNAP").is_some()
+}
+
+/// whether this is linux running under WSL, whose kernel names itself after microsoft
+fn is_wsl() -> bool {
+    std::env::var_os("WSL_DISTRO_NAME").is_some()
+        || std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .is_ok_and(|release| release.to_lowercase().contains("microsoft"))
+}
+
+/// The windows path of a linux path under WSL: the drives mounted at `/mnt/<drive>` are translated directly, and the
+/// rest of the file system is asked of `wslpath`, e.g. `\\wsl$\Ubuntu\home`.
+fn windows_path(path: &str) -> String {
+    let mut parts = path.splitn(4, '/');
+    if let (Some(""), Some("mnt"), Some(drive)) = (parts.next(), parts.next(), parts.next()) {
+        if drive.len() == 1 && drive.chars().all(|c| c.is_ascii_alphabetic()) {
+            let rest = parts.next().unwrap_or_default();
+            return format!("{}:\\{}", drive.to_uppercase(), rest.replace('/', "\\"));
+        }
+    }
+    Command::new("wslpath")
+        .arg("-w")
+        .arg(path)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+#[cfg(any(target_os = "macos", test))]
+struct MacProvider(Box<dyn Runner>);
+
+#[cfg(any(target_os = "macos", test))]
+impl OsProvider for MacProvider {
+    fn runner(&self) -> &dyn Runner {
+        self.0.as_ref()
+    }
+
+    fn opener(&self) -> &'static str {
+        "open"
+    }
+
+    fn explore_at_file(&self, link: &str) -> Result<(), String> {
+        self.runner()
+            .spawn(Command::new(self.opener()).arg("-R").arg(link))
+    }
+}
+
+/// the provider of this system, which runs its programs
+pub fn provider() -> Box<dyn OsProvider> {
+    provider_with(Box::new(SystemRunner))
+}
+
+#[cfg(target_os = "windows")]
+pub fn provider_with(runner: Box<dyn Runner>) -> Box<dyn OsProvider> {
+    Box::new(WindowsProvider(runner))
+}
+
+/// WSL and sandboxes are told apart from plain linux when opening, as the same binary runs on all of them
+#[cfg(target_os = "linux")]
+pub fn provider_with(runner: Box<dyn Runner>) -> Box<dyn OsProvider> {
+    if is_sandboxed() {
+        Box::new(PortalProvider(runner))
+    } else if is_wsl() {
+        Box::new(WslProvider(runner))
+    } else {
+        Box::new(LinuxProvider(runner))
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn provider_with(runner: Box<dyn Runner>) -> Box<dyn OsProvider> {
+    Box::new(MacProvider(runner))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+
+    // the commands run, each as its program followed by its arguments
+    type Log = Rc<RefCell<Vec<Vec<String>>>>;
+
+    /// records the commands instead of running them, with `installed` the programs on its path
+    #[derive(Default)]
+    struct Recorder {
+        installed: Vec<&'static str>,
+        commands: Log,
+    }
+
+    impl Recorder {
+        fn with(installed: &[&'static str]) -> (Box<dyn Runner>, Log) {
+            let recorder = Self {
+                installed: installed.to_vec(),
+                ..Self::default()
+            };
+            let commands = recorder.commands.clone();
+            (Box::new(recorder), commands)
+        }
+    }
+
+    impl Runner for Recorder {
+        fn spawn(&self, command: &mut Command) -> Result<(), String> {
+            let command = std::iter::once(command.get_program())
+                .chain(command.get_args())
+                .map(|part| part.to_string_lossy().into_owned())
+                .collect();
+            self.commands.borrow_mut().push(command);
+            Ok(())
+        }
+
+        fn open_default(&self, target: &str) -> Result<(), String> {
+            self.commands
+                .borrow_mut()
+                .push(vec!["<default>".to_string(), target.to_string()]);
+            Ok(())
+        }
+
+        fn on_path(&self, program: &str) -> bool {
+            self.installed.contains(&program)
+        }
+    }
+
+    fn commands(commands: &Log) -> Vec<Vec<String>> {
+        commands.borrow().clone()
+    }
+
+    #[test]
+    fn linux_opens_with_xdg_open() {
+        let (runner, recorded) = Recorder::with(&["xdg-open"]);
+        let provider = LinuxProvider(runner);
+        assert!(provider.has_opener());
+        provider.open_file("/home/a.txt").unwrap();
+        provider.open_dir("/home").unwrap();
+        provider.explore_at_file("/home/a.txt").unwrap();
+        provider.open_url("https://example.com").unwrap();
+        assert_eq!(
+            commands(&recorded),
+            [
+                vec!["<default>", "/home/a.txt"],
+                vec!["xdg-open", "/home"],
+                vec!["xdg-open", "/home"],
+                vec!["<default>", "https://example.com"],
+            ]
+        );
+    }
+
+    #[test]
+    fn linux_without_xdg_open() {
+        let (runner, _) = Recorder::with(&[]);
+        assert!(!LinuxProvider(runner).has_opener());
+    }
+
+    #[test]
+    fn wsl_opens_with_wslview() {
+        let (runner, recorded) = Recorder::with(&["wslview", "explorer.exe"]);
+        let provider = WslProvider(runner);
+        provider.open_file("/mnt/c/Users/a.txt").unwrap();
+        provider.open_url("https://example.com").unwrap();
+        assert_eq!(
+            commands(&recorded),
+            [
+                vec!["wslview", "/mnt/c/Users/a.txt"],
+                vec!["wslview", "https://example.com"],
+            ]
+        );
+    }
+
+    #[test]
+    fn wsl_falls_back_on_explorer_with_windows_paths() {
+        let (runner, recorded) = Recorder::with(&["explorer.exe"]);
+        let provider = WslProvider(runner);
+        assert!(provider.has_opener());
+        provider.open_file("/mnt/c/Users/a.txt").unwrap();
+        provider.open_dir("/mnt/d/games").unwrap();
+        provider.explore_at_file("/mnt/c/Users/a.txt").unwrap();
+        provider.open_url("https://example.com").unwrap();
+        assert_eq!(
+            commands(&recorded),
+            [
+                vec!["explorer.exe", "C:\\Users\\a.txt"],
+                vec!["explorer.exe", "D:\\games"],
+                vec!["explorer.exe", "/select,C:\\Users\\a.txt"],
+                vec!["explorer.exe", "https://example.com"],
+            ]
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn portal_runs_no_programs() {
+        let (runner, recorded) = Recorder::with(&[]);
+        let provider = PortalProvider(runner);
+        // the portal is on the session bus, not the path
+        assert!(provider.has_opener());
+        // files are opened before the portal is called, so a missing one fails without reaching it
+        assert!(provider
+            .explore_at_file("/nonexistent/flist/a.txt")
+            .is_err());
+        assert!(provider.open_file("/nonexistent/flist/a.txt").is_err());
+        assert!(commands(&recorded).is_empty());
+    }
+
+    #[test]
+    fn mac_opens_with_open() {
+        let (runner, recorded) = Recorder::with(&["open"]);
+        let provider = MacProvider(runner);
+        assert!(provider.has_opener());
+        provider.open_file("/Users/a.txt").unwrap();
+        provider.open_dir("/Users").unwrap();
+        provider.explore_at_file("/Users/a.txt").unwrap();
+        assert_eq!(
+            commands(&recorded),
+            [
+                vec!["<default>", "/Users/a.txt"],
+                vec!["open", "/Users"],
+                vec!["open", "-R", "/Users/a.txt"],
+            ]
+        );
+    }
+
+    #[test]
+    fn windows_selects_with_explorer() {
+        let (runner, recorded) = Recorder::with(&["explorer"]);
+        let provider = WindowsProvider(runner);
+        provider.explore_at_file("C:\\a.txt").unwrap();
+        assert_eq!(
+            commands(&recorded),
+            [vec!["explorer", "/select,", "C:\\a.txt"]]
+        );
+    }
+}
@@ -1,8 +1,45 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
 use crate::config::Entry;
-use crate::config::FlistConfig;
+use crate::config::{
+    DuplicateNames, FlistConfig, InsertPosition, InsertSource, OverflowPolicy, Watch,
+};
+use crate::errors::{FlistError, InsertError, RevertError, SectionFull};
+use crate::events::{EventKind, ProjectEvent};
+use crate::feed::{self, FeedItem};
+use crate::fields::FieldValue;
+use crate::journal::{self, JournalEvent, List, Snapshot};
+use crate::link::Link;
+use crate::requests::InsertRequest;
+use crate::target;
+use crate::tree;
+
+/// the file that keeps the id the next entry gets, so that the ids of removed entries are never given out again
+pub const IDS_FILE: &str = "ids.json";
+
+#[derive(Debug, Clone, Copy)]
+pub enum EntryLocation {
+    Entries(usize),
+    Archive(usize),
+    Snoozed(usize),
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Ids {
+    next: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HistoryItem {
+    pub id: u64,
+    pub opened_at: DateTime<Utc>,
+}
 
 #[derive(Debug)]
 pub struct Project {
@@ -10,6 +47,26 @@ pub struct Project {
     pub config: FlistConfig,
     pub entries: Vec<Entry>,
     pub archive: Vec<Entry>,
+    /// entries hidden from the main list until their `snoozed_until`, sorted by it
+    pub snoozed: Vec<Entry>,
+    /// entries listed by providers, these are refreshed rather than saved
+    pub provided: Vec<Entry>,
+    /// the most recently opened entries, latest first
+    pub history: Vec<HistoryItem>,
+    /// who is making changes, recorded on the entries they add or change
+    pub identity: Option<String>,
+    /// whether saving prints what it would change instead of writing it
+    pub dry_run: bool,
+    /// the order the main list is shown in, which isn't saved
+    pub sort: tree::Sort,
+    // events that were not yet delivered to webhooks
+    events: Vec<ProjectEvent>,
+    // the saved entries by id, to journal what changed by the next save
+    saved: HashMap<u64, Snapshot>,
+    // the id of the next entry, above every id the project ever gave out
+    next_entry_id: u64,
+    // the id of the next journal event
+    next_journal_id: u64,
 }
 
 impl Project {
@@ -18,53 +75,466 @@ impl Project {
         config: FlistConfig,
         entries: Vec<Entry>,
         archive: Vec<Entry>,
+        snoozed: Vec<Entry>,
+        history: Vec<HistoryItem>,
     ) -> Self {
         Self {
             root,
             config,
             entries,
             archive,
+            snoozed,
+            provided: Vec::new(),
+            history,
+            identity: current_user(),
+            dry_run: false,
+            sort: tree::Sort::default(),
+            events: Vec::new(),
+            saved: HashMap::new(),
+            next_journal_id: 1,
+            next_entry_id: 1,
         }
     }
 
-    pub fn from_dir(root: &Path, config: FlistConfig) -> Self {
-        let entries_path = root.join("entries.json");
-        let archive_path = root.join("archive.json");
-        let entries = if entries_path.exists() {
-            serde_json::from_str(
-                &std::fs::read_to_string(&entries_path).expect("Failed to read entries file"),
-            )
-            .expect("Failed to parse entries file")
+    pub fn from_dir(root: &Path, config: FlistConfig) -> Result<Self, FlistError> {
+        let entries = read_list(&root.join("entries.json"))?;
+        let archive = read_list(&root.join("archive.json"))?;
+        let snoozed = read_list(&root.join("snoozed.json"))?;
+        // the history is a convenience, a broken history file shouldn't prevent opening the project
+        let history = fs::read_to_string(root.join("history.json"))
+            .ok()
+            .and_then(|history| serde_json::from_str(&history).ok())
+            .unwrap_or_default();
+        let mut ret = Self::new(
+            root.to_path_buf(),
+            config,
+            entries,
+            archive,
+            snoozed,
+            history,
+        );
+        let opened = ret.history.iter().map(|item| item.id);
+        let listed = ret.all_entries().map(|entry| entry.id);
+        ret.next_entry_id = next_entry_id(root, opened.chain(listed));
+        ret.assign_missing_ids();
+        ret.saved = ret.snapshots();
+        ret.next_journal_id = journal::next_id(root);
+        Ok(ret)
+    }
+
+    /// the entries of the saved lists by id, as the journal compares them
+    pub fn snapshots(&self) -> HashMap<u64, Snapshot> {
+        journal::snapshots([
+            (List::Entries, self.entries.as_slice()),
+            (List::Archive, self.archive.as_slice()),
+            (List::Snoozed, self.snoozed.as_slice()),
+        ])
+    }
+
+    fn record(&mut self, kind: EventKind, entry: &Entry) {
+        self.events.push(ProjectEvent {
+            kind,
+            entry: entry.clone(),
+        });
+    }
+
+    /// records the opening for the hooks and the history, the history is saved right away
+    pub fn opened(&mut self, id: u64) {
+        // the page of a watched entry is seen as it was last checked
+        if let Some(watch) = self.get_mut(id).and_then(|entry| entry.watch.as_mut()) {
+            watch.seen = watch.latest;
+        }
+        if let Some(entry) = self.get(id).cloned() {
+            self.record(EventKind::Opened, &entry);
+            self.history.retain(|item| item.id != id);
+            self.history.insert(
+                0,
+                HistoryItem {
+                    id,
+                    opened_at: Utc::now(),
+                },
+            );
+            self.history.truncate(self.config.history_size);
+            let history =
+                serde_json::to_string(&self.history).expect("Failed to serialize history");
+            // like reading it, a history that can't be written shouldn't stop the opening
+            let _ = fs::write(self.root.join("history.json"), history);
+        }
+    }
+
+    /// the starred entries of the main list, the snoozed entries, and the archive
+    pub fn starred(&self) -> Vec<&Entry> {
+        self.all_entries().filter(|entry| entry.starred).collect()
+    }
+
+    pub fn toggle_star(&mut self, id: u64) {
+        if let Some(entry) = self.get_mut(id) {
+            entry.starred = !entry.starred;
+        }
+        self.touch(id);
+    }
+
+    pub fn toggle_pin(&mut self, id: u64) {
+        if let Some(entry) = self.get_mut(id) {
+            entry.pinned = !entry.pinned;
+        }
+        self.touch(id);
+    }
+
+    /// records a change to the entry with `id`: its revision goes up, and the current user is the last to change it
+    fn touch(&mut self, id: u64) {
+        let identity = self.identity.clone();
+        if let Some(entry) = self.get_mut(id) {
+            entry.revision += 1;
+            if identity.is_some() {
+                entry.modified_by = identity;
+            }
+        }
+    }
+
+    fn touch_idx(&mut self, entry_idx: usize) {
+        self.touch(self.entries[entry_idx].id);
+    }
+
+    /// the entries of the history that still exist, with when they were opened
+    pub fn recently_opened(&self) -> Vec<(&Entry, DateTime<Utc>)> {
+        self.history
+            .iter()
+            .filter_map(|item| Some((self.get(item.id)?, item.opened_at)))
+            .collect()
+    }
+
+    pub fn take_events(&mut self) -> Vec<ProjectEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn all_entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries
+            .iter()
+            .chain(self.archive.iter())
+            .chain(self.snoozed.iter())
+    }
+
+    /// The inferred `name` of an entry linking to `link`, changed according to the config if another entry already has
+    /// it.
+    pub fn unique_name(&self, name: String, link: &Link) -> String {
+        let taken = |name: &str| self.all_entries().any(|entry| entry.name == name);
+        if !taken(&name) {
+            return name;
+        }
+        match self.config.duplicate_names {
+            DuplicateNames::Keep => return name,
+            DuplicateNames::Context => {
+                if let Some(context) = link.context() {
+                    let with_context = format!("{name} ({context})");
+                    if !taken(&with_context) {
+                        return with_context;
+                    }
+                }
+            }
+            DuplicateNames::Numbered => {}
+        }
+        // also when the context is taken too, e.g. two README.md files in directories of the same name
+        (2..)
+            .map(|n| format!("{name} ({n})"))
+            .find(|numbered| !taken(numbered))
+            .unwrap()
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let listed = self.all_entries().map(|entry| entry.id).max().unwrap_or(0);
+        let id = self.next_entry_id.max(listed + 1);
+        self.next_entry_id = id + 1;
+        id
+    }
+
+    /// projects from before entries had ids get them on load
+    fn assign_missing_ids(&mut self) {
+        let mut next_id = self.next_entry_id;
+        for entry in self
+            .entries
+            .iter_mut()
+            .chain(self.archive.iter_mut())
+            .chain(self.snoozed.iter_mut())
+            .filter(|entry| entry.id == 0)
+        {
+            entry.id = next_id;
+            next_id += 1;
+        }
+        self.next_entry_id = next_id;
+    }
+
+    pub fn locate(&self, id: u64) -> Option<EntryLocation> {
+        if let Some(idx) = self.entries.iter().position(|entry| entry.id == id) {
+            Some(EntryLocation::Entries(idx))
+        } else if let Some(idx) = self.archive.iter().position(|entry| entry.id == id) {
+            Some(EntryLocation::Archive(idx))
         } else {
-            vec![]
-        };
-        let archive = if archive_path.exists() {
-            serde_json::from_str(
-                &std::fs::read_to_string(&archive_path).expect("Failed to read archive file"),
-            )
-            .expect("Failed to parse archive file")
+            self.snoozed
+                .iter()
+                .position(|entry| entry.id == id)
+                .map(EntryLocation::Snoozed)
+        }
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Entry> {
+        self.all_entries().find(|entry| entry.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut Entry> {
+        self.entries
+            .iter_mut()
+            .chain(self.archive.iter_mut())
+            .chain(self.snoozed.iter_mut())
+            .find(|entry| entry.id == id)
+    }
+
+    /// The id of the entry at `target` in the main list if it is a number, or of the entry named `target` otherwise,
+    /// looked for among the main and snoozed entries, and the archived ones if `archived` is set.
+    pub fn find(&self, target: &str, archived: bool) -> Result<u64, String> {
+        if let Ok(idx) = target.parse::<usize>() {
+            return self
+                .entries
+                .get(idx)
+                .map(|entry| entry.id)
+                .ok_or_else(|| format!("No entry at index {idx}"));
+        }
+        let archive = if archived { &self.archive[..] } else { &[] };
+        self.entries
+            .iter()
+            .chain(self.snoozed.iter())
+            .chain(archive.iter())
+            .find(|entry| entry.name == target)
+            .map(|entry| entry.id)
+            .ok_or_else(|| format!("No entry named {target}"))
+    }
+
+    /// deletes the entry with `id` wherever it is, without archiving it
+    pub fn remove(&mut self, id: u64) -> Option<Entry> {
+        Some(match self.locate(id)? {
+            EntryLocation::Entries(idx) => self.entries.remove(idx),
+            EntryLocation::Archive(idx) => self.archive.remove(idx),
+            EntryLocation::Snoozed(idx) => self.snoozed.remove(idx),
+        })
+    }
+
+    /// entries that list the entry with `id` in their see-also references
+    pub fn referenced_by(&self, id: u64) -> impl Iterator<Item = &Entry> {
+        self.all_entries()
+            .filter(move |entry| entry.see_also.contains(&id))
+    }
+
+    pub fn toggle_reference(&mut self, entry_idx: usize, target_id: u64) {
+        let see_also = &mut self.entries[entry_idx].see_also;
+        if let Some(idx) = see_also.iter().position(|id| *id == target_id) {
+            see_also.remove(idx);
         } else {
-            vec![]
-        };
-        Self::new(root.to_path_buf(), config, entries, archive)
+            see_also.push(target_id);
+        }
+        self.touch_idx(entry_idx);
     }
 
-    pub fn insert_entry(&mut self, entry: Entry) {
-        self.entries.insert(0, entry)
+    /// the indices of the entries in section `name`, other than the entry with id `placing`
+    fn section_indices<'a>(
+        &'a self,
+        name: &'a str,
+        placing: Option<u64>,
+    ) -> impl Iterator<Item = usize> + 'a {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(move |(_, entry)| {
+                entry.section.as_deref() == Some(name) && Some(entry.id) != placing
+            })
+            .map(|(idx, _)| idx)
     }
 
-    pub fn insert_entry_at(&mut self, entry: Entry, idx: usize) {
-        self.entries.insert(idx, entry)
+    /// Makes room for one more entry in `section` according to the overflow policies of the sections involved, never
+    /// counting or archiving the entry with id `placing`, the one being moved there. Returns the section the entry
+    /// should go into.
+    fn make_room(
+        &mut self,
+        section: Option<String>,
+        placing: Option<u64>,
+    ) -> Result<Option<String>, SectionFull> {
+        let mut section = section;
+        let mut spilled_from = Vec::new();
+        loop {
+            let Some(name) = section.clone() else {
+                return Ok(None);
+            };
+            let Some((max, overflow)) = self
+                .config
+                .section(&name)
+                .and_then(|config| Some((config.max?, config.overflow.clone())))
+            else {
+                return Ok(section);
+            };
+            if self.section_indices(&name, placing).count() < max {
+                return Ok(section);
+            }
+            match overflow {
+                OverflowPolicy::Block => return Err(SectionFull(name)),
+                OverflowPolicy::ArchiveOldest => {
+                    while self.section_indices(&name, placing).count() >= max {
+                        let Some(oldest) = self
+                            .section_indices(&name, placing)
+                            .min_by_key(|idx| self.entries[*idx].time_added)
+                        else {
+                            // a section with a max of 0 can never hold entries
+                            return Err(SectionFull(name));
+                        };
+                        self.archive_entry(oldest);
+                    }
+                    return Ok(section);
+                }
+                OverflowPolicy::Spill(into) => {
+                    spilled_from.push(name.clone());
+                    if spilled_from.contains(&into) {
+                        return Err(SectionFull(name));
+                    }
+                    section = Some(into);
+                }
+            }
+        }
     }
 
-    pub fn archive_entry(&mut self, entry_idx: usize) {
-        let entry = self.entries.remove(entry_idx);
+    /// inserts the entry where the config puts new entries by default, the top unless configured otherwise
+    pub fn insert_entry(&mut self, entry: Entry) -> Result<(), InsertError> {
+        let position = self.config.insert.default.unwrap_or(InsertPosition::Top);
+        self.insert_entry_in(entry, position, None).map(|_| ())
+    }
+
+    /// Inserts the entry before the entry currently at `idx`, respecting the rules of the config and the limit of its
+    /// section. Returns the index the entry ended up at, which might differ from `idx` if entries had to be archived.
+    pub fn insert_entry_at(&mut self, mut entry: Entry, idx: usize) -> Result<usize, InsertError> {
+        let violations = self.config.rules.violations(&entry);
+        if !violations.is_empty() {
+            return Err(InsertError::Invalid(violations));
+        }
+        let anchor = self.entries.get(idx).map(|entry| entry.id);
+        entry.section = self.make_room(entry.section.take(), None)?;
+        let idx = anchor
+            .and_then(|id| self.entries.iter().position(|entry| entry.id == id))
+            .unwrap_or(idx.min(self.entries.len()));
+        if entry.id == 0 {
+            entry.id = self.next_id();
+        }
+        if entry.added_by.is_none() {
+            entry.added_by = self.identity.clone();
+        }
+        self.record(EventKind::Added, &entry);
+        self.entries.insert(idx, entry);
+        Ok(idx)
+    }
+
+    /// Adds an entry captured from outside the list, i.e. sent by another flist, added from the command line, or
+    /// pasted, where the config puts entries from `source`. `selected` is the index of the selected main entry, if any.
+    pub fn capture(
+        &mut self,
+        entry: Entry,
+        source: InsertSource,
+        selected: Option<usize>,
+    ) -> Result<usize, InsertError> {
+        let position = self.config.insert.position(source, self.config.inbox);
+        self.insert_entry_in(entry, position, selected)
+    }
+
+    fn insert_entry_in(
+        &mut self,
+        mut entry: Entry,
+        position: InsertPosition,
+        selected: Option<usize>,
+    ) -> Result<usize, InsertError> {
+        match (position, selected.filter(|idx| *idx < self.entries.len())) {
+            (InsertPosition::Top, _) | (InsertPosition::AfterSelection, None) => {
+                self.insert_entry_at(entry, 0)
+            }
+            (InsertPosition::Bottom, _) => {
+                // the bottom of the list is outside any nesting
+                entry.parent = None;
+                let len = self.entries.len();
+                self.insert_entry_at(entry, len)
+            }
+            (InsertPosition::AfterSelection, Some(idx)) => {
+                let selected = &self.entries[idx];
+                entry.parent = selected.parent;
+                if entry.section.is_none() {
+                    entry.section = selected.section.clone();
+                }
+                self.insert_entry_at(entry, idx + 1)
+            }
+            (InsertPosition::Inbox, _) => {
+                entry.inbox = true;
+                entry.parent = None;
+                self.insert_entry_at(entry, 0)
+            }
+        }
+    }
+
+    /// the indices of the main entries waiting in the inbox, in list order
+    pub fn inbox(&self) -> Vec<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.inbox)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// takes the entry at `entry_idx` out of the inbox, leaving it where it is in the list
+    pub fn keep(&mut self, entry_idx: usize) {
+        self.entries[entry_idx].inbox = false;
+        self.touch_idx(entry_idx);
+    }
+
+    /// moves the entry at `entry_idx` to `section`, returns its new index
+    pub fn set_section(
+        &mut self,
+        entry_idx: usize,
+        section: Option<String>,
+    ) -> Result<usize, SectionFull> {
+        let id = self.entries[entry_idx].id;
+        let section = self.make_room(section, Some(id))?;
+        // making room archives other entries only, which may come before the entry and move it up
+        let Some(entry_idx) = self.entries.iter().position(|entry| entry.id == id) else {
+            return Err(SectionFull(section.unwrap_or_default()));
+        };
+        self.entries[entry_idx].section = section;
+        self.touch_idx(entry_idx);
+        Ok(entry_idx)
+    }
+
+    fn push_to_archive(&mut self, mut entry: Entry) {
+        entry.revision += 1;
+        if let Some(identity) = &self.identity {
+            entry.modified_by = Some(identity.clone());
+        }
+        self.record(EventKind::Archived, &entry);
         self.archive.insert(0, entry);
         if self.archive.len() > self.config.max_archive {
             self.archive.pop();
         }
     }
 
+    pub fn archive_entry(&mut self, entry_idx: usize) {
+        let entry = self.entries.remove(entry_idx);
+        self.push_to_archive(entry);
+    }
+
+    /// archives the entry with `id`, whether it is in the main list or snoozed
+    pub fn complete(&mut self, id: u64) {
+        match self.locate(id) {
+            Some(EntryLocation::Entries(idx)) => self.archive_entry(idx),
+            Some(EntryLocation::Snoozed(idx)) => {
+                let mut entry = self.snoozed.remove(idx);
+                entry.snoozed_until = None;
+                self.push_to_archive(entry);
+            }
+            Some(EntryLocation::Archive(_)) | None => {}
+        }
+    }
+
     pub fn remove_from_archive(&mut self, entry_idx: usize) {
         self.archive.remove(entry_idx);
     }
@@ -72,6 +542,347 @@ impl Project {
     pub fn restore_from_archive(&mut self, entry_idx: usize) {
         let entry = self.archive.remove(entry_idx);
         self.entries.insert(0, entry);
+        self.touch_idx(0);
+    }
+
+    fn insert_snoozed(&mut self, entry: Entry) {
+        let idx = self
+            .snoozed
+            .partition_point(|snoozed| snoozed.snoozed_until <= entry.snoozed_until);
+        self.snoozed.insert(idx, entry);
+    }
+
+    pub fn snooze_entry(&mut self, entry_idx: usize, until: DateTime<Utc>) {
+        let mut entry = self.entries.remove(entry_idx);
+        entry.snoozed_until = Some(until);
+        let id = entry.id;
+        self.insert_snoozed(entry);
+        self.touch(id);
+    }
+
+    /// pushes the due date of the entry with `id` to `by` after it, or after `now` if it is overdue
+    pub fn postpone_due(&mut self, id: u64, by: Duration, now: DateTime<Utc>) {
+        let entry = self
+            .entries
+            .iter_mut()
+            .chain(self.snoozed.iter_mut())
+            .find(|entry| entry.id == id);
+        if let Some(entry) = entry {
+            if let Some(due) = entry.due {
+                entry.due = Some(due.max(now) + by);
+                entry.due_reminded = false;
+            }
+        }
+        self.touch(id);
+    }
+
+    /// keeps the snoozed entry with `id` snoozed for `by` longer
+    pub fn postpone_snooze(&mut self, id: u64, by: Duration, now: DateTime<Utc>) {
+        if let Some(EntryLocation::Snoozed(idx)) = self.locate(id) {
+            let mut entry = self.snoozed.remove(idx);
+            entry.snoozed_until = Some(entry.snoozed_until.unwrap_or(now).max(now) + by);
+            self.insert_snoozed(entry);
+            self.touch(id);
+        }
+    }
+
+    pub fn unsnooze(&mut self, entry_idx: usize) {
+        let mut entry = self.snoozed.remove(entry_idx);
+        entry.snoozed_until = None;
+        self.entries.insert(0, entry);
+    }
+
+    /// returns the number of snoozed entries that were returned to the main list
+    pub fn resurface_snoozed(&mut self, now: DateTime<Utc>) -> usize {
+        let due = self
+            .snoozed
+            .partition_point(|snoozed| snoozed.snoozed_until.is_none_or(|until| until <= now));
+        for _ in 0..due {
+            self.unsnooze(0);
+        }
+        due
+    }
+
+    /// nests the entry at `entry_idx` under its previous sibling, returns whether there was one
+    pub fn indent(&mut self, entry_idx: usize) -> bool {
+        let Some(new_parent) = tree::sibling(&self.entries, entry_idx, false) else {
+            return false;
+        };
+        self.entries[new_parent].collapsed = false;
+        self.entries[entry_idx].parent = Some(self.entries[new_parent].id);
+        self.touch_idx(entry_idx);
+        true
+    }
+
+    /// moves the entry at `entry_idx` out of its parent, to right after it. Returns the entry's new index.
+    pub fn outdent(&mut self, entry_idx: usize) -> Option<usize> {
+        let parent_idx = tree::parent(&self.entries, entry_idx)?;
+        self.entries[entry_idx].parent = self.entries[parent_idx].parent;
+        self.touch_idx(entry_idx);
+        let new_idx = if entry_idx > parent_idx {
+            parent_idx + 1
+        } else {
+            parent_idx
+        };
+        self.move_entry(entry_idx, new_idx);
+        Some(new_idx)
+    }
+
+    pub fn set_collapsed(&mut self, entry_idx: usize, collapsed: bool) {
+        self.entries[entry_idx].collapsed = collapsed;
+    }
+
+    /// expands all the ancestors of the entry at `entry_idx`
+    pub fn reveal(&mut self, entry_idx: usize) {
+        let mut current = entry_idx;
+        // bounded in case a hand-edited file contains a cycle
+        for _ in 0..self.entries.len() {
+            let Some(parent_idx) = tree::parent(&self.entries, current) else {
+                break;
+            };
+            self.entries[parent_idx].collapsed = false;
+            current = parent_idx;
+        }
+    }
+
+    /// marks the main entries whose due date passed, returning those that were not marked before
+    pub fn mark_due(&mut self, now: DateTime<Utc>) -> Vec<Entry> {
+        let mut newly_due = Vec::new();
+        for entry in self.entries.iter_mut() {
+            if entry.due_reminded || entry.due.is_none_or(|due| due > now) {
+                continue;
+            }
+            entry.due_reminded = true;
+            newly_due.push(entry.clone());
+        }
+        for entry in &newly_due {
+            self.record(EventKind::Due, entry);
+        }
+        newly_due
+    }
+
+    /// sets the custom field of the entry at `entry_idx`, or removes it if `value` is None
+    pub fn set_field(&mut self, entry_idx: usize, name: &str, value: Option<FieldValue>) {
+        let fields = &mut self.entries[entry_idx].fields;
+        // replace a differently cased spelling of the same field
+        fields.retain(|key, _| !key.eq_ignore_ascii_case(name));
+        if let Some(value) = value {
+            fields.insert(name.to_string(), value);
+        }
+        self.touch_idx(entry_idx);
+    }
+
+    /// starts or stops checking the page of the entry at `entry_idx` for changes
+    pub fn toggle_watch(&mut self, entry_idx: usize) {
+        let entry = &mut self.entries[entry_idx];
+        entry.watch = match entry.watch {
+            Some(_) => None,
+            None => Some(Watch::default()),
+        };
+        self.touch_idx(entry_idx);
+    }
+
+    /// Records the latest content hash of a watched page, returns whether anything changed. The first hash is the
+    /// content the entry is compared against until it is opened.
+    pub fn watched(&mut self, id: u64, hash: u64) -> bool {
+        let Some(watch) = self.get_mut(id).and_then(|entry| entry.watch.as_mut()) else {
+            return false;
+        };
+        let before = watch.clone();
+        watch.latest = Some(hash);
+        watch.seen.get_or_insert(hash);
+        *watch != before
+    }
+
+    /// Adds the unread items of the feed entry with `id` as temporary entries nested under it, skipping items whose
+    /// link is already in the project, and marks the feed as read. Returns how many entries were added.
+    pub fn expand_feed(&mut self, id: u64, items: Vec<FeedItem>) -> usize {
+        let Some(EntryLocation::Entries(feed_idx)) = self.locate(id) else {
+            return 0;
+        };
+        let feed = &self.entries[feed_idx];
+        let (read, section) = (feed.feed_read, feed.section.clone());
+        let existing = self
+            .all_entries()
+            .map(|entry| entry.link.as_str().to_string())
+            .collect::<HashSet<_>>();
+        let mut added = 0;
+        for item in items
+            .into_iter()
+            .filter(|item| item.is_unread(read) && !existing.contains(&item.link))
+            .take(feed::EXPAND_LIMIT)
+        {
+            let mut entry = Entry::from(InsertRequest {
+                name: item.title,
+                link: Link::from(item.link.as_str()),
+                metadata: Vec::new(),
+                due: None,
+                section: section.clone(),
+                tags: Vec::new(),
+                temp: true,
+            });
+            entry.parent = Some(id);
+            // the feed might have moved if entries were archived to make room
+            let Some(EntryLocation::Entries(feed_idx)) = self.locate(id) else {
+                break;
+            };
+            if self.insert_entry_at(entry, feed_idx + 1 + added).is_ok() {
+                added += 1;
+            }
+        }
+        if let Some(feed) = self.get_mut(id) {
+            feed.feed_read = Some(Utc::now());
+            feed.unread = Some(0);
+            feed.collapsed = false;
+        }
+        self.touch(id);
+        added
+    }
+
+    /// makes the temporary entry at `entry_idx` a regular one, that is saved
+    pub fn keep_temporary(&mut self, entry_idx: usize) {
+        self.entries[entry_idx].ephemeral = false;
+        self.touch_idx(entry_idx);
+    }
+
+    /// tags the entry at `entry_idx`, unless it already has the tag
+    pub fn add_tag(&mut self, entry_idx: usize, tag: &str) {
+        let tags = &mut self.entries[entry_idx].tags;
+        if !tags.iter().any(|existing| existing == tag) {
+            tags.push(tag.to_string());
+        }
+        self.touch_idx(entry_idx);
+    }
+
+    pub fn set_due(&mut self, entry_idx: usize, due: Option<DateTime<Utc>>) {
+        let entry = &mut self.entries[entry_idx];
+        entry.due = due;
+        entry.due_reminded = false;
+        self.touch_idx(entry_idx);
+    }
+
+    /// replaces the name, link, and metadata of the entry at `entry_idx`
+    pub fn edit(&mut self, entry_idx: usize, name: String, link: Link, metadata: Vec<String>) {
+        let entry = &mut self.entries[entry_idx];
+        entry.name = name;
+        entry.link = link;
+        entry.metadata = metadata;
+        self.touch_idx(entry_idx);
+    }
+
+    /// Renames the entry with `id`, unless it is no longer named `expected` or is past `revision`, as when it was
+    /// changed since whoever asks read it.
+    pub fn rename(
+        &mut self,
+        id: u64,
+        name: String,
+        expected: Option<&str>,
+        revision: Option<u64>,
+    ) -> Result<(), String> {
+        let entry = self
+            .get_mut(id)
+            .ok_or_else(|| format!("No entry with id {id}"))?;
+        check_revision(entry, revision)?;
+        if let Some(expected) = expected.filter(|expected| *expected != entry.name) {
+            return Err(format!(
+                "Refused to rename {expected}, it was renamed to {} since",
+                entry.name
+            ));
+        }
+        entry.name = name;
+        self.touch(id);
+        Ok(())
+    }
+
+    /// Points the entry with `id` at `link`, unless it no longer links to `expected` or is past `revision`, as when it
+    /// was changed since whoever asks read it.
+    pub fn change_link(
+        &mut self,
+        id: u64,
+        link: Link,
+        expected: Option<&Link>,
+        revision: Option<u64>,
+    ) -> Result<(), String> {
+        let entry = self
+            .get_mut(id)
+            .ok_or_else(|| format!("No entry with id {id}"))?;
+        check_revision(entry, revision)?;
+        if let Some(expected) = expected.filter(|expected| expected.as_str() != entry.link.as_str())
+        {
+            return Err(format!(
+                "Refused to change the link of {}, it was changed from {} to {} since",
+                entry.name,
+                expected.as_str(),
+                entry.link.as_str()
+            ));
+        }
+        entry.link = link;
+        self.touch(id);
+        Ok(())
+    }
+
+    pub fn set_notes(&mut self, entry_idx: usize, notes: String) {
+        self.entries[entry_idx].notes = notes;
+        self.touch_idx(entry_idx);
+    }
+
+    pub fn relink(&mut self, entry_idx: usize, link: Link) {
+        let entry = &mut self.entries[entry_idx];
+        entry.target_hash = target::content_hash(&link);
+        entry.link = link;
+        self.touch_idx(entry_idx);
+    }
+
+    /// Records the downloaded copy of the entry with `id`'s url. With `relink` the entry links to the copy instead, and
+    /// the url is kept in its notes. Returns whether the entry still exists.
+    pub fn downloaded(&mut self, id: u64, path: String, relink: bool) -> bool {
+        let Some(entry) = self.get_mut(id) else {
+            return false;
+        };
+        if relink {
+            let url = std::mem::replace(&mut entry.link, Link::File(path));
+            entry
+                .metadata
+                .push(format!("downloaded from {}", url.as_str()));
+        } else {
+            entry.download = Some(path);
+        }
+        self.touch(id);
+        true
+    }
+
+    /// Records the snapshot of the entry with `id`'s page. Returns whether the entry still exists.
+    pub fn snapshotted(&mut self, id: u64, path: String) -> bool {
+        let Some(entry) = self.get_mut(id) else {
+            return false;
+        };
+        entry.snapshot = Some(path);
+        self.touch(id);
+        true
+    }
+
+    /// Records the estimated reading time of the entry with `id`. Returns whether the entry still exists.
+    pub fn set_reading_time(&mut self, id: u64, minutes: u32) -> bool {
+        let Some(entry) = self.get_mut(id) else {
+            return false;
+        };
+        entry.reading_minutes = Some(minutes);
+        self.touch(id);
+        true
+    }
+
+    /// Records the content hash of the file targets at `link` that have none yet, e.g. of entries added before hashes
+    /// were kept. Returns whether any entry changed.
+    pub fn hash_target(&mut self, link: &str, hash: u64) -> bool {
+        let mut changed = false;
+        let lists = [&mut self.entries, &mut self.archive, &mut self.snoozed];
+        for entry in lists.into_iter().flatten() {
+            if entry.link.as_str() == link && entry.target_hash.is_none() {
+                entry.target_hash = Some(hash);
+                changed = true;
+            }
+        }
+        changed
     }
 
     pub fn move_entry(&mut self, from: usize, to: usize) {
@@ -82,12 +893,199 @@ impl Project {
         self.entries.insert(to, entry);
     }
 
-    pub fn save(&self) {
+    /// replaces the entries of the provider, keeping the entries of different providers in configuration order
+    pub fn set_provided(&mut self, provider: &str, entries: Vec<Entry>) {
+        self.provided
+            .retain(|entry| entry.provider.as_deref() != Some(provider));
+        self.provided.extend(entries);
+        let order = |entry: &Entry| {
+            self.config
+                .providers
+                .iter()
+                .position(|config| Some(&config.name) == entry.provider.as_ref())
+        };
+        let mut provided = std::mem::take(&mut self.provided);
+        provided.sort_by_key(order);
+        self.provided = provided;
+    }
+
+    /// undoes the changes of a single journal event to its entry, keeping later changes
+    pub fn revert(&mut self, event: &JournalEvent) -> Result<(), RevertError> {
+        let snapshot = self.snapshot(event.entry_id);
+        let reverted = journal::reverted(event, snapshot.as_ref())?;
+        self.place(event.entry_id, reverted);
+        self.touch(event.entry_id);
+        Ok(())
+    }
+
+    /// applies a change that was not saved, as recorded before a crash
+    pub fn replay(&mut self, event: &JournalEvent) {
+        self.place(event.entry_id, event.after.clone());
+    }
+
+    fn list_of(&self, id: u64) -> Option<(usize, List)> {
+        self.locate(id).map(|location| match location {
+            EntryLocation::Entries(idx) => (idx, List::Entries),
+            EntryLocation::Archive(idx) => (idx, List::Archive),
+            EntryLocation::Snoozed(idx) => (idx, List::Snoozed),
+        })
+    }
+
+    fn snapshot(&self, id: u64) -> Option<Snapshot> {
+        let (_, list) = self.list_of(id)?;
+        Some(Snapshot {
+            list,
+            entry: self.get(id)?.clone(),
+        })
+    }
+
+    // puts the entry with `id` in the state of `snapshot`, or removes it if there is none
+    fn place(&mut self, id: u64, snapshot: Option<Snapshot>) {
+        let current = self.list_of(id);
+        match (current, snapshot) {
+            (Some((idx, list)), Some(snapshot)) if list == snapshot.list => {
+                self.list_mut(list)[idx] = snapshot.entry;
+            }
+            (current, snapshot) => {
+                if let Some((idx, list)) = current {
+                    self.list_mut(list).remove(idx);
+                }
+                match snapshot {
+                    Some(Snapshot {
+                        list: List::Snoozed,
+                        entry,
+                    }) => self.insert_snoozed(entry),
+                    Some(Snapshot { list, entry }) => self.list_mut(list).insert(0, entry),
+                    None => {}
+                }
+            }
+        }
+    }
+
+    fn list_mut(&mut self, list: List) -> &mut Vec<Entry> {
+        match list {
+            List::Entries => &mut self.entries,
+            List::Archive => &mut self.archive,
+            List::Snoozed => &mut self.snoozed,
+        }
+    }
+
+    /// writes the entries, and appends what changed since the last save to the journal
+    pub fn save(&mut self) -> Result<(), FlistError> {
+        let current = self.snapshots();
+        let changes = journal::diff(
+            &self.saved,
+            &current,
+            self.next_journal_id,
+            self.identity.as_deref(),
+            Utc::now(),
+        );
+        if self.dry_run {
+            if changes.is_empty() {
+                println!("Nothing would change");
+            }
+            print!("{}", journal::render_diff(&changes));
+            return Ok(());
+        }
+        if !changes.is_empty() {
+            journal::write_unsaved(&self.root, &changes).map_err(FlistError::io(
+                "write",
+                &self.root.join(journal::UNSAVED_FILE),
+            ))?;
+        }
+
         let entries_path = self.root.join("entries.json");
         let archive_path = self.root.join("archive.json");
-        let entries = serde_json::to_string(&self.entries).expect("Failed to serialize entries");
-        let archive = serde_json::to_string(&self.archive).expect("Failed to serialize archive");
-        fs::write(entries_path, entries).expect("Failed to write entries file");
-        fs::write(archive_path, archive).expect("Failed to write archive file");
+        let snoozed_path = self.root.join("snoozed.json");
+        let entries =
+            serde_json::to_string(&saved(&self.entries)).expect("Failed to serialize entries");
+        let archive =
+            serde_json::to_string(&saved(&self.archive)).expect("Failed to serialize archive");
+        let snoozed =
+            serde_json::to_string(&saved(&self.snoozed)).expect("Failed to serialize snoozed");
+        fs::write(&entries_path, entries).map_err(FlistError::io("write", &entries_path))?;
+        fs::write(&archive_path, archive).map_err(FlistError::io("write", &archive_path))?;
+        fs::write(&snoozed_path, snoozed).map_err(FlistError::io("write", &snoozed_path))?;
+        let ids_path = self.root.join(IDS_FILE);
+        let ids = serde_json::to_string(&Ids {
+            next: self.next_entry_id,
+        })
+        .expect("Failed to serialize ids");
+        fs::write(&ids_path, ids).map_err(FlistError::io("write", &ids_path))?;
+        journal::append(&self.root, &changes).map_err(FlistError::io(
+            "write",
+            &self.root.join(journal::JOURNAL_FILE),
+        ))?;
+        journal::clear_unsaved(&self.root).map_err(FlistError::io(
+            "delete",
+            &self.root.join(journal::UNSAVED_FILE),
+        ))?;
+        self.next_journal_id += changes.len() as u64;
+        self.saved = current;
+        Ok(())
+    }
+
+    /// records the changes since the last save to be replayed on the next start, for when flist is stopped before
+    /// it can save them
+    pub fn stash_unsaved(&self) -> io::Result<()> {
+        let changes = journal::diff(
+            &self.saved,
+            &self.snapshots(),
+            self.next_journal_id,
+            self.identity.as_deref(),
+            Utc::now(),
+        );
+        if changes.is_empty() {
+            return Ok(());
+        }
+        journal::write_unsaved(&self.root, &changes)
     }
 }
+
+/// The id the next entry of the project at `root` gets, above `used` and the ids it gave out before. Projects from
+/// before the ids were kept avoid the ids of their journal instead.
+pub fn next_entry_id(root: &Path, used: impl IntoIterator<Item = u64>) -> u64 {
+    let ids = fs::read_to_string(root.join(IDS_FILE))
+        .ok()
+        .and_then(|ids| serde_json::from_str::<Ids>(&ids).ok())
+        .unwrap_or_default();
+    let journaled = journal::read(root)
+        .into_iter()
+        .chain(journal::read_unsaved(root))
+        .map(|event| event.entry_id);
+    let max_id = journaled.chain(used).max().unwrap_or(0);
+    ids.next.max(max_id + 1)
+}
+
+/// the entries saved in the list's file, none if it doesn't exist yet
+fn read_list(path: &Path) -> Result<Vec<Entry>, FlistError> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let text = fs::read_to_string(path).map_err(FlistError::io("read", path))?;
+    serde_json::from_str(&text).map_err(|err| FlistError::malformed(path, err))
+}
+
+/// the entries of the list that are written to its file, all but the temporary ones
+fn saved(entries: &[Entry]) -> Vec<&Entry> {
+    entries.iter().filter(|entry| !entry.ephemeral).collect()
+}
+
+// refuses an edit made by whoever read the entry at `revision`, if the entry changed since
+fn check_revision(entry: &Entry, revision: Option<u64>) -> Result<(), String> {
+    match revision {
+        Some(revision) if revision != entry.revision => Err(format!(
+            "Refused to edit {}, it is at revision {} rather than {revision}",
+            entry.name, entry.revision
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// the user to record on changes, `FLIST_USER` if it is set, otherwise the login name
+fn current_user() -> Option<String> {
+    ["FLIST_USER", "USER", "USERNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok())
+        .filter(|user| !user.is_empty())
+}
@@ -0,0 +1,77 @@
+use std::path::Path;
+use std::process::Stdio;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Entry, ProviderConfig};
+use crate::requests::InsertRequest;
+use crate::shell;
+
+const PROTOCOL_VERSION: u32 = 1;
+
+/// written to a provider's stdin
+#[derive(Debug, Serialize)]
+struct ProviderRequest<'a> {
+    version: u32,
+    project: &'a Path,
+}
+
+/// expected on a provider's stdout
+#[derive(Debug, Deserialize)]
+struct ProviderResponse {
+    entries: Vec<ProvidedEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProvidedEntry {
+    name: String,
+    link: String,
+    #[serde(default)]
+    metadata: Vec<String>,
+}
+
+/// Runs the provider's command and returns the entries it listed, marked with the provider's name. The command gets a
+/// JSON request on stdin and must print a JSON response with the entries before exiting successfully.
+pub fn fetch(provider: &ProviderConfig, project_root: &Path) -> Result<Vec<Entry>, String> {
+    let mut child = shell::command(&provider.command)
+        .current_dir(project_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("Failed to run provider {}: {err}", provider.name))?;
+    let request = ProviderRequest {
+        version: PROTOCOL_VERSION,
+        project: project_root,
+    };
+    // the provider might not read its input, in which case the pipe is closed
+    let _ = serde_json::to_writer(child.stdin.take().unwrap(), &request);
+    let output = child
+        .wait_with_output()
+        .map_err(|err| format!("Failed to run provider {}: {err}", provider.name))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Provider {} failed ({})",
+            provider.name, output.status
+        ));
+    }
+    let response: ProviderResponse = serde_json::from_slice(&output.stdout)
+        .map_err(|err| format!("Provider {} gave an invalid response: {err}", provider.name))?;
+    Ok(response
+        .entries
+        .into_iter()
+        .map(|provided| {
+            let mut entry = Entry::from(InsertRequest {
+                name: provided.name,
+                link: provided.link.as_str().into(),
+                metadata: provided.metadata,
+                due: None,
+                section: None,
+                tags: Vec::new(),
+                temp: false,
+            });
+            entry.provider = Some(provider.name.clone());
+            entry
+        })
+        .collect())
+}
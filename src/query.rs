@@ -0,0 +1,106 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use crate::config::Entry;
+use crate::fields::FieldValue;
+use crate::project::Project;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    Tag(String),
+    Section(String),
+    Id(u64),
+    /// a custom field compared to a value, the ordering is what the field's value must be relative to it
+    Field(String, Ordering, FieldValue),
+    /// part of the entry's name
+    Name(String),
+}
+
+/// A filter over entries, made of space separated terms that must all match: `tag:<tag>`, `section:<section>`,
+/// `id:<id>`, custom field comparisons like `isbn=<value>`, `pages><value>`, or `read<<value>`, or any other text to
+/// look for in the entry's name. Matching ignores case.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Self {
+        let terms = text
+            .split_whitespace()
+            .map(|term| {
+                let lower = term.to_lowercase();
+                if let Some(tag) = lower.strip_prefix("tag:") {
+                    Term::Tag(tag.to_string())
+                } else if let Some(section) = lower.strip_prefix("section:") {
+                    Term::Section(section.to_string())
+                } else if let Some(id) = lower.strip_prefix("id:").and_then(|id| id.parse().ok()) {
+                    Term::Id(id)
+                } else if let Some((field, ordering, value)) = parse_comparison(&lower) {
+                    Term::Field(field, ordering, value)
+                } else {
+                    Term::Name(lower)
+                }
+            })
+            .collect();
+        Self { terms }
+    }
+
+    pub fn matches(&self, entry: &Entry) -> bool {
+        self.terms.iter().all(|term| match term {
+            Term::Tag(tag) => entry.tags.iter().any(|other| other.to_lowercase() == *tag),
+            Term::Section(section) => entry
+                .section
+                .as_ref()
+                .is_some_and(|other| other.to_lowercase() == *section),
+            Term::Id(id) => entry.id == *id,
+            Term::Field(field, ordering, value) => entry
+                .field(field)
+                .and_then(|actual| actual.compare(value))
+                .is_some_and(|actual| actual == *ordering),
+            Term::Name(part) => entry.name.to_lowercase().contains(part),
+        })
+    }
+}
+
+/// splits `<field>=<value>`, `<field>><value>`, or `<field><<value>` into its parts
+fn parse_comparison(term: &str) -> Option<(String, Ordering, FieldValue)> {
+    let idx = term.find(['=', '<', '>'])?;
+    let (field, rest) = term.split_at(idx);
+    if field.is_empty() {
+        return None;
+    }
+    let ordering = match &rest[..1] {
+        "=" => Ordering::Equal,
+        "<" => Ordering::Less,
+        _ => Ordering::Greater,
+    };
+    Some((field.to_string(), ordering, FieldValue::parse(&rest[1..])))
+}
+
+/// the query an alias stands for, or the text itself if it isn't an alias
+pub fn expand<'a>(aliases: &'a BTreeMap<String, String>, text: &'a str) -> &'a str {
+    aliases.get(text.trim()).map_or(text, String::as_str)
+}
+
+/// opening more entries than this at once asks first
+pub const CONFIRM_OPEN_ABOVE: usize = 5;
+
+/// the main entries that match, followed with `everywhere` by the snoozed and archived entries that match
+pub fn matching<'a>(project: &'a Project, query: &Query, everywhere: bool) -> Vec<&'a Entry> {
+    let mut ret = project
+        .entries
+        .iter()
+        .filter(|entry| query.matches(entry))
+        .collect::<Vec<_>>();
+    if everywhere {
+        ret.extend(
+            project
+                .snoozed
+                .iter()
+                .chain(project.archive.iter())
+                .filter(|entry| query.matches(entry)),
+        );
+    }
+    ret
+}
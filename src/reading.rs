@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use scraper::{ElementRef, Html, Selector};
+
+use crate::net::Net;
+
+const READING_TIMEOUT: Duration = Duration::from_millis(5000);
+const READING_UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/117.0.0.0 Safari/537.36";
+const WORDS_PER_MINUTE: usize = 230;
+
+/// Estimates the minutes needed to read the page at `url`, by the word count of its paragraphs. Pages without
+/// readable text (e.g. videos or files) have no estimate.
+pub fn estimate_minutes(url: &str, net: &Net) -> Option<u32> {
+    let client = net.client(READING_TIMEOUT, READING_UA).ok()?;
+    let body = net
+        .get(&client, url)
+        .ok()?
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.text())
+        .ok()?;
+    let words = readable_words(&Html::parse_document(&body));
+    (words > 0).then(|| words.div_ceil(WORDS_PER_MINUTE) as u32)
+}
+
+fn readable_words(document: &Html) -> usize {
+    readable_blocks(document)
+        .iter()
+        .flat_map(|block| block.text())
+        .map(|text| text.split_whitespace().count())
+        .sum()
+}
+
+/// The headings and paragraphs of the page's article, or of the whole page if it has no article, in document order.
+pub fn readable_blocks(document: &Html) -> Vec<ElementRef<'_>> {
+    ["article", "main", "body"]
+        .into_iter()
+        .map(|root| {
+            let selector = ["h1", "h2", "h3", "p"]
+                .map(|tag| format!("{root} {tag}"))
+                .join(", ");
+            let selector = Selector::parse(&selector).unwrap();
+            document
+                .select(&selector)
+                .filter(|block| block.text().any(|text| !text.trim().is_empty()))
+                .collect::<Vec<_>>()
+        })
+        .find(|blocks| blocks.iter().any(|block| block.value().name() == "p"))
+        .unwrap_or_default()
+}
@@ -0,0 +1,116 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use reqwest::Url;
+
+use crate::link::Link;
+
+/// how many titles are fetched at once
+pub const REFRESH_WORKERS: usize = 4;
+// the least time between two requests to the same host, so that a list of links to one site isn't a burst
+const HOST_INTERVAL: Duration = Duration::from_millis(1000);
+// how often a worker waiting for its host's turn checks whether the refresh was cancelled
+const CANCEL_POLL: Duration = Duration::from_millis(100);
+const BAR_WIDTH: usize = 20;
+
+/// A refresh of the names of many entries, shared by the workers that fetch them. Each worker takes the next entry
+/// whose host may be requested again.
+pub struct TitleRefresh {
+    queue: Mutex<VecDeque<(u64, Link)>>,
+    // when each host may be requested next
+    hosts: Mutex<HashMap<String, Instant>>,
+    total: usize,
+    done: AtomicUsize,
+    workers: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl TitleRefresh {
+    pub fn new(links: Vec<(u64, Link)>) -> Self {
+        Self {
+            total: links.len(),
+            queue: Mutex::new(links.into()),
+            hosts: Mutex::new(HashMap::new()),
+            done: AtomicUsize::new(0),
+            workers: AtomicUsize::new(0),
+            cancelled: AtomicBool::new(false),
+        }
+    }
+
+    /// the entries still queued are dropped, those being fetched still finish
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// called by a worker before it starts taking entries
+    pub fn start_worker(&self) {
+        self.workers.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// called by a worker once `next` has nothing more for it
+    pub fn stop_worker(&self) {
+        self.workers.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// whether every worker has stopped
+    pub fn is_finished(&self) -> bool {
+        self.workers.load(Ordering::SeqCst) == 0
+    }
+
+    /// The next entry to fetch, once its host may be requested, or None if there are none left or the refresh was
+    /// cancelled.
+    pub fn next(&self) -> Option<(u64, Link)> {
+        if self.is_cancelled() {
+            return None;
+        }
+        let (id, link) = self.queue.lock().unwrap().pop_front()?;
+        let host = Url::parse(link.feed_url().unwrap_or(link.as_str()))
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+            .unwrap_or_default();
+        let turn = {
+            let mut hosts = self.hosts.lock().unwrap();
+            let now = Instant::now();
+            let turn = hosts
+                .get(&host)
+                .copied()
+                .filter(|at| *at > now)
+                .unwrap_or(now);
+            hosts.insert(host, turn + HOST_INTERVAL);
+            turn
+        };
+        while Instant::now() < turn {
+            if self.is_cancelled() {
+                return None;
+            }
+            thread::sleep(CANCEL_POLL.min(turn.saturating_duration_since(Instant::now())));
+        }
+        Some((id, link))
+    }
+
+    /// called by a worker once it fetched the name of the entry it took
+    pub fn finish_one(&self) {
+        self.done.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn describe(&self) -> String {
+        let done = self.done.load(Ordering::SeqCst).min(self.total);
+        if self.is_cancelled() {
+            return format!("Cancelling title refresh... {done}/{}", self.total);
+        }
+        let filled = BAR_WIDTH * done / self.total.max(1);
+        format!(
+            "Refreshing titles [{}{}] {done}/{}, <Esc> to cancel",
+            "#".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled),
+            self.total
+        )
+    }
+}
@@ -0,0 +1,53 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// the registry lives with the user's config rather than in any project, as it lists all of them
+const REGISTRY_FILE: &str = "projects.json";
+
+/// the registry of the projects that were opened on this machine, under the user's config directory
+fn path() -> Option<PathBuf> {
+    let dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(dir.join("flist").join(REGISTRY_FILE))
+}
+
+fn read(path: &Path) -> Vec<PathBuf> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|registry| serde_json::from_str(&registry).ok())
+        .unwrap_or_default()
+}
+
+/// Adds the project at `root` to the registry, if it isn't listed already.
+pub fn register(root: &Path) -> Result<(), String> {
+    let Some(path) = path() else {
+        return Err("No config directory to keep the registry of projects in".to_string());
+    };
+    let root = root
+        .canonicalize()
+        .map_err(|err| format!("Failed to register {}: {err}", root.display()))?;
+    let mut projects = read(&path);
+    if projects.contains(&root) {
+        return Ok(());
+    }
+    projects.push(root);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|err| format!("Failed to create {}: {err}", dir.display()))?;
+    }
+    let registry = serde_json::to_string_pretty(&projects).expect("Failed to serialize registry");
+    fs::write(&path, registry).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
+
+/// The registered projects that still exist, in the order they were first opened.
+pub fn projects() -> Vec<PathBuf> {
+    path()
+        .map(|path| read(&path))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|root| root.join("flist.toml").exists())
+        .collect()
+}
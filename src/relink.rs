@@ -0,0 +1,158 @@
+use std::ffi::OsStr;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::link::Link;
+use crate::project::Project;
+use crate::target;
+
+// bounds the search, so a root like the home directory doesn't take forever
+const MAX_SEARCH_DEPTH: usize = 8;
+
+/// whether the link points to a file or directory that no longer exists
+pub fn is_broken(link: &Link) -> bool {
+    match link {
+        Link::File(path) | Link::Directory(path) => !Path::new(path).exists(),
+        Link::Url(_) | Link::Feed(_) => false,
+    }
+}
+
+/// a file or directory that might be the missing target of an entry
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub path: String,
+    /// whether the file has the content the target had, as far as its hash tells
+    pub same_content: bool,
+}
+
+/// Files and directories under the roots with the same name as the missing target, those with the content of
+/// `hash` first. Without configured roots, the closest existing ancestor above the target's directory is searched, so
+/// moves into sibling folders are found.
+pub fn candidates(missing: &str, roots: &[String], hash: Option<u64>) -> Vec<Candidate> {
+    let missing = Path::new(missing);
+    let Some(name) = missing.file_name() else {
+        return Vec::new();
+    };
+    let roots = if roots.is_empty() {
+        missing
+            .ancestors()
+            .skip(2)
+            .find(|ancestor| ancestor.is_dir())
+            .map(|ancestor| vec![ancestor.to_path_buf()])
+            .unwrap_or_default()
+    } else {
+        roots.iter().map(PathBuf::from).collect()
+    };
+    let mut found = Vec::new();
+    for root in roots {
+        search(&root, name, MAX_SEARCH_DEPTH, &mut found);
+    }
+    found.sort();
+    found.dedup();
+    let mut ret = found
+        .into_iter()
+        .map(|path| Candidate {
+            same_content: hash.is_some() && target::content_hash(&Link::File(path.clone())) == hash,
+            path,
+        })
+        .collect::<Vec<_>>();
+    ret.sort_by_key(|candidate| !candidate.same_content);
+    ret
+}
+
+fn search(dir: &Path, name: &OsStr, depth: usize, found: &mut Vec<String>) {
+    let Ok(children) = fs::read_dir(dir) else {
+        return;
+    };
+    for child in children.flatten() {
+        let path = child.path();
+        if child.file_name() == name {
+            found.push(path.to_string_lossy().into_owned());
+        }
+        let is_hidden = child.file_name().to_string_lossy().starts_with('.');
+        // symlinks are not followed, to avoid cycles
+        let is_dir = child.file_type().is_ok_and(|file_type| file_type.is_dir());
+        if is_dir && !is_hidden && depth > 0 {
+            search(&path, name, depth - 1, found);
+        }
+    }
+}
+
+/// Relinks every broken entry that has exactly one candidate with the content the target had, returns a report of
+/// what was (or would be) done. Entries whose content isn't known, or that have no such candidate or several, are
+/// reported with their candidates to relink from the view.
+pub fn relink_all(project: &mut Project, dry_run: bool) -> String {
+    let roots = project.config.relink_roots.clone();
+    let identity = project.identity.clone();
+    let mut report = String::new();
+    let mut relinked = 0;
+    for entry in project
+        .entries
+        .iter_mut()
+        .chain(project.archive.iter_mut())
+        .chain(project.snoozed.iter_mut())
+    {
+        if !is_broken(&entry.link) {
+            continue;
+        }
+        let found = candidates(entry.link.as_str(), &roots, entry.target_hash);
+        let same = found
+            .iter()
+            .filter(|candidate| candidate.same_content)
+            .collect::<Vec<_>>();
+        let paths = found
+            .iter()
+            .map(|candidate| candidate.path.as_str())
+            .collect::<Vec<_>>();
+        match (same.as_slice(), paths.as_slice()) {
+            (_, []) => writeln!(report, "{}: {} not found", entry.name, entry.link.as_str()),
+            ([candidate], _) => {
+                let path = &candidate.path;
+                relinked += 1;
+                let line = writeln!(report, "{}: {} -> {path}", entry.name, entry.link.as_str());
+                entry.link = Link::from(path.as_str());
+                entry.target_hash = target::content_hash(&entry.link);
+                entry.revision += 1;
+                if identity.is_some() {
+                    entry.modified_by = identity.clone();
+                }
+                line
+            }
+            ([], _) if entry.target_hash.is_some() => writeln!(
+                report,
+                "{}: {} not found, files of its name have other content:\n  {}",
+                entry.name,
+                entry.link.as_str(),
+                paths.join("\n  ")
+            ),
+            // without the target's content, even a single file of its name might be another file
+            ([], _) => writeln!(
+                report,
+                "{}: {} is ambiguous, its content is unknown, candidates:\n  {}",
+                entry.name,
+                entry.link.as_str(),
+                paths.join("\n  ")
+            ),
+            _ => writeln!(
+                report,
+                "{}: {} is ambiguous, several files have its content:\n  {}",
+                entry.name,
+                entry.link.as_str(),
+                same.iter()
+                    .map(|candidate| candidate.path.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n  ")
+            ),
+        }
+        .unwrap();
+    }
+    if report.is_empty() {
+        report.push_str("No broken links\n");
+    } else if dry_run {
+        writeln!(report, "Would relink {relinked} entries").unwrap();
+    } else {
+        writeln!(report, "Relinked {relinked} entries").unwrap();
+    }
+    report
+}
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use notify_rust::Notification;
+
+use crate::config::{Entry, NotificationConfig};
+
+/// Desktop notifications for entries that became due or resurfaced from a snooze.
+#[derive(Debug, Default)]
+pub struct Reminders {
+    // notifications waiting for quiet hours to end
+    queued: Vec<Reminder>,
+}
+
+#[derive(Debug)]
+struct Reminder {
+    summary: &'static str,
+    body: String,
+}
+
+impl Reminders {
+    pub fn entry_resurfaced(&mut self, entry: &Entry) {
+        self.queued.push(Reminder {
+            summary: "Snoozed entry is back",
+            body: entry.name.clone(),
+        });
+    }
+
+    pub fn entry_due(&mut self, entry: &Entry) {
+        self.queued.push(Reminder {
+            summary: "Entry is due",
+            body: entry.name.clone(),
+        });
+    }
+
+    pub fn flush(&mut self, config: &NotificationConfig, now: DateTime<Utc>) {
+        if !config.enabled {
+            self.queued.clear();
+            return;
+        }
+        if self.queued.is_empty() || config.is_quiet(now) {
+            return;
+        }
+        let reminders = std::mem::take(&mut self.queued);
+        // showing a notification might block on the notification daemon
+        std::thread::spawn(move || {
+            for reminder in reminders {
+                let _ = Notification::new()
+                    .appname("flist")
+                    .summary(reminder.summary)
+                    .body(&reminder.body)
+                    .show();
+            }
+        });
+    }
+}
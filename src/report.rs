@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Entry;
+use crate::link::Link;
+use crate::project::Project;
+
+#[derive(Debug, Clone, Copy, Default, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportGroup {
+    #[default]
+    Section,
+    /// entries with several tags are listed under each of them
+    Tag,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    Text,
+    Markdown,
+    /// a standalone page with clickable links
+    Html,
+}
+
+/// A readable report of the main and snoozed entries, grouped by section or tag, for pasting into status updates.
+/// Entries outside any group come last, temporary entries are left out.
+pub fn render(
+    project: &Project,
+    now: DateTime<Utc>,
+    group: ReportGroup,
+    format: ReportFormat,
+) -> String {
+    let mut groups: BTreeMap<&str, Vec<&Entry>> = BTreeMap::new();
+    let mut ungrouped = Vec::new();
+    for entry in project
+        .entries
+        .iter()
+        .chain(project.snoozed.iter())
+        .filter(|entry| !entry.ephemeral)
+    {
+        let names = match group {
+            ReportGroup::Section => entry.section.iter().map(String::as_str).collect(),
+            ReportGroup::Tag => entry.tags.iter().map(String::as_str).collect::<Vec<_>>(),
+        };
+        if names.is_empty() {
+            ungrouped.push(entry);
+        }
+        for name in names {
+            groups.entry(name).or_default().push(entry);
+        }
+    }
+    let other = match group {
+        ReportGroup::Section => "No section",
+        ReportGroup::Tag => "Untagged",
+    };
+
+    let title = format!(
+        "Flist report, {}",
+        now.with_timezone(&Local).format("%Y-%m-%d")
+    );
+    let mut ret = match format {
+        ReportFormat::Text => format!("{title}\n{}\n", "=".repeat(title.chars().count())),
+        ReportFormat::Markdown => format!("# {title}\n"),
+        ReportFormat::Html => format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        ),
+    };
+    let groups = groups
+        .into_iter()
+        .chain((!ungrouped.is_empty()).then_some((other, ungrouped)));
+    for (name, entries) in groups {
+        match format {
+            ReportFormat::Text => writeln!(ret, "\n{name} ({})", entries.len()),
+            ReportFormat::Markdown => writeln!(ret, "\n## {name} ({})\n", entries.len()),
+            ReportFormat::Html => {
+                writeln!(
+                    ret,
+                    "<h2>{} ({})</h2>\n<ul>",
+                    escape_html(name),
+                    entries.len()
+                )
+            }
+        }
+        .unwrap();
+        for entry in entries {
+            write_entry(&mut ret, entry, format);
+        }
+        if let ReportFormat::Html = format {
+            ret.push_str("</ul>\n");
+        }
+    }
+    if let ReportFormat::Html = format {
+        ret.push_str("</body>\n</html>\n");
+    }
+    ret
+}
+
+/// Writes the html report to index.html in `dir`, creating it if needed, returns the path of the page.
+pub fn publish(project: &Project, dir: &Path, group: ReportGroup) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join("index.html");
+    fs::write(
+        &path,
+        render(project, Utc::now(), group, ReportFormat::Html),
+    )?;
+    Ok(path)
+}
+
+/// escapes text for html, both for an element's content and for a quoted attribute
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn write_entry(out: &mut String, entry: &Entry, format: ReportFormat) {
+    let mut dates = vec![format!(
+        "added {}",
+        entry.time_added.with_timezone(&Local).format("%Y-%m-%d")
+    )];
+    if let Some(due) = entry.due {
+        dates.push(format!(
+            "due {}",
+            due.with_timezone(&Local).format("%Y-%m-%d")
+        ));
+    }
+    if let Some(until) = entry.snoozed_until {
+        dates.push(format!(
+            "snoozed until {}",
+            until.with_timezone(&Local).format("%Y-%m-%d")
+        ));
+    }
+    let dates = dates.join(", ");
+    match (format, &entry.link) {
+        (ReportFormat::Text, link) => {
+            writeln!(out, "  - {} ({dates})", entry.name).unwrap();
+            writeln!(out, "    {}", link.as_str()).unwrap();
+        }
+        (ReportFormat::Markdown, Link::Url(url)) => {
+            writeln!(out, "- [{}]({url}) _({dates})_", entry.name).unwrap()
+        }
+        (ReportFormat::Markdown, link) => {
+            writeln!(out, "- {} `{}` _({dates})_", entry.name, link.as_str()).unwrap()
+        }
+        (ReportFormat::Html, Link::Url(url)) => write!(
+            out,
+            "<li><a href=\"{}\">{}</a> <small>({dates})</small>",
+            escape_html(url),
+            escape_html(&entry.name)
+        )
+        .unwrap(),
+        (ReportFormat::Html, link) => write!(
+            out,
+            "<li>{} <code>{}</code> <small>({dates})</small>",
+            escape_html(&entry.name),
+            escape_html(link.as_str())
+        )
+        .unwrap(),
+    }
+    if let ReportFormat::Html = format {
+        if !entry.metadata.is_empty() {
+            out.push_str("\n<ul>\n");
+            for note in &entry.metadata {
+                writeln!(out, "<li>{}</li>", escape_html(note)).unwrap();
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</li>\n");
+        return;
+    }
+    let indent = match format {
+        ReportFormat::Text => "    ",
+        ReportFormat::Markdown => "  ",
+        ReportFormat::Html => unreachable!("html notes are written as a nested list"),
+    };
+    for note in &entry.metadata {
+        writeln!(out, "{indent}- {note}").unwrap();
+    }
+}
@@ -1,11 +1,146 @@
+use std::fs;
+use std::io::{self, Read};
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::{args::AddArgs, link::Link};
+use crate::{
+    args::{AddArgs, AddIssueArgs, BookmarksArgs, ImportArgs, ImportSource},
+    bookmarks::{self, FolderMapping},
+    errors::FlistError,
+    issues,
+    link::Link,
+    net::Net,
+    tls::Client,
+    when::parse_when,
+};
+
+/// the version of the remote protocol, raised whenever a request type is added or a request gains a field
+pub const PROTOCOL_VERSION: u32 = 6;
+/// the first protocol whose renames and link edits check their `revision`, older flists ignore it
+const REVISION_PROTOCOL: u32 = 6;
+/// the request types this version of flist accepts, as listed in the answer to hello
+pub const REQUEST_TYPES: &[&str] = &[
+    "hello",
+    "insert",
+    "insert-many",
+    "archive",
+    "remove",
+    "rename",
+    "edit-link",
+];
 
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 pub enum RemoteRequest {
     Insert(InsertRequest),
+    Hello(HelloRequest),
+    /// many entries over a single connection, added with a single save
+    InsertMany(Vec<InsertRequest>),
+    Archive(ArchiveRequest),
+    Remove(RemoveRequest),
+    Rename(RenameRequest),
+    EditLink(EditLinkRequest),
+}
+
+/// archives the main or snoozed entry with the name, or at the index of the main list
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ArchiveRequest {
+    pub archive: String,
+}
+
+/// deletes the entry with the name, or at the index of the main list, without archiving it
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RemoveRequest {
+    pub remove: String,
+}
+
+/// Renames the entry with the name, or at the index of the main list. With `expected`, the rename is refused if the
+/// entry is no longer named so, so that an edit made since the client read the name isn't overwritten.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RenameRequest {
+    pub rename: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// the revision of the entry the client read, the rename is refused if the entry changed in any way since
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<u64>,
+}
+
+impl RenameRequest {
+    /// the protocol the running flist must be on to carry out the request as meant
+    pub fn protocol(&self) -> u32 {
+        self.revision.map_or(0, |_| REVISION_PROTOCOL)
+    }
+}
+
+/// Points the entry with the name, or at the index of the main list, at another link. With `expected`, the change is
+/// refused if the entry no longer links there.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EditLinkRequest {
+    #[serde(rename = "edit-link")]
+    pub edit_link: String,
+    pub link: Link,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<Link>,
+    /// the revision of the entry the client read, the change is refused if the entry changed in any way since
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<u64>,
+}
+
+impl EditLinkRequest {
+    /// the protocol the running flist must be on to carry out the request as meant
+    pub fn protocol(&self) -> u32 {
+        self.revision.map_or(0, |_| REVISION_PROTOCOL)
+    }
+}
+
+/// asks the running flist what it is and what it accepts, the only request that is answered
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HelloRequest {
+    /// the protocol version of the client
+    pub hello: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HelloResponse {
+    pub protocol: u32,
+    /// the name of the project's directory
+    pub project: String,
+    pub requests: Vec<String>,
+}
+
+impl HelloResponse {
+    pub fn new(project: String) -> Self {
+        Self {
+            protocol: PROTOCOL_VERSION,
+            project,
+            requests: REQUEST_TYPES
+                .iter()
+                .map(|request| request.to_string())
+                .collect(),
+        }
+    }
+
+    pub fn accepts(&self, request: &str) -> bool {
+        self.requests.iter().any(|accepted| accepted == request)
+    }
+}
+
+/// Greets the running flist over `stream`, None if it is from before hello and so doesn't answer.
+pub fn hello(mut stream: Client) -> io::Result<Option<HelloResponse>> {
+    serde_json::to_writer(
+        &mut stream,
+        &HelloRequest {
+            hello: PROTOCOL_VERSION,
+        },
+    )?;
+    // the listener reads requests to the end
+    stream.finish()?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    Ok(serde_json::from_str(&response).ok())
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -13,14 +148,121 @@ pub struct InsertRequest {
     pub name: String,
     pub link: Link,
     pub metadata: Vec<String>,
+    #[serde(default)]
+    pub due: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub section: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// whether the entry only lives until the running view is closed
+    #[serde(default)]
+    pub temp: bool,
 }
 
-impl From<AddArgs> for InsertRequest {
-    fn from(args: AddArgs) -> Self {
-        Self {
+impl TryFrom<AddArgs> for InsertRequest {
+    type Error = FlistError;
+
+    fn try_from(args: AddArgs) -> Result<Self, FlistError> {
+        let due = args
+            .due
+            .map(|due| {
+                parse_when(&due, Utc::now())
+                    .ok_or_else(|| FlistError::Command(format!("Invalid due date {due}")))
+            })
+            .transpose()?;
+        Ok(Self {
             name: args.name,
             link: args.link.as_str().into(),
             metadata: args.metadata,
+            due,
+            section: args.section,
+            tags: args.tags,
+            temp: args.temp,
+        })
+    }
+}
+
+/// Each line of the file is a link, named after itself, or a name and a link separated by a tab.
+impl TryFrom<ImportArgs> for Vec<InsertRequest> {
+    type Error = FlistError;
+
+    fn try_from(args: ImportArgs) -> Result<Self, FlistError> {
+        if let Some(ImportSource::Bookmarks(args)) = args.source {
+            return Self::try_from(args);
         }
+        let text = match &args.file {
+            Some(path) if path.as_os_str() != "-" => {
+                fs::read_to_string(path).map_err(FlistError::io("read", path))?
+            }
+            _ => io::read_to_string(io::stdin())
+                .map_err(|err| FlistError::Command(format!("Failed to read stdin: {err}")))?,
+        };
+        Ok(text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let (name, link) = line.split_once('\t').unwrap_or((line, line));
+                InsertRequest {
+                    name: name.trim().to_string(),
+                    link: link.trim().into(),
+                    metadata: Vec::new(),
+                    due: None,
+                    section: args.section.clone(),
+                    tags: args.tags.clone(),
+                    temp: false,
+                }
+            })
+            .collect())
+    }
+}
+
+impl TryFrom<BookmarksArgs> for Vec<InsertRequest> {
+    type Error = FlistError;
+
+    fn try_from(args: BookmarksArgs) -> Result<Self, FlistError> {
+        let html = fs::read_to_string(&args.file).map_err(FlistError::io("read", &args.file))?;
+        Ok(bookmarks::parse(&html)
+            .into_iter()
+            .map(|bookmark| {
+                let mut tags = args.tags.clone();
+                let mut metadata = Vec::new();
+                match args.folders {
+                    FolderMapping::Tags => tags.extend(bookmark.folders),
+                    FolderMapping::Metadata if !bookmark.folders.is_empty() => {
+                        metadata.push(format!("folder: {}", bookmark.folders.join(" / ")))
+                    }
+                    FolderMapping::Metadata | FolderMapping::None => {}
+                }
+                InsertRequest {
+                    name: bookmark.name,
+                    link: bookmark.url.as_str().into(),
+                    metadata,
+                    due: None,
+                    section: args.section.clone(),
+                    tags,
+                    temp: false,
+                }
+            })
+            .collect())
+    }
+}
+
+impl InsertRequest {
+    /// the issue at the url of `args`, named and tagged through its site's api
+    pub fn issue(args: AddIssueArgs, net: &Net) -> Result<Self, FlistError> {
+        let issue = issues::parse(&args.url).ok_or_else(|| {
+            FlistError::Command(format!("{} is not a GitHub or GitLab issue URL", args.url))
+        })?;
+        let info = issues::fetch(&issue, net).map_err(FlistError::Command)?;
+        Ok(Self {
+            name: info.name,
+            link: Link::Url(args.url),
+            metadata: Vec::new(),
+            due: None,
+            section: args.section,
+            tags: info.tags,
+            temp: false,
+        })
     }
 }
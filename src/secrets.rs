@@ -0,0 +1,50 @@
+use keyring::Entry;
+use serde::{Deserialize, Serialize};
+
+// the service flist's secrets are stored under in the keyring, each under its own name
+const SERVICE: &str = "flist";
+
+/// A value of `flist.toml` that can be kept in the system's keyring instead, written as `{ secret = "<name>" }` for the
+/// secret stored with `flist secret set <name>`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Secret {
+    Plain(String),
+    Keyring { secret: String },
+}
+
+impl Secret {
+    /// the value, read from the keyring if it is kept there
+    pub fn resolve(&self) -> Result<String, String> {
+        match self {
+            Self::Plain(value) => Ok(value.clone()),
+            Self::Keyring { secret } => get(secret),
+        }
+    }
+}
+
+fn entry(name: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, name).map_err(|err| format!("Failed to open the keyring for {name}: {err}"))
+}
+
+pub fn get(name: &str) -> Result<String, String> {
+    entry(name)?.get_password().map_err(|err| match err {
+        keyring::Error::NoEntry => {
+            format!("No secret {name} in the keyring, store it with `flist secret set {name}`")
+        }
+        err => format!("Failed to read secret {name} from the keyring: {err}"),
+    })
+}
+
+pub fn set(name: &str, value: &str) -> Result<(), String> {
+    entry(name)?
+        .set_password(value)
+        .map_err(|err| format!("Failed to store secret {name} in the keyring: {err}"))
+}
+
+pub fn delete(name: &str) -> Result<(), String> {
+    entry(name)?.delete_password().map_err(|err| match err {
+        keyring::Error::NoEntry => format!("No secret {name} in the keyring"),
+        err => format!("Failed to delete secret {name} from the keyring: {err}"),
+    })
+}
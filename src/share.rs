@@ -0,0 +1,45 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::Stdio;
+
+use crate::config::Entry;
+use crate::shell;
+
+/// The entry as it is shared: its name, its link, and its notes, a line each.
+pub fn text(entry: &Entry) -> String {
+    let mut ret = format!("{}\n{}\n", entry.name, entry.link.as_str());
+    for note in &entry.metadata {
+        ret.push_str(note);
+        ret.push('\n');
+    }
+    ret
+}
+
+/// Pipes the entry into the share command, which also gets its name and link in the same variables as hooks, for
+/// commands that take them as arguments, e.g. a `mailto:` url.
+pub fn share(command: &str, project_root: &Path, entry: &Entry) -> Result<(), String> {
+    let mut child = shell::command(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .current_dir(project_root)
+        .env("FLIST_PROJECT", project_root)
+        .env("FLIST_ENTRY_NAME", &entry.name)
+        .env("FLIST_ENTRY_LINK", entry.link.as_str())
+        .env("FLIST_ENTRY_METADATA", entry.metadata.join("\n"))
+        .spawn()
+        .map_err(|err| format!("Failed to run share command: {err}"))?;
+    // the command may not read its input, e.g. if it only uses the variables
+    let _ = child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(text(entry).as_bytes());
+    let status = child
+        .wait()
+        .map_err(|err| format!("Failed to wait for share command: {err}"))?;
+    if !status.success() {
+        return Err(format!("Share command failed with {status}"));
+    }
+    Ok(())
+}
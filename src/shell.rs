@@ -0,0 +1,14 @@
+use std::process::Command;
+
+/// A command that runs `line` through the platform's shell.
+pub fn command(line: &str) -> Command {
+    if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(line);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(line);
+        command
+    }
+}
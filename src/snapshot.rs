@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use scraper::{Html, Selector};
+
+use crate::download::free_path;
+use crate::net::{Net, BROWSER_UA};
+use crate::reading;
+use crate::report::escape_html;
+
+pub const SNAPSHOT_DIR: &str = "snapshots";
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_millis(10000);
+
+/// Downloads the page at `url` and saves its headings and paragraphs as a plain html file in the project's snapshot
+/// folder, named after the page's title. Returns the path of the snapshot relative to the project, so that it moves
+/// with the project.
+pub fn take(project_root: &Path, url: &str, net: &Net) -> Result<String, String> {
+    let client = net.client(SNAPSHOT_TIMEOUT, BROWSER_UA)?;
+    let body = net
+        .get(&client, url)?
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.text())
+        .map_err(|err| format!("Failed to fetch {url}: {err}"))?;
+    let document = Html::parse_document(&body);
+    let blocks = reading::readable_blocks(&document);
+    if blocks.is_empty() {
+        return Err(format!("No readable content at {url}"));
+    }
+    let title = document
+        .select(&Selector::parse("title").unwrap())
+        .next()
+        .map(|title| title.text().collect::<String>())
+        .unwrap_or_else(|| url.to_string());
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n</head>\n<body>\n<p><a href=\"{1}\">{1}</a></p>\n",
+        escape_html(title.trim()),
+        escape_html(url)
+    );
+    for block in blocks {
+        let tag = block.value().name();
+        let text = block.text().collect::<String>();
+        html.push_str(&format!(
+            "<{tag}>{}</{tag}>\n",
+            escape_html(&text.split_whitespace().collect::<Vec<_>>().join(" "))
+        ));
+    }
+    html.push_str("</body>\n</html>\n");
+
+    let dir = project_root.join(SNAPSHOT_DIR);
+    fs::create_dir_all(&dir).map_err(|err| format!("Failed to create {}: {err}", dir.display()))?;
+    // numbered rather than overwritten, the snapshot of another entry or an earlier one of this entry may be taken
+    let path = free_path(&dir, &format!("{}.html", file_stem(title.trim())));
+    fs::write(&path, html).map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    let file = path.file_name().unwrap().to_string_lossy();
+    Ok(format!("{SNAPSHOT_DIR}/{file}"))
+}
+
+// the title, lowercased, with anything but letters and digits between its words replaced by dashes
+fn file_stem(title: &str) -> String {
+    let stem = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .take(60)
+        .collect::<String>();
+    if stem.is_empty() {
+        "snapshot".to_string()
+    } else {
+        stem.trim_end_matches('-').to_string()
+    }
+}
@@ -0,0 +1,97 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::link::Link;
+
+// files are told apart by their size and their start, reading them whole would take too long for large files
+const HASHED_BYTES: u64 = 1024 * 1024;
+
+/// the size and modification time of a file or directory entry's target
+#[derive(Debug, Clone, Copy)]
+pub struct TargetStat {
+    /// in bytes, directories count the total size of their contents
+    pub size: u64,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// the path of the link's target, for links that point to the filesystem
+fn local_path(link: &Link) -> Option<&Path> {
+    match link {
+        Link::File(path) | Link::Directory(path) => Some(Path::new(path)),
+        Link::Url(_) | Link::Feed(_) => None,
+    }
+}
+
+/// The modification time of the link's target, cheap enough to call for every entry.
+pub fn modified(link: &Link) -> Option<DateTime<Utc>> {
+    let modified = fs::metadata(local_path(link)?).ok()?.modified().ok()?;
+    Some(modified.into())
+}
+
+/// A hash of the content of a file entry's target, to tell the file apart from other files of the same name. Links
+/// to directories and missing files have none.
+pub fn content_hash(link: &Link) -> Option<u64> {
+    let Link::File(path) = link else {
+        return None;
+    };
+    let file = File::open(path).ok()?;
+    let size = file.metadata().ok()?.len();
+    let mut bytes = size.to_le_bytes().to_vec();
+    file.take(HASHED_BYTES).read_to_end(&mut bytes).ok()?;
+    Some(fnv1a(&bytes))
+}
+
+/// Walks directories to sum their size, so this might take a while.
+pub fn stat(link: &Link) -> Option<TargetStat> {
+    let path = local_path(link)?;
+    let metadata = fs::metadata(path).ok()?;
+    let size = if metadata.is_dir() {
+        dir_size(path)
+    } else {
+        metadata.len()
+    };
+    Some(TargetStat {
+        size,
+        modified: metadata.modified().ok().map(DateTime::from),
+    })
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(children) = fs::read_dir(path) else {
+        return 0;
+    };
+    children
+        .flatten()
+        .map(|child| match child.metadata() {
+            // symlinks are not followed, to avoid cycles
+            Ok(metadata) if metadata.is_dir() => dir_size(&child.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// A hash of `bytes` for saving, so it must not change between versions of rust as the std hasher may.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
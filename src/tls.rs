@@ -0,0 +1,190 @@
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpStream};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+use rustls::{
+    Certificate, ClientConfig, ClientConnection, PrivateKey, ServerConfig, ServerConnection,
+    ServerName, StreamOwned,
+};
+use sha2::{Digest, Sha256};
+
+// the name the certificate is made out to, clients check the fingerprint and not the name
+const SERVER_NAME: &str = "localhost";
+
+/// The certificate a running flist serves remote requests with, made for every run and never written to disk, and its
+/// fingerprint, which the lock records for clients to pin.
+pub struct Identity {
+    pub config: Arc<ServerConfig>,
+    pub fingerprint: String,
+}
+
+impl Identity {
+    pub fn generate() -> Result<Self, String> {
+        let cert = rcgen::generate_simple_self_signed(vec![SERVER_NAME.to_string()])
+            .map_err(|err| err.to_string())?;
+        let der = cert.serialize_der().map_err(|err| err.to_string())?;
+        let fingerprint = fingerprint(&der);
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(
+                vec![Certificate(der)],
+                PrivateKey(cert.serialize_private_key_der()),
+            )
+            .map_err(|err| err.to_string())?;
+        Ok(Self {
+            config: Arc::new(config),
+            fingerprint,
+        })
+    }
+}
+
+/// the hex sha256 of a der certificate
+fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// the server's end of a remote request, its handshake is done on the first read
+pub fn accept(config: Arc<ServerConfig>, stream: TcpStream) -> io::Result<Server> {
+    let conn = ServerConnection::new(config).map_err(io::Error::other)?;
+    Ok(Server(StreamOwned::new(conn, stream)))
+}
+
+pub struct Server(StreamOwned<ServerConnection, TcpStream>);
+
+impl Server {
+    /// whether the client went away before the handshake, as clients probing whether the project is open do
+    pub fn probed(&mut self) -> bool {
+        let Self(stream) = self;
+        matches!(
+            stream.conn.complete_io(&mut stream.sock),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof
+        )
+    }
+
+    /// tells the client the response is complete, it reads to the end
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.0.conn.send_close_notify();
+        self.0.flush()
+    }
+}
+
+impl Read for Server {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for Server {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// A connection to the listener of the running flist, with the fingerprint its lock pins, None for instances from
+/// before TLS, which are spoken to in plaintext.
+#[derive(Debug)]
+pub struct Peer {
+    pub stream: TcpStream,
+    pub fingerprint: Option<String>,
+}
+
+impl Peer {
+    pub fn addr(&self) -> io::Result<SocketAddr> {
+        self.stream.peer_addr()
+    }
+
+    /// the handshake, which fails unless the listener's certificate is the pinned one
+    pub fn secure(self) -> io::Result<Client> {
+        let Some(fingerprint) = self.fingerprint else {
+            return Ok(Client::Plain(self.stream));
+        };
+        let config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(Pinned(fingerprint)))
+            .with_no_client_auth();
+        let name = ServerName::try_from(SERVER_NAME).map_err(io::Error::other)?;
+        let conn = ClientConnection::new(Arc::new(config), name).map_err(io::Error::other)?;
+        let mut stream = StreamOwned::new(conn, self.stream);
+        stream.conn.complete_io(&mut stream.sock)?;
+        Ok(Client::Tls(Box::new(stream)))
+    }
+}
+
+pub enum Client {
+    Tls(Box<StreamOwned<ClientConnection, TcpStream>>),
+    Plain(TcpStream),
+}
+
+impl Client {
+    /// tells the listener the request is complete, it reads requests to the end
+    pub fn finish(&mut self) -> io::Result<()> {
+        match self {
+            Self::Tls(stream) => {
+                stream.conn.send_close_notify();
+                stream.flush()
+            }
+            Self::Plain(stream) => stream.shutdown(Shutdown::Write),
+        }
+    }
+}
+
+impl Read for Client {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Tls(stream) => stream.read(buf),
+            Self::Plain(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Client {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Tls(stream) => stream.write(buf),
+            Self::Plain(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Tls(stream) => stream.flush(),
+            Self::Plain(stream) => stream.flush(),
+        }
+    }
+}
+
+/// accepts only the certificate with the fingerprint in the lock, the certificate is self-signed so no chain is checked
+struct Pinned(String);
+
+impl ServerCertVerifier for Pinned {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if fingerprint(&end_entity.0) == self.0 {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(
+                "the certificate of the running flist is not the one in its lock".to_string(),
+            ))
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use chrono::Local;
+
+const LOG_FILE: &str = "flist.log";
+const TAIL_POLL_MS: u64 = 500;
+
+/// Appends lines to the project's log file when tracing is on, and does nothing otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct Tracer {
+    path: Option<PathBuf>,
+}
+
+impl Tracer {
+    pub fn new(root: &Path, enabled: bool) -> Self {
+        Self {
+            path: enabled.then(|| root.join(LOG_FILE)),
+        }
+    }
+
+    /// a failure to write the log is not worth failing a request over, so it is ignored
+    pub fn log(&self, line: &str) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) else {
+            return;
+        };
+        let _ = writeln!(
+            file,
+            "{} {line}",
+            Local::now().format("%Y-%m-%d %H:%M:%S%.3f")
+        );
+    }
+}
+
+/// Prints the last `lines` lines of the log, and then, if `follow` is set, the lines added to it until interrupted.
+pub fn tail(root: &Path, lines: usize, follow: bool) {
+    let path = root.join(LOG_FILE);
+    let text = fs::read_to_string(&path).unwrap_or_default();
+    let all = text.lines().collect::<Vec<_>>();
+    for line in &all[all.len().saturating_sub(lines)..] {
+        println!("{line}");
+    }
+    if !follow {
+        return;
+    }
+    let mut position = text.len() as u64;
+    loop {
+        thread::sleep(Duration::from_millis(TAIL_POLL_MS));
+        let Ok(mut file) = fs::File::open(&path) else {
+            continue;
+        };
+        let len = file.metadata().map_or(0, |metadata| metadata.len());
+        // the log was removed and started over
+        if len < position {
+            position = 0;
+        }
+        let mut added = String::new();
+        if file.seek(SeekFrom::Start(position)).is_ok() && file.read_to_string(&mut added).is_ok() {
+            print!("{added}");
+            position += added.len() as u64;
+        }
+    }
+}
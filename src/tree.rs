@@ -0,0 +1,212 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::config::Entry;
+use crate::link::Link;
+use crate::target;
+
+/// The order siblings are shown in. The main list is only ever saved in its manual order, the others are ways of
+/// viewing it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Sort {
+    /// as the entries were inserted and dragged
+    #[default]
+    Manual,
+    Newest,
+    Name,
+    /// urls, then feeds, directories, and files, each by name
+    LinkType,
+    /// by when the target was last modified, newest first, entries without a local target last
+    Modified,
+}
+
+impl Sort {
+    /// the order `s` switches to
+    pub fn next(self) -> Self {
+        match self {
+            Self::Manual => Self::Newest,
+            Self::Newest => Self::Name,
+            Self::Name => Self::LinkType,
+            Self::LinkType => Self::Modified,
+            Self::Modified => Self::Manual,
+        }
+    }
+
+    /// as in "sorted by ..."
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Manual => "manual order",
+            Self::Newest => "newest",
+            Self::Name => "name",
+            Self::LinkType => "link type",
+            Self::Modified => "modification time",
+        }
+    }
+
+    // pinned siblings go first, in the same order among themselves
+    fn arrange(self, entries: &[Entry], siblings: &mut [usize]) {
+        let name = |idx: &usize| entries[*idx].name.to_lowercase();
+        match self {
+            Self::Manual => {}
+            Self::Newest => siblings.sort_by_key(|idx| Reverse(entries[*idx].time_added)),
+            Self::Name => siblings.sort_by_key(name),
+            Self::LinkType => siblings.sort_by_key(|idx| {
+                let rank = match entries[*idx].link {
+                    Link::Url(_) => 0,
+                    Link::Feed(_) => 1,
+                    Link::Directory(_) => 2,
+                    Link::File(_) => 3,
+                };
+                (rank, name(idx))
+            }),
+            Self::Modified => {
+                siblings.sort_by_cached_key(|idx| Reverse(target::modified(&entries[*idx].link)))
+            }
+        }
+        siblings.sort_by_key(|idx| !entries[*idx].pinned);
+    }
+}
+
+/// a visible line of a nested entry list
+#[derive(Debug, Clone, Copy)]
+pub struct Row {
+    /// the index of the entry in the flat list
+    pub idx: usize,
+    pub depth: usize,
+    pub has_children: bool,
+}
+
+/// the index of each entry's parent in `entries`, entries whose parent is not in the list are top-level, as is the
+/// first entry of a cycle of parents
+fn parent_indices(entries: &[Entry]) -> Vec<Option<usize>> {
+    let by_id = entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.id != 0)
+        .map(|(idx, entry)| (entry.id, idx))
+        .collect::<HashMap<_, _>>();
+    let mut ret = entries
+        .iter()
+        .map(|entry| entry.parent.and_then(|parent| by_id.get(&parent).copied()))
+        .collect::<Vec<_>>();
+    for idx in cycle_roots(&ret) {
+        ret[idx] = None;
+    }
+    ret
+}
+
+/// The first index of each cycle in `parents`, i.e. of entries whose parents lead back to them.
+pub fn cycle_roots(parents: &[Option<usize>]) -> Vec<usize> {
+    // whether the index was reached from the index the current walk started at, or from an earlier one
+    let mut walked_from = vec![None; parents.len()];
+    let mut ret = Vec::new();
+    for start in 0..parents.len() {
+        let mut current = Some(start);
+        while let Some(idx) = current {
+            match walked_from[idx] {
+                None => walked_from[idx] = Some(start),
+                Some(from) if from == start => {
+                    let mut first = idx;
+                    let mut member = idx;
+                    while let Some(next) = parents[member].filter(|next| *next != idx) {
+                        first = first.min(next);
+                        member = next;
+                    }
+                    ret.push(first);
+                    break;
+                }
+                // an earlier walk went on from here, and already found any cycle ahead
+                Some(_) => break,
+            }
+            current = parents[idx];
+        }
+    }
+    ret
+}
+
+/// The visible rows of `entries`, in display order. Siblings are in `sort` order, the manual one keeping their relative
+/// order from the flat list, with the pinned ones first. The descendants of collapsed entries are omitted.
+pub fn rows(entries: &[Entry], sort: Sort) -> Vec<Row> {
+    let parents = parent_indices(entries);
+    let mut children: HashMap<Option<usize>, Vec<usize>> = HashMap::new();
+    for (idx, parent) in parents.iter().enumerate() {
+        children.entry(*parent).or_default().push(idx);
+    }
+    for siblings in children.values_mut() {
+        sort.arrange(entries, siblings);
+    }
+    let mut ret = Vec::with_capacity(entries.len());
+    let mut stack = children
+        .get(&None)
+        .map(|roots| roots.iter().rev().map(|idx| (*idx, 0)).collect::<Vec<_>>())
+        .unwrap_or_default();
+    while let Some((idx, depth)) = stack.pop() {
+        let kids = children.get(&Some(idx));
+        ret.push(Row {
+            idx,
+            depth,
+            has_children: kids.is_some(),
+        });
+        if let Some(kids) = kids {
+            if !entries[idx].collapsed {
+                stack.extend(kids.iter().rev().map(|kid| (*kid, depth + 1)));
+            }
+        }
+    }
+    ret
+}
+
+/// the index of the entry displayed before (or after) the entry at `idx`
+pub fn step(entries: &[Entry], idx: usize, forward: bool, sort: Sort) -> Option<usize> {
+    let rows = rows(entries, sort);
+    let pos = rows.iter().position(|row| row.idx == idx)?;
+    let new_pos = if forward {
+        pos + 1
+    } else {
+        pos.checked_sub(1)?
+    };
+    rows.get(new_pos).map(|row| row.idx)
+}
+
+pub fn first(entries: &[Entry], sort: Sort) -> Option<usize> {
+    rows(entries, sort).first().map(|row| row.idx)
+}
+
+pub fn last(entries: &[Entry], sort: Sort) -> Option<usize> {
+    rows(entries, sort).last().map(|row| row.idx)
+}
+
+pub fn parent(entries: &[Entry], idx: usize) -> Option<usize> {
+    parent_indices(entries)[idx]
+}
+
+pub fn first_child(entries: &[Entry], idx: usize) -> Option<usize> {
+    parent_indices(entries)
+        .iter()
+        .position(|parent| *parent == Some(idx))
+}
+
+/// The index of the closest sibling before (or after) the entry at `idx` in the flat list. Only entries pinned as the
+/// entry is count, since pinned entries are shown apart from the others.
+pub fn sibling(entries: &[Entry], idx: usize, forward: bool) -> Option<usize> {
+    let parents = parent_indices(entries);
+    let is_sibling = |other: &usize| {
+        parents[*other] == parents[idx] && entries[*other].pinned == entries[idx].pinned
+    };
+    if forward {
+        (idx + 1..entries.len()).find(is_sibling)
+    } else {
+        (0..idx).rev().find(is_sibling)
+    }
+}
+
+/// the first and last positions in the flat list among the siblings of the entry at `idx` pinned as it is
+pub fn sibling_bounds(entries: &[Entry], idx: usize) -> (usize, usize) {
+    let parents = parent_indices(entries);
+    let mut siblings = (0..entries.len()).filter(|other| {
+        parents[*other] == parents[idx] && entries[*other].pinned == entries[idx].pinned
+    });
+    let first = siblings.clone().next().unwrap_or(idx);
+    let last = siblings.next_back().unwrap_or(idx);
+    (first, last)
+}
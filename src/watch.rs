@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use scraper::Html;
+
+use crate::net::Net;
+use crate::reading;
+use crate::target::fnv1a;
+
+const WATCH_TIMEOUT: Duration = Duration::from_millis(10000);
+const WATCH_UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/117.0.0.0 Safari/537.36";
+
+/// A hash of the readable text of the page at `url`, so that changes to scripts, ads, or markup don't count as changes
+/// to the page. Pages that can't be fetched have no hash.
+pub fn content_hash(url: &str, net: &Net) -> Option<u64> {
+    let client = net.client(WATCH_TIMEOUT, WATCH_UA).ok()?;
+    let body = net
+        .get(&client, url)
+        .ok()?
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.text())
+        .ok()?;
+    let document = Html::parse_document(&body);
+    let text = reading::readable_blocks(&document)
+        .iter()
+        .flat_map(|block| block.text())
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(fnv1a(text.as_bytes()))
+}
@@ -0,0 +1,65 @@
+use std::path::Path;
+use std::time::Duration;
+
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+
+use crate::config::{Entry, WebhookConfig};
+use crate::events::{EventKind, ProjectEvent};
+use crate::net::Net;
+
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+const WEBHOOK_UA: &str = concat!("flist/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Serialize)]
+struct Payload<'a> {
+    event: EventKind,
+    project: &'a str,
+    entry: &'a Entry,
+}
+
+/// POSTs every event as JSON to the webhooks subscribed to it. Failed deliveries are dropped, an unreachable
+/// webhook shouldn't get in the way of using the project.
+pub fn deliver(
+    webhooks: &[WebhookConfig],
+    project_root: &Path,
+    events: &[ProjectEvent],
+    net: &Net,
+) {
+    if webhooks.is_empty() || events.is_empty() {
+        return;
+    }
+    let project = project_root
+        .canonicalize()
+        .unwrap_or_else(|_| project_root.to_path_buf())
+        .display()
+        .to_string();
+    let Ok(client) = net.client(Duration::from_secs(WEBHOOK_TIMEOUT_SECS), WEBHOOK_UA) else {
+        return;
+    };
+    // urls kept in the keyring are read once for all the events
+    let webhooks = webhooks
+        .iter()
+        .filter_map(|webhook| Some((webhook, webhook.url.resolve().ok()?)))
+        .collect::<Vec<_>>();
+    for event in events {
+        let payload = Payload {
+            event: event.kind,
+            project: &project,
+            entry: &event.entry,
+        };
+        let body = serde_json::to_vec(&payload).expect("Failed to serialize webhook payload");
+        for (_, url) in webhooks
+            .iter()
+            .filter(|(webhook, _)| webhook.wants(event.kind))
+        {
+            let Ok(request) = net.post(&client, url) else {
+                continue;
+            };
+            let _ = request
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.clone())
+                .send();
+        }
+    }
+}
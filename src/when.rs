@@ -0,0 +1,45 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+/// Parses a user-provided point in time, either relative to `now` ("30m", "2h", "3d", "1w", "tomorrow") or absolute
+/// in local time ("2023-10-01", "2023-10-01 18:30"). Dates without a time refer to the start of that day.
+pub fn parse_when(input: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    if input == "tomorrow" {
+        let tomorrow = now.with_timezone(&Local).date_naive().succ_opt()?;
+        return local_to_utc(tomorrow.and_time(NaiveTime::MIN));
+    }
+    if let Some(duration) = parse_duration(input) {
+        // far enough ahead, the time is past what can be represented, which is no time at all
+        return now.checked_add_signed(duration);
+    }
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M") {
+        return local_to_utc(datetime);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return local_to_utc(date.and_time(NaiveTime::MIN));
+    }
+    None
+}
+
+fn parse_duration(input: &str) -> Option<Duration> {
+    let unit_start = input.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = input.split_at(unit_start);
+    let amount: i64 = amount.parse().ok()?;
+    match unit {
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
+fn local_to_utc(datetime: NaiveDateTime) -> Option<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&datetime)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}